@@ -0,0 +1,276 @@
+//! Build context packaging for `build_image`
+//!
+//! The context directory is walked, filtered through its `.dockerignore` and written
+//! into an uncompressed tar that is uploaded to the daemon as the build context.
+
+use std::path::Path;
+
+use crate::tar::TarBuilder;
+
+/// Name of the ignore file read from the root of the context
+pub const DOCKERIGNORE_FILE: &str = ".dockerignore";
+
+struct IgnorePattern {
+    segments: Vec<String>,
+    negate: bool,
+}
+
+/// Parsed `.dockerignore`, following Docker's rules: `#` comments, `*`, `?`, `[...]`
+/// and `**` globs, `!` to re-include, and the last matching pattern wins. A pattern
+/// that matches a directory also excludes everything inside it.
+pub struct DockerIgnore {
+    patterns: Vec<IgnorePattern>,
+}
+
+impl DockerIgnore {
+    pub fn parse(contents: &str) -> Self {
+        let patterns = contents
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let line = line.trim();
+                let (negate, pattern) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest.trim()),
+                    None => (false, line),
+                };
+                let segments: Vec<String> = pattern
+                    .split('/')
+                    .filter(|s| !s.is_empty() && *s != ".")
+                    .map(str::to_string)
+                    .collect();
+                (!segments.is_empty()).then_some(IgnorePattern { segments, negate })
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    /// Whether any pattern re-includes paths, in which case ignored directories still
+    /// have to be walked
+    pub fn has_negations(&self) -> bool {
+        self.patterns.iter().any(|p| p.negate)
+    }
+
+    /// Whether `path` (relative to the context, `/`-separated) is excluded
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let mut ignored = false;
+        for pattern in &self.patterns {
+            let matched =
+                (1..=segments.len()).any(|len| match_segments(&pattern.segments, &segments[..len]));
+            if matched {
+                ignored = !pattern.negate;
+            }
+        }
+        ignored
+    }
+}
+
+/// Match path segments against pattern segments, where a `**` segment spans any
+/// number of path segments
+fn match_segments(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| match_segments(rest, &path[skip..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path_rest)) => {
+                let pattern_chars: Vec<char> = first.chars().collect();
+                let segment_chars: Vec<char> = segment.chars().collect();
+                match_glob(&pattern_chars, &segment_chars) && match_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Match a single path segment against a glob: `*`, `?`, `[...]` classes (with `^`
+/// or `!` negation and ranges) and `\` escapes
+fn match_glob(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', rest)) => (0..=text.len()).any(|skip| match_glob(rest, &text[skip..])),
+        Some(('?', rest)) => !text.is_empty() && match_glob(rest, &text[1..]),
+        Some(('[', rest)) => {
+            let Some((&c, text_rest)) = text.split_first() else {
+                return false;
+            };
+            let Some(close) = rest.iter().skip(1).position(|&ch| ch == ']').map(|i| i + 1) else {
+                // Unterminated class: treat '[' literally
+                return c == '[' && match_glob(rest, text_rest);
+            };
+            let (class, after) = (&rest[..close], &rest[close + 1..]);
+            let (negated, class) = match class.split_first() {
+                Some(('^' | '!', inner)) => (true, inner),
+                _ => (false, class),
+            };
+            let mut in_class = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    in_class |= class[i] <= c && c <= class[i + 2];
+                    i += 3;
+                } else {
+                    in_class |= class[i] == c;
+                    i += 1;
+                }
+            }
+            in_class != negated && match_glob(after, text_rest)
+        }
+        Some(('\\', rest)) if !rest.is_empty() => {
+            text.first() == Some(&rest[0]) && match_glob(&rest[1..], &text[1..])
+        }
+        Some((&p, rest)) => text.first() == Some(&p) && match_glob(rest, &text[1..]),
+    }
+}
+
+fn mtime_secs(metadata: &std::fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs())
+}
+
+fn file_mode(metadata: &std::fs::Metadata) -> u32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode()
+    }
+    #[cfg(not(unix))]
+    {
+        if metadata.is_dir() {
+            0o755
+        } else {
+            0o644
+        }
+    }
+}
+
+/// Tar up the build context in `dir`, skipping what `.dockerignore` excludes. The
+/// Dockerfile and `.dockerignore` itself are always sent, as the daemon needs them.
+pub fn build_context_tar(dir: &Path, dockerfile: &str) -> Result<Vec<u8>, String> {
+    let ignore = match std::fs::read_to_string(dir.join(DOCKERIGNORE_FILE)) {
+        Ok(contents) => DockerIgnore::parse(&contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => DockerIgnore::parse(""),
+        Err(e) => return Err(format!("Failed to read {}: {}", DOCKERIGNORE_FILE, e)),
+    };
+    let dockerfile = dockerfile.trim_start_matches("./");
+    let always_include = [dockerfile, DOCKERIGNORE_FILE];
+
+    let mut tar = TarBuilder::new();
+    let mut pending = vec![String::new()];
+    while let Some(rel_dir) = pending.pop() {
+        let abs_dir = dir.join(&rel_dir);
+        let mut entries: Vec<_> = std::fs::read_dir(&abs_dir)
+            .map_err(|e| format!("Failed to read {}: {}", abs_dir.display(), e))?
+            .filter_map(Result::ok)
+            .collect();
+        entries.sort_by_key(|e| e.file_name());
+
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let rel = if rel_dir.is_empty() {
+                name
+            } else {
+                format!("{}/{}", rel_dir, name)
+            };
+            let metadata = std::fs::symlink_metadata(entry.path())
+                .map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+            let ignored = ignore.is_ignored(&rel) && !always_include.contains(&rel.as_str());
+
+            if metadata.is_dir() {
+                if !ignored {
+                    tar.append_dir(&rel, file_mode(&metadata), mtime_secs(&metadata))?;
+                }
+                // A negated pattern may re-include something inside an ignored directory,
+                // and the Dockerfile is sent even from inside one
+                let holds_dockerfile = dockerfile
+                    .strip_prefix(rel.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'));
+                if !ignored || ignore.has_negations() || holds_dockerfile {
+                    pending.push(rel);
+                }
+            } else if ignored {
+                continue;
+            } else if metadata.file_type().is_symlink() {
+                let target = std::fs::read_link(entry.path())
+                    .map_err(|e| format!("Failed to read link {}: {}", rel, e))?;
+                tar.append_symlink(&rel, &target.to_string_lossy(), mtime_secs(&metadata))?;
+            } else {
+                let data = std::fs::read(entry.path())
+                    .map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+                tar.append_file(&rel, &data, file_mode(&metadata), mtime_secs(&metadata))?;
+            }
+        }
+    }
+    Ok(tar.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dockerignore_excludes_directories_and_their_contents() {
+        let ignore = DockerIgnore::parse("node_modules\n.git\n");
+        assert!(ignore.is_ignored("node_modules"));
+        assert!(ignore.is_ignored("node_modules/react/index.js"));
+        assert!(ignore.is_ignored(".git/HEAD"));
+        assert!(!ignore.is_ignored("src/main.rs"));
+        // Patterns are anchored at the context root
+        assert!(!ignore.is_ignored("packages/app/node_modules/x.js"));
+    }
+
+    #[test]
+    fn dockerignore_supports_wildcards_and_double_star() {
+        let ignore = DockerIgnore::parse("# build output\n*.log\n**/*.tmp\ndist/??.js\n");
+        assert!(ignore.is_ignored("debug.log"));
+        assert!(!ignore.is_ignored("logs/debug.log"));
+        assert!(ignore.is_ignored("a/b/c/file.tmp"));
+        assert!(ignore.is_ignored("file.tmp"));
+        assert!(ignore.is_ignored("dist/ab.js"));
+        assert!(!ignore.is_ignored("dist/abc.js"));
+    }
+
+    #[test]
+    fn dockerignore_negation_reincludes_later_matches() {
+        let ignore = DockerIgnore::parse("*.md\n!README.md\n");
+        assert!(ignore.is_ignored("CHANGELOG.md"));
+        assert!(!ignore.is_ignored("README.md"));
+        assert!(ignore.has_negations());
+
+        // The last matching pattern wins
+        let ignore = DockerIgnore::parse("!README.md\n*.md\n");
+        assert!(ignore.is_ignored("README.md"));
+    }
+
+    #[test]
+    fn build_context_includes_dockerfile_inside_ignored_directory() {
+        let dir = std::env::temp_dir().join("opentainer-build-context-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("docker")).unwrap();
+        std::fs::write(dir.join(DOCKERIGNORE_FILE), "docker\n").unwrap();
+        std::fs::write(dir.join("docker/Dockerfile"), "FROM scratch\n").unwrap();
+        std::fs::write(dir.join("docker/secret.env"), "TOKEN=1\n").unwrap();
+
+        let archive = build_context_tar(&dir, "./docker/Dockerfile").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        let contains = |needle: &[u8]| archive.windows(needle.len()).any(|w| w == needle);
+        assert!(contains(b"docker/Dockerfile"));
+        assert!(contains(b"FROM scratch"));
+        assert!(!contains(b"secret.env"));
+        assert!(!contains(b"TOKEN=1"));
+    }
+
+    #[test]
+    fn dockerignore_handles_classes_and_leading_slash() {
+        let ignore = DockerIgnore::parse("/tmp\nfile[0-9].txt\ncache[!a]\n");
+        assert!(ignore.is_ignored("tmp/x"));
+        assert!(ignore.is_ignored("file7.txt"));
+        assert!(!ignore.is_ignored("filex.txt"));
+        assert!(ignore.is_ignored("cacheb"));
+        assert!(!ignore.is_ignored("cachea"));
+    }
+}
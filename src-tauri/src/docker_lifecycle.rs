@@ -1,5 +1,6 @@
 use tokio::process::Command;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
 use std::time::Duration;
 use tokio::time::sleep;
 
@@ -10,6 +11,24 @@ static WE_STARTED_DOCKER: AtomicBool = AtomicBool::new(false);
 /// Flag to prevent concurrent starts
 static START_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
 
+/// Bumped by cancel_wait_for_docker(); a wait ends once it moves past the value the
+/// wait counts from, so every wait in progress sees the same cancel
+static WAIT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Generation at the start of the current start_docker_runtime() call, so a cancel
+/// during the start phase also ends the wait that follows it. Cleared once a wait ends,
+/// or when the start returns without one following.
+static START_WAIT_GENERATION: Mutex<Option<u64>> = Mutex::new(None);
+
+/// How often a wait checks for cancellation between readiness polls
+const WAIT_CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Colima profile we started, so quitting stops the same VM
+static STARTED_PROFILE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Colima's profile when none is given (`colima start` without `--profile`)
+pub const DEFAULT_COLIMA_PROFILE: &str = "default";
+
 /// Docker lifecycle management for Opentainer
 ///
 /// Strategy:
@@ -21,18 +40,218 @@ static START_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct DockerStatus {
     pub running: bool,
+    /// Which backend is serving the Docker API (see `classify_provider`), "none" if not running
+    pub provider: String,
     pub colima_installed: bool,
     pub we_started: bool,
     pub error: Option<String>,
 }
 
+/// Resources allocated to the Colima VM when we start it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VmResources {
+    pub cpu: u32,
+    pub memory_gb: u32,
+    pub disk_gb: u32,
+}
+
+impl Default for VmResources {
+    fn default() -> Self {
+        Self {
+            cpu: 2,
+            memory_gb: 4,
+            disk_gb: 60,
+        }
+    }
+}
+
+/// Upper bound for the VM disk size; Colima allocates it lazily but 2 TB is plenty
+const MAX_DISK_GB: u32 = 2048;
+
+impl VmResources {
+    /// Fill in the defaults for anything the caller didn't ask for. Only requested
+    /// values are checked against the host: the defaults are Colima's own and must
+    /// keep working on hosts smaller than them. `host_memory_gb` may be unknown, in
+    /// which case only the lower bound is enforced for memory.
+    pub fn with_overrides(
+        cpu: Option<u32>,
+        memory_gb: Option<u32>,
+        disk_gb: Option<u32>,
+        host_cpus: u32,
+        host_memory_gb: Option<u32>,
+    ) -> Result<Self, String> {
+        let defaults = Self::default();
+        if let Some(cpu) = cpu {
+            check_cpu(cpu, host_cpus)?;
+        }
+        if let Some(memory_gb) = memory_gb {
+            check_memory(memory_gb, host_memory_gb)?;
+        }
+        if let Some(disk_gb) = disk_gb {
+            check_disk(disk_gb)?;
+        }
+        Ok(Self {
+            cpu: cpu.unwrap_or(defaults.cpu),
+            memory_gb: memory_gb.unwrap_or(defaults.memory_gb),
+            disk_gb: disk_gb.unwrap_or(defaults.disk_gb),
+        })
+    }
+}
+
+fn check_cpu(cpu: u32, host_cpus: u32) -> Result<(), String> {
+    if !(1..=host_cpus).contains(&cpu) {
+        return Err(format!("CPU count must be between 1 and {}", host_cpus));
+    }
+    Ok(())
+}
+
+fn check_memory(memory_gb: u32, host_memory_gb: Option<u32>) -> Result<(), String> {
+    if memory_gb < 1 {
+        return Err("Memory must be at least 1 GB".to_string());
+    }
+    if let Some(max) = host_memory_gb {
+        if memory_gb > max {
+            return Err(format!("Memory must be between 1 and {} GB", max));
+        }
+    }
+    Ok(())
+}
+
+fn check_disk(disk_gb: u32) -> Result<(), String> {
+    if !(1..=MAX_DISK_GB).contains(&disk_gb) {
+        return Err(format!(
+            "Disk size must be between 1 and {} GB",
+            MAX_DISK_GB
+        ));
+    }
+    Ok(())
+}
+
+/// Validate a Colima profile name before it is passed to `colima --profile`
+/// or used as a directory under `~/.colima`.
+pub fn validate_colima_profile(profile: &str) -> Result<(), String> {
+    if profile.is_empty() {
+        return Err("Colima profile cannot be empty".to_string());
+    }
+    if profile.len() > 64 {
+        return Err("Colima profile name too long".to_string());
+    }
+    let mut chars = profile.chars();
+    let first_ok = chars.next().is_some_and(|c| c.is_ascii_alphanumeric());
+    if !first_ok || !chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Invalid Colima profile: {}", profile));
+    }
+    Ok(())
+}
+
+/// Docker socket of a Colima profile (`~/.colima/<profile>/docker.sock`)
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn colima_socket_path(home: &str, profile: &str) -> String {
+    format!("{home}/.colima/{profile}/docker.sock")
+}
+
+/// The profile we started, or the default one
+pub fn active_colima_profile() -> String {
+    STARTED_PROFILE
+        .lock()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| DEFAULT_COLIMA_PROFILE.to_string())
+}
+
+/// Callback receiving human-readable progress lines while a runtime starts
+pub type ProgressFn = std::sync::Arc<dyn Fn(String) + Send + Sync>;
+
+/// Turn a line of `colima start` output into a progress message.
+/// Colima logs in logfmt (`time=... level=info msg="starting colima"`); the `msg`
+/// value is extracted when present, otherwise the trimmed line is returned.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn colima_progress_message(line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let Some(start) = line.find("msg=") else {
+        return Some(line.to_string());
+    };
+    let rest = &line[start + 4..];
+    let msg = match rest.strip_prefix('"') {
+        Some(quoted) => {
+            let mut msg = String::new();
+            let mut chars = quoted.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => msg.extend(chars.next()),
+                    '"' => break,
+                    c => msg.push(c),
+                }
+            }
+            msg
+        }
+        None => rest
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    };
+    (!msg.is_empty()).then_some(msg)
+}
+
+/// Number of logical CPUs on the host
+pub fn host_cpu_count() -> u32 {
+    std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(1)
+}
+
+/// Total physical memory on the host in whole GB, if it can be determined
+pub fn host_memory_gb() -> Option<u32> {
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("sysctl")
+            .args(["-n", "hw.memsize"])
+            .output()
+            .ok()?;
+        let bytes: u64 = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .ok()?;
+        Some((bytes / (1024 * 1024 * 1024)) as u32)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+        let kb: u64 = meminfo
+            .lines()
+            .find(|l| l.starts_with("MemTotal:"))?
+            .split_whitespace()
+            .nth(1)?
+            .parse()
+            .ok()?;
+        Some((kb / (1024 * 1024)) as u32)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        None
+    }
+}
+
 /// Check if Docker daemon is currently running by attempting to connect
-/// Tries multiple socket paths including Colima's custom socket
-pub async fn check_docker_running() -> bool {
+/// Tries multiple socket paths including the Colima profile's socket
+pub async fn check_docker_running(profile: &str) -> bool {
+    find_running_docker(profile).await.is_some()
+}
+
+/// Find a responsive Docker daemon.
+/// Returns the client and the socket path it answered on (symlinks resolved, if known).
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+async fn find_running_docker(profile: &str) -> Option<(bollard::Docker, Option<String>)> {
     // First try the default connection (respects DOCKER_HOST env var)
     if let Ok(docker) = bollard::Docker::connect_with_local_defaults() {
         if docker.ping().await.is_ok() {
-            return true;
+            return Some((docker, default_socket_path()));
         }
     }
 
@@ -40,20 +259,140 @@ pub async fn check_docker_running() -> bool {
     #[cfg(target_os = "macos")]
     {
         if let Ok(home) = std::env::var("HOME") {
-            let colima_socket = format!("unix://{home}/.colima/default/docker.sock");
+            let colima_socket = format!("unix://{}", colima_socket_path(&home, profile));
             if let Ok(docker) = bollard::Docker::connect_with_socket(
                 &colima_socket,
                 120,
                 bollard::API_DEFAULT_VERSION,
             ) {
                 if docker.ping().await.is_ok() {
-                    return true;
+                    return Some((docker, Some(colima_socket)));
                 }
             }
         }
     }
 
-    false
+    // On Windows the local default is `//./pipe/docker_engine`; newer Docker Desktop
+    // releases serve the Linux engine on their own pipe as well
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(docker) = bollard::Docker::connect_with_named_pipe(
+            DOCKER_DESKTOP_LINUX_PIPE,
+            120,
+            bollard::API_DEFAULT_VERSION,
+        ) {
+            if docker.ping().await.is_ok() {
+                return Some((docker, Some(DOCKER_DESKTOP_LINUX_PIPE.to_string())));
+            }
+        }
+    }
+
+    // Podman users typically have no Docker socket at all
+    for socket in podman_socket_candidates() {
+        if let Ok(docker) =
+            bollard::Docker::connect_with_socket(&socket, 120, bollard::API_DEFAULT_VERSION)
+        {
+            if docker.ping().await.is_ok() {
+                return Some((docker, Some(socket)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Socket the default connection uses. On macOS `/var/run/docker.sock` is usually a
+/// symlink into the provider's own directory, so resolve it to tell providers apart.
+fn default_socket_path() -> Option<String> {
+    let path = match std::env::var("DOCKER_HOST") {
+        Ok(host) => host.strip_prefix("unix://")?.to_string(),
+        Err(_) => "/var/run/docker.sock".to_string(),
+    };
+    let resolved = std::fs::canonicalize(&path).ok()?;
+    Some(resolved.to_string_lossy().to_string())
+}
+
+/// Named pipe Docker Desktop exposes for its Linux engine on Windows
+#[cfg(target_os = "windows")]
+const DOCKER_DESKTOP_LINUX_PIPE: &str = "npipe:////./pipe/dockerDesktopLinuxEngine";
+
+/// Path to the Docker Desktop executable, if it is installed in the standard location
+#[cfg(target_os = "windows")]
+fn docker_desktop_exe() -> Option<std::path::PathBuf> {
+    let program_files =
+        std::env::var("ProgramFiles").unwrap_or_else(|_| "C:\\Program Files".to_string());
+    let exe = std::path::Path::new(&program_files)
+        .join("Docker")
+        .join("Docker")
+        .join("Docker Desktop.exe");
+    exe.exists().then_some(exe)
+}
+
+/// Name the Docker provider from the daemon's reported OS/host name and the socket path.
+/// Returns one of "orbstack", "colima", "podman", "docker-desktop" or "docker-engine".
+pub fn classify_provider(operating_system: &str, name: &str, socket: Option<&str>) -> String {
+    let socket = socket.unwrap_or_default();
+    let provider = if operating_system.contains("OrbStack") || socket.contains("/.orbstack/") {
+        "orbstack"
+    } else if name.starts_with("colima") || socket.contains("/.colima/") {
+        "colima"
+    } else if socket.contains("podman") {
+        "podman"
+    } else if operating_system.contains("Docker Desktop")
+        || socket.contains("/.docker/run/")
+        || socket.contains("dockerDesktop")
+    {
+        "docker-desktop"
+    } else {
+        "docker-engine"
+    };
+    provider.to_string()
+}
+
+/// Detect which provider is running, or "none" if no daemon responds
+pub async fn detect_provider(profile: &str) -> String {
+    let Some((docker, socket)) = find_running_docker(profile).await else {
+        return "none".to_string();
+    };
+
+    match docker.info().await {
+        Ok(info) => classify_provider(
+            info.operating_system.as_deref().unwrap_or_default(),
+            info.name.as_deref().unwrap_or_default(),
+            socket.as_deref(),
+        ),
+        Err(_) => classify_provider("", "", socket.as_deref()),
+    }
+}
+
+/// Candidate Podman API socket paths for the current platform.
+/// Podman serves a Docker-compatible API, so these can be used with bollard directly.
+pub fn podman_socket_candidates() -> Vec<String> {
+    #[allow(unused_mut)]
+    let mut candidates = Vec::new();
+
+    #[cfg(target_os = "linux")]
+    {
+        // Rootless service first, then the system-wide rootful socket
+        if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+            candidates.push(format!("{runtime_dir}/podman/podman.sock"));
+        }
+        candidates.push("/run/podman/podman.sock".to_string());
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // podman machine exposes its API socket under the user's data dir;
+        // the exact subdirectory depends on the VM provider and Podman version
+        if let Ok(home) = std::env::var("HOME") {
+            let machine_dir = format!("{home}/.local/share/containers/podman/machine");
+            candidates.push(format!("{machine_dir}/podman.sock"));
+            candidates.push(format!("{machine_dir}/applehv/podman.sock"));
+            candidates.push(format!("{machine_dir}/qemu/podman.sock"));
+        }
+    }
+
+    candidates
 }
 
 /// Check if Colima is installed on the system (macOS)
@@ -81,15 +420,129 @@ pub async fn check_colima_installed() -> bool {
 
     #[cfg(target_os = "windows")]
     {
-        // Windows - check for Docker in WSL or native
-        false // TODO: Implement Windows support
+        // On Windows, check for Docker Desktop (installed binary or its service)
+        if docker_desktop_exe().is_some() {
+            return true;
+        }
+        Command::new("sc")
+            .args(["query", "com.docker.service"])
+            .output()
+            .await
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+}
+
+/// A Colima VM's state and the resources allocated to it
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct ColimaStatus {
+    pub running: bool,
+    pub cpus: Option<u64>,
+    /// Bytes
+    pub memory: Option<u64>,
+    /// Bytes
+    pub disk: Option<u64>,
+    pub arch: Option<String>,
+    /// Container runtime inside the VM, e.g. "docker" or "containerd"
+    pub runtime: Option<String>,
+}
+
+impl ColimaStatus {
+    /// Read the fields `colima status --json` and `colima list --json` share.
+    /// The CPU count is `cpu` in the former and `cpus` in the latter.
+    fn from_json(value: &serde_json::Value, running: bool) -> Self {
+        let number = |key: &str| value.get(key).and_then(serde_json::Value::as_u64);
+        let text = |key: &str| {
+            value
+                .get(key)
+                .and_then(serde_json::Value::as_str)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            running,
+            cpus: number("cpus").or_else(|| number("cpu")),
+            memory: number("memory"),
+            disk: number("disk"),
+            arch: text("arch"),
+            runtime: text("runtime"),
+        }
+    }
+}
+
+/// Parse `colima status --json`, which only succeeds for a running VM
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn parse_colima_status_json(output: &str) -> Option<ColimaStatus> {
+    let value: serde_json::Value = serde_json::from_str(output.trim()).ok()?;
+    value
+        .is_object()
+        .then(|| ColimaStatus::from_json(&value, true))
+}
+
+/// Find `profile` in `colima list --json`, which prints one JSON object per line
+/// and also covers stopped VMs
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn parse_colima_list_json(output: &str, profile: &str) -> Option<ColimaStatus> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line.trim()).ok())
+        .find(|value| value.get("name").and_then(serde_json::Value::as_str) == Some(profile))
+        .map(|value| {
+            let running = value
+                .get("status")
+                .and_then(serde_json::Value::as_str)
+                .is_some_and(|status| status.eq_ignore_ascii_case("running"));
+            ColimaStatus::from_json(&value, running)
+        })
+}
+
+/// Get the state and allocated resources of a Colima profile (macOS only)
+pub async fn get_colima_status(profile: &str) -> Result<ColimaStatus, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let output = Command::new("colima")
+            .args(["status", "--profile", profile, "--json"])
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run colima status: {}", e))?;
+        if output.status.success() {
+            if let Some(status) = parse_colima_status_json(&String::from_utf8_lossy(&output.stdout))
+            {
+                return Ok(status);
+            }
+        }
+
+        // Stopped VMs (and Colima versions without `status --json`) only show up in the list
+        let output = Command::new("colima")
+            .args(["list", "--json"])
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run colima list: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Failed to list Colima profiles: {}", stderr.trim()));
+        }
+        parse_colima_list_json(&String::from_utf8_lossy(&output.stdout), profile)
+            .ok_or_else(|| format!("Colima profile not found: {}", profile))
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = profile;
+        Err("Colima is only supported on macOS".to_string())
     }
 }
 
 /// Start Docker runtime (Colima on macOS, systemd on Linux)
 /// Note: This spawns the process and returns immediately.
 /// Use wait_for_docker_ready() to wait for Docker to be responsive.
-pub async fn start_docker_runtime() -> Result<(), String> {
+/// `resources` and `profile` only apply to Colima; systemd-managed Docker runs on the host directly.
+/// Colima's output keeps streaming to `on_progress` after this returns.
+pub async fn start_docker_runtime(
+    resources: VmResources,
+    profile: &str,
+    on_progress: ProgressFn,
+) -> Result<(), String> {
     // Prevent concurrent starts
     if START_IN_PROGRESS
         .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
@@ -98,58 +551,105 @@ pub async fn start_docker_runtime() -> Result<(), String> {
         log::info!("Start already in progress, skipping duplicate call");
         return Ok(());
     }
+    begin_start_phase();
 
-    // If we already started before, just return
+    // If we already started this profile, just return. Only one Colima profile is
+    // tracked for stopping on quit, so a different one can't be started alongside it.
     if WE_STARTED_DOCKER.load(Ordering::SeqCst) {
-        START_IN_PROGRESS.store(false, Ordering::SeqCst);
-        return Ok(());
+        end_start_phase_without_wait();
+        let active = active_colima_profile();
+        if !cfg!(target_os = "macos") || active == profile {
+            return Ok(());
+        }
+        return Err(format!(
+            "Colima profile {} started by Opentainer is still running, stop it before starting {}",
+            active, profile
+        ));
     }
 
     #[cfg(target_os = "macos")]
     {
         // First check if already running
-        let status_output = Command::new("colima").arg("status").output().await;
+        let status_output = Command::new("colima")
+            .args(["status", "--profile", profile])
+            .output()
+            .await;
 
         if let Ok(output) = status_output {
             if output.status.success() {
                 // Already running - someone else started it
-                START_IN_PROGRESS.store(false, Ordering::SeqCst);
+                end_start_phase_without_wait();
                 return Ok(());
             }
         }
 
         // Spawn Colima in the background - don't wait for it
-        // colima start can take several minutes on first run (downloads VM image)
-        let child = Command::new("colima")
-            .args(["start", "--cpu", "2", "--memory", "4", "--disk", "60"])
+        // colima start can take several minutes on first run (downloads VM image),
+        // so its output is piped through to the UI instead of being discarded
+        let mut child = Command::new("colima")
+            .args([
+                "start".to_string(),
+                "--profile".to_string(),
+                profile.to_string(),
+                "--cpu".to_string(),
+                resources.cpu.to_string(),
+                "--memory".to_string(),
+                resources.memory_gb.to_string(),
+                "--disk".to_string(),
+                resources.disk_gb.to_string(),
+            ])
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
             .spawn()
             .map_err(|e| {
-                START_IN_PROGRESS.store(false, Ordering::SeqCst);
+                end_start_phase_without_wait();
                 format!("Failed to start Colima: {}", e)
             })?;
 
+        if let Some(stdout) = child.stdout.take() {
+            tokio::spawn(forward_progress(stdout, on_progress.clone()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(forward_progress(stderr, on_progress));
+        }
+
         // Mark that we started Docker
         WE_STARTED_DOCKER.store(true, Ordering::SeqCst);
+        *STARTED_PROFILE.lock().unwrap() = Some(profile.to_string());
 
         log::info!(
-            "Colima start spawned with PID: {:?}, WE_STARTED_DOCKER=true",
+            "Colima start spawned for profile {} with PID: {:?}, WE_STARTED_DOCKER=true",
+            profile,
             child.id()
         );
 
+        // Reap the process once it exits so it doesn't linger as a zombie
+        tokio::spawn(async move {
+            match child.wait().await {
+                Ok(status) => log::info!("colima start exited with {}", status),
+                Err(e) => log::warn!("Failed to wait for colima start: {}", e),
+            }
+        });
+
         START_IN_PROGRESS.store(false, Ordering::SeqCst);
         Ok(())
     }
 
     #[cfg(target_os = "linux")]
     {
+        let _ = (resources, profile, on_progress);
         // On Linux, try to start via systemctl (may require sudo)
         let output = Command::new("systemctl")
             .args(["start", "docker"])
             .output()
             .await
-            .map_err(|e| format!("Failed to start Docker: {}", e))?;
+            .map_err(|e| {
+                end_start_phase_without_wait();
+                format!("Failed to start Docker: {}", e)
+            })?;
 
         if !output.status.success() {
+            end_start_phase_without_wait();
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(format!("Failed to start Docker: {}", stderr));
         }
@@ -160,11 +660,42 @@ pub async fn start_docker_runtime() -> Result<(), String> {
 
     #[cfg(target_os = "windows")]
     {
-        Err("Windows support not yet implemented".to_string())
+        let _ = (resources, profile, on_progress);
+
+        let Some(exe) = docker_desktop_exe() else {
+            end_start_phase_without_wait();
+            return Err("Docker Desktop is not installed".to_string());
+        };
+
+        // Docker Desktop is a user-facing app with its own tray icon, so we launch it
+        // but leave WE_STARTED_DOCKER unset: quitting Opentainer shouldn't close it.
+        let child = Command::new(&exe).spawn().map_err(|e| {
+            end_start_phase_without_wait();
+            format!("Failed to start Docker Desktop: {}", e)
+        })?;
+
+        log::info!("Docker Desktop launched with PID: {:?}", child.id());
+
+        START_IN_PROGRESS.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Read a child's output line by line and pass progress messages on
+#[cfg(target_os = "macos")]
+async fn forward_progress(output: impl tokio::io::AsyncRead + Unpin, on_progress: ProgressFn) {
+    use tokio::io::AsyncBufReadExt;
+
+    let mut lines = tokio::io::BufReader::new(output).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if let Some(message) = colima_progress_message(&line) {
+            on_progress(message);
+        }
     }
 }
 
-/// Stop Docker runtime (only if we started it)
+/// Stop Docker runtime (only if we started it).
+/// On macOS this stops the Colima profile recorded by `start_docker_runtime`.
 pub async fn stop_docker_runtime() -> Result<(), String> {
     // Only stop if we started it
     if !WE_STARTED_DOCKER.load(Ordering::SeqCst) {
@@ -173,8 +704,9 @@ pub async fn stop_docker_runtime() -> Result<(), String> {
 
     #[cfg(target_os = "macos")]
     {
+        let profile = active_colima_profile();
         let output = Command::new("colima")
-            .arg("stop")
+            .args(["stop", "--profile", &profile])
             .output()
             .await
             .map_err(|e| format!("Failed to stop Colima: {}", e))?;
@@ -188,6 +720,7 @@ pub async fn stop_docker_runtime() -> Result<(), String> {
         }
 
         WE_STARTED_DOCKER.store(false, Ordering::SeqCst);
+        *STARTED_PROFILE.lock().unwrap() = None;
         Ok(())
     }
 
@@ -210,20 +743,56 @@ pub async fn stop_docker_runtime() -> Result<(), String> {
 
     #[cfg(target_os = "windows")]
     {
-        Err("Windows support not yet implemented".to_string())
+        // We never mark Docker Desktop as started by us, so there is nothing to stop
+        Ok(())
     }
 }
 
-/// Wait for Docker to be ready, with a timeout
-pub async fn wait_for_docker_ready(timeout_secs: u64) -> Result<(), String> {
+/// Open a start phase: cancels from here on also end the wait that follows it
+fn begin_start_phase() {
+    *START_WAIT_GENERATION.lock().unwrap() = Some(WAIT_GENERATION.load(Ordering::SeqCst));
+}
+
+/// Close a start phase that no wait follows (already running, or failed to start),
+/// so its generation doesn't carry over to an unrelated later wait
+fn end_start_phase_without_wait() {
+    START_IN_PROGRESS.store(false, Ordering::SeqCst);
+    *START_WAIT_GENERATION.lock().unwrap() = None;
+}
+
+/// Wait for Docker to be ready, with a timeout.
+/// Returns an error soon after cancel_wait_for_docker() is called, including a cancel
+/// made while the runtime was still starting.
+pub async fn wait_for_docker_ready(timeout_secs: u64, profile: &str) -> Result<(), String> {
+    // A cancel aimed at an earlier wait must not end this one
+    let since = START_WAIT_GENERATION
+        .lock()
+        .unwrap()
+        .unwrap_or_else(|| WAIT_GENERATION.load(Ordering::SeqCst));
+    let result = poll_docker_ready(timeout_secs, profile, since).await;
+    *START_WAIT_GENERATION.lock().unwrap() = None;
+    result
+}
+
+async fn poll_docker_ready(timeout_secs: u64, profile: &str, since: u64) -> Result<(), String> {
     let poll_interval = Duration::from_secs(2);
     let max_attempts = timeout_secs / 2;
+    let cancelled = || WAIT_GENERATION.load(Ordering::SeqCst) != since;
 
     for _ in 0..max_attempts {
-        if check_docker_running().await {
+        if cancelled() {
+            return Err("Waiting for Docker was cancelled".to_string());
+        }
+        if check_docker_running(profile).await {
             return Ok(());
         }
-        sleep(poll_interval).await;
+        let deadline = tokio::time::Instant::now() + poll_interval;
+        while tokio::time::Instant::now() < deadline {
+            if cancelled() {
+                return Err("Waiting for Docker was cancelled".to_string());
+            }
+            sleep(WAIT_CANCEL_CHECK_INTERVAL).await;
+        }
     }
 
     Err(format!(
@@ -232,14 +801,22 @@ pub async fn wait_for_docker_ready(timeout_secs: u64) -> Result<(), String> {
     ))
 }
 
+/// Stop every running wait_for_docker_ready(), e.g. when the user closes the
+/// "starting Docker" dialog. During a start it also ends the wait that follows.
+pub fn cancel_wait_for_docker() {
+    WAIT_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
 /// Get comprehensive Docker status
-pub async fn get_docker_status() -> DockerStatus {
-    let running = check_docker_running().await;
+pub async fn get_docker_status(profile: &str) -> DockerStatus {
+    let provider = detect_provider(profile).await;
+    let running = provider != "none";
     let colima_installed = check_colima_installed().await;
     let we_started = WE_STARTED_DOCKER.load(Ordering::SeqCst);
 
     DockerStatus {
         running,
+        provider,
         colima_installed,
         we_started,
         error: None,
@@ -251,21 +828,86 @@ pub fn did_we_start_docker() -> bool {
     WE_STARTED_DOCKER.load(Ordering::SeqCst)
 }
 
+/// Installation instructions, plus the Linux distribution they were tailored to
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct InstallInstructions {
+    /// Detected distribution, e.g. "Ubuntu 24.04 LTS"; None off Linux or when unknown
+    pub distro: Option<String>,
+    pub instructions: String,
+}
+
+/// Commands shared by every distro once the package is installed
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const LINUX_POST_INSTALL: &str = "sudo systemctl enable --now docker\nsudo usermod -aG docker $USER\n\nLog out and back in so the group change applies.";
+
+/// Pick install steps for the distribution described by /etc/os-release. Derivatives
+/// are matched through ID_LIKE, e.g. Linux Mint gets the Ubuntu steps.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn linux_install_instructions(os_release: &str) -> InstallInstructions {
+    let field = |key: &str| {
+        os_release.lines().find_map(|line| {
+            let value = line.trim().strip_prefix(key)?.strip_prefix('=')?;
+            Some(value.trim_matches(|c| c == '"' || c == '\'').to_string())
+        })
+    };
+    let mut ids: Vec<String> = field("ID").into_iter().collect();
+    if let Some(like) = field("ID_LIKE") {
+        ids.extend(like.split_whitespace().map(str::to_string));
+    }
+    let is = |names: &[&str]| ids.iter().any(|id| names.contains(&id.as_str()));
+
+    let steps = if is(&["ubuntu", "debian"]) {
+        Some("sudo apt-get update\nsudo apt-get install -y docker.io")
+    } else if is(&["fedora"]) {
+        Some("sudo dnf install -y moby-engine")
+    } else if is(&["arch"]) {
+        Some("sudo pacman -S --needed docker")
+    } else {
+        None
+    };
+    let distro = field("PRETTY_NAME").or_else(|| field("NAME"));
+
+    match steps {
+        Some(steps) => InstallInstructions {
+            instructions: format!(
+                "Install Docker Engine on {}:\n\n{}\n{}\n\nMore options: https://docs.docker.com/engine/install/",
+                distro.as_deref().unwrap_or("Linux"),
+                steps,
+                LINUX_POST_INSTALL
+            ),
+            distro,
+        },
+        None => InstallInstructions {
+            distro,
+            instructions: "Install Docker Engine:\n\nhttps://docs.docker.com/engine/install/".to_string(),
+        },
+    }
+}
+
 /// Get installation instructions for the current platform
-pub fn get_install_instructions() -> String {
+pub fn get_install_instructions() -> InstallInstructions {
     #[cfg(target_os = "macos")]
     {
-        "Install Colima and Docker CLI:\n\nbrew install colima docker\n\nOpentainer will manage Colima automatically.".to_string()
+        InstallInstructions {
+            distro: None,
+            instructions: "Install Colima and Docker CLI:\n\nbrew install colima docker\n\nOpentainer will manage Colima automatically.".to_string(),
+        }
     }
 
     #[cfg(target_os = "linux")]
     {
-        "Install Docker Engine:\n\nhttps://docs.docker.com/engine/install/".to_string()
+        let os_release = std::fs::read_to_string("/etc/os-release")
+            .or_else(|_| std::fs::read_to_string("/usr/lib/os-release"))
+            .unwrap_or_default();
+        linux_install_instructions(&os_release)
     }
 
     #[cfg(target_os = "windows")]
     {
-        "Windows support coming soon.".to_string()
+        InstallInstructions {
+            distro: None,
+            instructions: "Install Docker Desktop for Windows:\n\nhttps://docs.docker.com/desktop/setup/install/windows-install/\n\nOpentainer will launch Docker Desktop when it isn't running.".to_string(),
+        }
     }
 }
 
@@ -273,16 +915,290 @@ pub fn get_install_instructions() -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_colima_status_json() {
+        let output = r#"{"display_name":"colima","driver":"QEMU","arch":"aarch64","runtime":"docker","mount_type":"sshfs","kubernetes":false,"cpu":4,"memory":8589934592,"disk":107374182400}"#;
+        assert_eq!(
+            parse_colima_status_json(output),
+            Some(ColimaStatus {
+                running: true,
+                cpus: Some(4),
+                memory: Some(8589934592),
+                disk: Some(107374182400),
+                arch: Some("aarch64".to_string()),
+                runtime: Some("docker".to_string()),
+            })
+        );
+        assert_eq!(parse_colima_status_json("colima is not running"), None);
+    }
+
+    #[test]
+    fn test_parse_colima_list_json() {
+        let output = concat!(
+            r#"{"name":"default","status":"Running","arch":"aarch64","cpus":2,"memory":2147483648,"disk":64424509440,"runtime":"docker"}"#,
+            "\n",
+            r#"{"name":"work","status":"Stopped","arch":"x86_64","cpus":6,"memory":12884901888,"disk":0,"runtime":"containerd"}"#,
+            "\n",
+        );
+        let work = parse_colima_list_json(output, "work").unwrap();
+        assert!(!work.running);
+        assert_eq!(work.cpus, Some(6));
+        assert_eq!(work.runtime.as_deref(), Some("containerd"));
+        assert!(parse_colima_list_json(output, "default").unwrap().running);
+        assert_eq!(parse_colima_list_json(output, "missing"), None);
+    }
+
     #[tokio::test]
     async fn test_check_docker_running() {
         // This will depend on whether Docker is actually running
-        let running = check_docker_running().await;
+        let running = check_docker_running(DEFAULT_COLIMA_PROFILE).await;
         println!("Docker running: {}", running);
     }
 
+    /// The wait tests share the global cancel state, so they must not overlap
+    static WAIT_TESTS: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    /// Await a wait that should end by cancellation. Docker may really be running here,
+    /// in which case it succeeds instead.
+    async fn assert_cancelled(wait: tokio::task::JoinHandle<Result<(), String>>) {
+        let result = tokio::time::timeout(Duration::from_secs(30), wait)
+            .await
+            .expect("cancelled wait should return promptly")
+            .unwrap();
+        if let Err(e) = result {
+            assert!(e.contains("cancelled"), "{}", e);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_docker_ready_cancels_every_wait() {
+        let _guard = WAIT_TESTS.lock().await;
+        let first = tokio::spawn(wait_for_docker_ready(600, "opentainer-test-missing"));
+        let second = tokio::spawn(wait_for_docker_ready(600, "opentainer-test-missing"));
+        sleep(Duration::from_millis(50)).await;
+        cancel_wait_for_docker();
+        assert_cancelled(first).await;
+        assert_cancelled(second).await;
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_docker_ready_sees_cancel_from_start_phase() {
+        let _guard = WAIT_TESTS.lock().await;
+        begin_start_phase();
+        cancel_wait_for_docker();
+        let wait = tokio::spawn(wait_for_docker_ready(600, "opentainer-test-missing"));
+        assert_cancelled(wait).await;
+        // The cancel was used up, so it doesn't reach a later wait
+        assert!(START_WAIT_GENERATION.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_start_phase_without_wait_does_not_cancel_later_wait() {
+        let _guard = WAIT_TESTS.lock().await;
+        begin_start_phase();
+        cancel_wait_for_docker();
+        end_start_phase_without_wait();
+        assert!(START_WAIT_GENERATION.lock().unwrap().is_none());
+        // Ends by timing out (or success), not by the earlier cancel
+        if let Err(e) = wait_for_docker_ready(1, "opentainer-test-missing").await {
+            assert!(!e.contains("cancelled"), "{}", e);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_docker_ready_can_be_cancelled() {
+        let _guard = WAIT_TESTS.lock().await;
+        let wait = tokio::spawn(wait_for_docker_ready(600, "opentainer-test-missing"));
+        sleep(Duration::from_millis(50)).await;
+        cancel_wait_for_docker();
+        assert_cancelled(wait).await;
+    }
+
     #[tokio::test]
     async fn test_check_colima_installed() {
         let installed = check_colima_installed().await;
         println!("Colima installed: {}", installed);
     }
+
+    #[test]
+    fn test_linux_install_instructions_by_distro() {
+        let ubuntu = linux_install_instructions(
+            "NAME=\"Ubuntu\"\nPRETTY_NAME=\"Ubuntu 24.04 LTS\"\nID=ubuntu\nID_LIKE=debian\n",
+        );
+        assert_eq!(ubuntu.distro.as_deref(), Some("Ubuntu 24.04 LTS"));
+        assert!(ubuntu.instructions.contains("apt-get install"));
+
+        let mint = linux_install_instructions(
+            "PRETTY_NAME=\"Linux Mint 22\"\nID=linuxmint\nID_LIKE=\"ubuntu debian\"\n",
+        );
+        assert!(mint.instructions.contains("apt-get install"));
+
+        let fedora = linux_install_instructions("NAME=\"Fedora Linux\"\nID=fedora\n");
+        assert_eq!(fedora.distro.as_deref(), Some("Fedora Linux"));
+        assert!(fedora.instructions.contains("dnf install"));
+
+        let manjaro =
+            linux_install_instructions("NAME=\"Manjaro Linux\"\nID=manjaro\nID_LIKE=arch\n");
+        assert!(manjaro.instructions.contains("pacman -S"));
+    }
+
+    #[test]
+    fn test_linux_install_instructions_fallback() {
+        let unknown = linux_install_instructions("NAME=\"Void\"\nID=void\n");
+        assert_eq!(unknown.distro.as_deref(), Some("Void"));
+        assert!(unknown
+            .instructions
+            .contains("https://docs.docker.com/engine/install/"));
+        assert!(!unknown.instructions.contains("sudo"));
+
+        let empty = linux_install_instructions("");
+        assert_eq!(empty.distro, None);
+    }
+
+    #[test]
+    fn test_classify_provider() {
+        assert_eq!(classify_provider("OrbStack", "orbstack", None), "orbstack");
+        assert_eq!(
+            classify_provider(
+                "Ubuntu 24.04 LTS",
+                "colima",
+                Some("/Users/me/.colima/default/docker.sock")
+            ),
+            "colima"
+        );
+        assert_eq!(
+            classify_provider(
+                "Docker Desktop",
+                "docker-desktop",
+                Some("/Users/me/.docker/run/docker.sock")
+            ),
+            "docker-desktop"
+        );
+        assert_eq!(
+            classify_provider(
+                "fedora",
+                "localhost",
+                Some("/run/user/1000/podman/podman.sock")
+            ),
+            "podman"
+        );
+        assert_eq!(
+            classify_provider("Ubuntu 22.04.4 LTS", "build-host", Some("/run/docker.sock")),
+            "docker-engine"
+        );
+    }
+
+    fn validate(
+        resources: &VmResources,
+        host_cpus: u32,
+        host_memory_gb: Option<u32>,
+    ) -> Result<VmResources, String> {
+        VmResources::with_overrides(
+            Some(resources.cpu),
+            Some(resources.memory_gb),
+            Some(resources.disk_gb),
+            host_cpus,
+            host_memory_gb,
+        )
+    }
+
+    #[test]
+    fn test_vm_resources_defaults_are_valid() {
+        let defaults = VmResources::default();
+        assert_eq!(defaults.cpu, 2);
+        assert_eq!(defaults.memory_gb, 4);
+        assert_eq!(defaults.disk_gb, 60);
+        assert!(validate(&defaults, 8, Some(16)).is_ok());
+    }
+
+    #[test]
+    fn test_vm_resources_rejects_out_of_range() {
+        let too_many_cpus = VmResources {
+            cpu: 16,
+            ..Default::default()
+        };
+        assert!(validate(&too_many_cpus, 8, Some(16)).is_err());
+
+        let no_memory = VmResources {
+            memory_gb: 0,
+            ..Default::default()
+        };
+        assert!(validate(&no_memory, 8, Some(16)).is_err());
+
+        let too_much_memory = VmResources {
+            memory_gb: 32,
+            ..Default::default()
+        };
+        assert!(validate(&too_much_memory, 8, Some(16)).is_err());
+        // Unknown host memory only enforces the lower bound
+        assert!(validate(&too_much_memory, 8, None).is_ok());
+
+        let huge_disk = VmResources {
+            disk_gb: 4096,
+            ..Default::default()
+        };
+        assert!(validate(&huge_disk, 8, Some(16)).is_err());
+    }
+
+    #[test]
+    fn test_vm_resources_only_checks_requested_values() {
+        // A 1-CPU, 3 GB host is smaller than the defaults, which must still be accepted
+        let resources = VmResources::with_overrides(None, None, None, 1, Some(3)).unwrap();
+        assert_eq!(resources.cpu, VmResources::default().cpu);
+        assert_eq!(resources.memory_gb, VmResources::default().memory_gb);
+
+        let resources = VmResources::with_overrides(Some(1), None, Some(20), 1, Some(3)).unwrap();
+        assert_eq!((resources.cpu, resources.disk_gb), (1, 20));
+        assert!(VmResources::with_overrides(Some(2), None, None, 1, Some(3)).is_err());
+        assert!(VmResources::with_overrides(None, Some(4), None, 1, Some(3)).is_err());
+    }
+
+    #[test]
+    fn test_validate_colima_profile() {
+        assert!(validate_colima_profile("default").is_ok());
+        assert!(validate_colima_profile("dev").is_ok());
+        assert!(validate_colima_profile("k8s-arm_64").is_ok());
+
+        assert!(validate_colima_profile("").is_err());
+        assert!(validate_colima_profile("-dev").is_err());
+        assert!(validate_colima_profile("../default").is_err());
+        assert!(validate_colima_profile("dev profile").is_err());
+        assert!(validate_colima_profile(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_colima_socket_path() {
+        assert_eq!(
+            colima_socket_path("/Users/me", DEFAULT_COLIMA_PROFILE),
+            "/Users/me/.colima/default/docker.sock"
+        );
+        assert_eq!(
+            colima_socket_path("/Users/me", "dev"),
+            "/Users/me/.colima/dev/docker.sock"
+        );
+    }
+
+    #[test]
+    fn test_colima_progress_message() {
+        assert_eq!(
+            colima_progress_message(
+                r#"time="2024-05-01T10:00:00+02:00" level=info msg="starting colima""#
+            ),
+            Some("starting colima".to_string())
+        );
+        assert_eq!(
+            colima_progress_message(r#"level=info msg="say \"hi\"" context=vm"#),
+            Some("say \"hi\"".to_string())
+        );
+        assert_eq!(
+            colima_progress_message("level=info msg=provisioning"),
+            Some("provisioning".to_string())
+        );
+        assert_eq!(
+            colima_progress_message("  downloading disk image 42%  "),
+            Some("downloading disk image 42%".to_string())
+        );
+        assert_eq!(colima_progress_message("   "), None);
+        assert_eq!(colima_progress_message(r#"level=info msg="""#), None);
+    }
 }
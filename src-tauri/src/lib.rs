@@ -1,11 +1,21 @@
+mod build_context;
 mod docker_lifecycle;
+mod tar;
 
+use bollard::container::{AttachContainerResults, LogOutput};
 use bollard::exec::{CreateExecOptions, StartExecResults};
-use bollard::models::ContainerStatsResponse;
+use bollard::models::{
+    ContainerCreateBody, ContainerInspectResponse, ContainerStatsResponse, ContainerUpdateBody,
+    EndpointSettings, HistoryResponseItem, ImageConfig, ImageDeleteResponseItem,
+    MountPointTypeEnum, NetworkConnectRequest, NetworkingConfig, RestartPolicy,
+    RestartPolicyNameEnum,
+};
 use bollard::exec::ResizeExecOptions;
 use bollard::query_parameters::{
-    CreateImageOptions, ListContainersOptions, ListImagesOptions, ListNetworksOptions,
-    ListVolumesOptions, LogsOptions, RemoveImageOptions, RemoveVolumeOptions, StatsOptions,
+    BuildImageOptions, CreateContainerOptions, CreateImageOptions, InspectContainerOptions,
+    ListContainersOptions, ListImagesOptions, ListNetworksOptions, ListVolumesOptions, LogsOptions,
+    RemoveContainerOptions, RemoveImageOptions, RemoveVolumeOptions, RenameContainerOptions,
+    StatsOptions, StopContainerOptions, TagImageOptions, WaitContainerOptions,
 };
 use bollard::Docker;
 use futures_util::stream::FuturesUnordered;
@@ -13,7 +23,7 @@ use futures_util::stream::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use tauri::{Emitter, State};
+use tauri::{Emitter, Manager, State};
 use tokio::sync::mpsc;
 use tokio::task::AbortHandle;
 
@@ -21,426 +31,1203 @@ use tokio::task::AbortHandle;
 struct InnerDockerState {
     client: Option<Docker>,
     path: String,
+    /// Request timeout applied to every client stored here, including after reconnects
+    timeout_secs: u64,
 }
 
-/// Connect to Docker, trying Colima's socket on macOS if default fails.
-/// Returns (Docker, path_string)
-fn connect_docker() -> Result<(Docker, String), bollard::errors::Error> {
-    // First try the default connection
-    if let Ok(docker) = Docker::connect_with_local_defaults() {
-        return Ok((docker, "default".to_string()));
-    }
+/// Request timeout used until the user picks another with `set_request_timeout`
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 120;
+/// Bounds accepted by `set_request_timeout`
+const REQUEST_TIMEOUT_RANGE: std::ops::RangeInclusive<u64> = 5..=3600;
+
+/// Attempts `connect_docker` makes before giving up
+const CONNECT_ATTEMPTS: u32 = 3;
+/// Delay before the second attempt; doubled for each one after that
+const CONNECT_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+/// How long a single ping may take before the candidate counts as unreachable
+const CONNECT_PING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Clients worth trying, in order: the default connection, Colima's socket on macOS,
+/// then Podman's sockets (recorded as "podman:<socket>")
+fn docker_candidates() -> Vec<(Result<Docker, bollard::errors::Error>, String)> {
+    let mut candidates = vec![(Docker::connect_with_local_defaults(), "default".to_string())];
 
     // On macOS, try Colima's socket path
     #[cfg(target_os = "macos")]
     {
         if let Ok(home) = std::env::var("HOME") {
-            let colima_socket = format!("{home}/.colima/default/docker.sock");
+            let colima_socket = docker_lifecycle::colima_socket_path(
+                &home,
+                &docker_lifecycle::active_colima_profile(),
+            );
             if std::path::Path::new(&colima_socket).exists() {
-                let d = Docker::connect_with_socket(
-                    &colima_socket,
-                    120,
+                candidates.push((
+                    Docker::connect_with_socket(
+                        &colima_socket,
+                        DEFAULT_REQUEST_TIMEOUT_SECS,
+                        bollard::API_DEFAULT_VERSION,
+                    ),
+                    colima_socket,
+                ));
+            }
+        }
+    }
+
+    // Try Podman's Docker-compatible API socket
+    for socket in docker_lifecycle::podman_socket_candidates() {
+        if std::path::Path::new(&socket).exists() {
+            candidates.push((
+                Docker::connect_with_socket(
+                    &socket,
+                    DEFAULT_REQUEST_TIMEOUT_SECS,
                     bollard::API_DEFAULT_VERSION,
-                )?;
-                return Ok((d, colima_socket));
+                ),
+                format!("podman:{}", socket),
+            ));
+        }
+    }
+
+    candidates
+}
+
+/// Ping a freshly created client, bounded by `CONNECT_PING_TIMEOUT`
+async fn ping_client(docker: &Docker) -> Result<(), bollard::errors::Error> {
+    match tokio::time::timeout(CONNECT_PING_TIMEOUT, docker.ping()).await {
+        Ok(result) => result.map(|_| ()),
+        Err(_) => Err(bollard::errors::Error::RequestTimeoutError),
+    }
+}
+
+/// Connect to Docker, trying Colima's socket on macOS and then Podman's sockets if default fails.
+/// Only clients that answer a ping are returned, and the whole chain is retried with
+/// exponential backoff so a socket that isn't accepting connections yet (laptop wake,
+/// right after `colima start`) doesn't fail the first call.
+/// Returns (Docker, path_string); Podman connections are recorded as "podman:<socket>"
+async fn connect_docker() -> Result<(Docker, String), bollard::errors::Error> {
+    let mut delay = CONNECT_RETRY_DELAY;
+    let mut first_err = None;
+    for attempt in 1..=CONNECT_ATTEMPTS {
+        for (client, path) in docker_candidates() {
+            let result = match client {
+                Ok(d) => ping_client(&d).await.map(|_| d),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(d) => return Ok((d, path)),
+                // Report the default connection's error, as before
+                Err(e) => {
+                    first_err.get_or_insert(e);
+                }
             }
         }
+        if attempt < CONNECT_ATTEMPTS {
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+    Err(first_err.unwrap_or(bollard::errors::Error::RequestTimeoutError))
+}
+
+/// Run a connect future from synchronous code, which may itself be on a runtime worker.
+/// Only for startup; commands await `DockerState::client` instead.
+fn connect_blocking<F>(connect: F) -> Result<(Docker, String), bollard::errors::Error>
+where
+    F: std::future::Future<Output = Result<(Docker, String), bollard::errors::Error>> + Send,
+{
+    std::thread::scope(|s| {
+        s.spawn(|| tauri::async_runtime::block_on(connect))
+            .join()
+            .unwrap_or(Err(bollard::errors::Error::RequestTimeoutError))
+    })
+}
+
+/// Connect to a specific connection path as recorded in `InnerDockerState::path`
+/// ("default", a socket path, "podman:<socket>" or a `scheme://` endpoint).
+fn connect_to_path(path: &str) -> Result<Docker, bollard::errors::Error> {
+    match path {
+        "default" | "" => Docker::connect_with_local_defaults(),
+        p if p.contains("://") => Docker::connect_with_host(p),
+        p => Docker::connect_with_socket(
+            p.strip_prefix("podman:").unwrap_or(p),
+            DEFAULT_REQUEST_TIMEOUT_SECS,
+            bollard::API_DEFAULT_VERSION,
+        ),
+    }
+}
+
+/// File in the app config dir remembering the connection the user last chose
+const CONNECTION_FILE: &str = "connection.json";
+
+#[derive(Serialize, Deserialize)]
+struct SavedConnection {
+    path: String,
+}
+
+fn connection_file(app_handle: &tauri::AppHandle) -> Option<std::path::PathBuf> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(CONNECTION_FILE))
+}
+
+/// Read the persisted connection path, if any
+fn load_saved_connection(app_handle: &tauri::AppHandle) -> Option<String> {
+    let contents = std::fs::read_to_string(connection_file(app_handle)?).ok()?;
+    serde_json::from_str::<SavedConnection>(&contents)
+        .ok()
+        .map(|c| c.path)
+}
+
+/// Remember a deliberately chosen connection path for the next launch
+fn save_connection(app_handle: &tauri::AppHandle, path: &str) {
+    let Some(file) = connection_file(app_handle) else {
+        return;
+    };
+    if let Some(dir) = file.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let saved = SavedConnection {
+        path: path.to_string(),
+    };
+    if let Err(e) = std::fs::write(&file, serde_json::to_string(&saved).unwrap_or_default()) {
+        log::warn!("Failed to save connection path: {}", e);
+    }
+}
+
+/// File in the app config dir holding user preferences
+const PREFERENCES_FILE: &str = "preferences.json";
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct Preferences {
+    /// Stop every running container before quitting
+    #[serde(default)]
+    stop_all_containers_on_quit: bool,
+    /// Registry prefix Docker Hub pulls are rewritten through, e.g. "mirror.corp"
+    #[serde(default)]
+    registry_mirror: Option<String>,
+}
+
+struct PreferencesState(Mutex<Preferences>);
+
+fn preferences_file(app_handle: &tauri::AppHandle) -> Option<std::path::PathBuf> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(PREFERENCES_FILE))
+}
+
+/// Read saved preferences, falling back to defaults
+fn load_preferences(app_handle: &tauri::AppHandle) -> Preferences {
+    preferences_file(app_handle)
+        .and_then(|file| std::fs::read_to_string(file).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_preferences(
+    app_handle: &tauri::AppHandle,
+    preferences: &Preferences,
+) -> Result<(), String> {
+    let file = preferences_file(app_handle).ok_or("Config directory unavailable")?;
+    if let Some(dir) = file.parent() {
+        let _ = std::fs::create_dir_all(dir);
     }
+    let contents = serde_json::to_string(preferences).map_err(|e| e.to_string())?;
+    std::fs::write(&file, contents).map_err(|e| format!("Failed to save preferences: {}", e))
+}
 
-    // Fall back to default error
-    let d = Docker::connect_with_local_defaults()?;
-    Ok((d, "default".to_string()))
+/// Outcome of the most recent reconnect attempt, shared with callers that waited on it
+#[derive(Default)]
+struct ConnectAttempt {
+    /// Incremented each time an attempt finishes
+    generation: u64,
+    error: Option<String>,
 }
 
+/// After a failed reconnect, how long callers get the cached error instead of a new
+/// attempt, so a daemon that's simply not there doesn't slow every command down
+const RECONNECT_COOLDOWN: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// Singleton Docker client — lazily initialized and cached.
 /// Uses `Mutex<InnerDockerState>` so it can reconnect if Docker wasn't available at startup.
-struct DockerState(Mutex<InnerDockerState>);
+struct DockerState {
+    inner: Mutex<InnerDockerState>,
+    /// Held for the duration of a reconnect so only one runs at a time. Async so
+    /// callers waiting on it yield their runtime thread instead of blocking it.
+    connecting: tokio::sync::Mutex<ConnectAttempt>,
+    /// When the last attempt failed; further attempts wait out `RECONNECT_COOLDOWN`
+    failed_at: Mutex<Option<std::time::Instant>>,
+    /// Mirror of `ConnectAttempt::generation` readable without waiting on `connecting`
+    attempts: std::sync::atomic::AtomicU64,
+}
 
 impl DockerState {
-    fn new() -> Self {
+    fn with_state(client: Option<Docker>, path: String) -> Self {
+        let timeout = std::time::Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS);
+        Self {
+            inner: Mutex::new(InnerDockerState {
+                client: client.map(|d| d.with_timeout(timeout)),
+                path,
+                timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+            }),
+            connecting: tokio::sync::Mutex::new(ConnectAttempt::default()),
+            failed_at: Mutex::new(None),
+            attempts: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    /// Try the persisted connection first, then the normal fallback chain. The saved
+    /// connection must answer a ping, so a dead socket isn't kept until the monitor
+    /// notices.
+    fn new(saved_path: Option<String>) -> Self {
         // Try to connect immediately; if Docker isn't up yet, store None
-        let (client, path) = match connect_docker() {
+        let connected = match &saved_path {
+            Some(saved) => connect_blocking(reconnect(saved)),
+            None => connect_blocking(connect_docker()),
+        };
+        if let (Some(saved), Ok((_, path))) = (&saved_path, &connected) {
+            if saved == path {
+                log::info!("Using saved Docker connection: {}", path);
+            } else {
+                log::warn!(
+                    "Saved Docker connection {} unavailable, using {}",
+                    saved,
+                    path
+                );
+            }
+        }
+        let (client, path) = match connected {
             Ok((d, p)) => (Some(d), p),
             Err(_) => (None, "".to_string()),
         };
-        Self(Mutex::new(InnerDockerState { client, path }))
+        Self::with_state(client, path)
+    }
+
+    /// Reconnect, single-flight: when several commands find no client at once, the
+    /// first runs `connect_docker` and the rest wait and share its result instead of
+    /// each opening their own sockets.
+    async fn connect_with_retry(&self) -> Result<Docker, String> {
+        self.connect_single_flight(connect_docker).await
+    }
+
+    /// `connect_with_retry` with the connect step passed in. Waiters `.await` the
+    /// attempt in progress rather than blocking their thread on it.
+    async fn connect_single_flight<F, Fut>(&self, connect: F) -> Result<Docker, String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<(Docker, String), bollard::errors::Error>>,
+    {
+        use std::sync::atomic::Ordering;
+
+        let seen = self.attempts.load(Ordering::SeqCst);
+        let mut attempt = self.connecting.lock().await;
+        let cooling_down = self
+            .failed_at
+            .lock()
+            .unwrap()
+            .is_some_and(|at| at.elapsed() < RECONNECT_COOLDOWN);
+        // Another caller finished an attempt while we were waiting, or one failed recently
+        if attempt.generation != seen || cooling_down {
+            return match self.cached_client() {
+                Some(docker) => Ok(docker),
+                None => Err(attempt
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| "Docker is not available".to_string())),
+            };
+        }
+
+        let result = connect().await.map_err(|e| e.to_string());
+        attempt.error = result.as_ref().err().cloned();
+        *self.failed_at.lock().unwrap() = result.is_err().then(std::time::Instant::now);
+        attempt.generation += 1;
+        self.attempts.store(attempt.generation, Ordering::SeqCst);
+
+        let (docker, path) = result?;
+        Ok(self.set_client(docker, path))
     }
 
-    fn connect_with_retry(&self) -> Result<Docker, String> {
-        let (docker, path) = connect_docker().map_err(|e| e.to_string())?;
-        let mut guard = self.0.lock().unwrap();
+    /// Drop the cached client and reconnect to the connection in use, falling back
+    /// to the default chain like the connection monitor does
+    async fn reconnect_saved(&self) -> Result<Docker, String> {
+        let path = self.get_path();
+        self.clear_client();
+        self.reset_reconnect_cooldown();
+        self.connect_single_flight(|| reconnect(&path)).await
+    }
+
+    /// Allow an immediate reconnect again, e.g. once Docker is known to be back
+    fn reset_reconnect_cooldown(&self) {
+        *self.failed_at.lock().unwrap() = None;
+    }
+
+    /// Replace the cached client, e.g. after switching to another Docker context.
+    /// Returns the stored client, which carries the configured request timeout.
+    fn set_client(&self, docker: Docker, path: String) -> Docker {
+        let mut guard = self.inner.lock().unwrap();
+        let docker = docker.with_timeout(std::time::Duration::from_secs(guard.timeout_secs));
         guard.client = Some(docker.clone());
         guard.path = path;
-        Ok(docker)
+        docker
+    }
+
+    /// Change the request timeout and re-apply it to the cached client
+    fn set_timeout(&self, secs: u64) {
+        let mut guard = self.inner.lock().unwrap();
+        guard.timeout_secs = secs;
+        let timeout = std::time::Duration::from_secs(secs);
+        guard.client = guard.client.take().map(|d| d.with_timeout(timeout));
     }
 
     /// Get (or reconnect) the Docker client. Caches the connection for reuse.
-    fn client(&self) -> Result<Docker, String> {
+    async fn client(&self) -> Result<Docker, String> {
         // Scope the lock to avoid holding it during connection attempt if simpler
         {
-            let guard = self.0.lock().unwrap();
+            let guard = self.inner.lock().unwrap();
             if let Some(ref docker) = guard.client {
                 return Ok(docker.clone());
             }
         }
         
-        self.connect_with_retry()
+        self.connect_with_retry().await
     }
 
     fn get_path(&self) -> String {
-        let guard = self.0.lock().unwrap();
+        let guard = self.inner.lock().unwrap();
         guard.path.clone()
     }
+
+    /// The cached client without attempting a reconnect
+    fn cached_client(&self) -> Option<Docker> {
+        self.inner.lock().unwrap().client.clone()
+    }
+
+    /// Drop a client that no longer answers so the next call reconnects
+    fn clear_client(&self) {
+        self.inner.lock().unwrap().client = None;
+    }
+
+    /// Forget the current connection and its path, so the next call goes through
+    /// the default fallback chain instead of reconnecting to the same endpoint
+    fn disconnect(&self) {
+        let mut guard = self.inner.lock().unwrap();
+        guard.client = None;
+        guard.path = String::new();
+    }
+}
+
+/// How often the connection monitor pings the daemon
+const CONNECTION_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Handle of the background connection monitor so it can be stopped on exit
+struct ConnectionMonitor(Mutex<Option<tauri::async_runtime::JoinHandle<()>>>);
+
+/// Reconnect to `path` (the saved connection, or the one in use before the daemon
+/// went away), falling back to the default chain. Only returns clients that answer
+/// a ping.
+async fn reconnect(path: &str) -> Result<(Docker, String), bollard::errors::Error> {
+    if let Ok(d) = connect_to_path(path) {
+        if ping_client(&d).await.is_ok() {
+            return Ok((d, path.to_string()));
+        }
+    }
+    connect_docker().await
+}
+
+/// Periodically ping the cached client; when the daemon restarts or stops, drop the
+/// stale client, emit `docker-disconnected`, and emit `docker-connected` once it's back.
+async fn monitor_connection(app_handle: tauri::AppHandle) {
+    let mut connected = app_handle.state::<DockerState>().cached_client().is_some();
+
+    loop {
+        tokio::time::sleep(CONNECTION_POLL_INTERVAL).await;
+        let docker_state = app_handle.state::<DockerState>();
+
+        let alive = match docker_state.cached_client() {
+            Some(docker) => ping_client(&docker).await.is_ok(),
+            None => false,
+        };
+        if alive {
+            if !connected {
+                connected = true;
+                let _ = app_handle.emit("docker-connected", docker_state.get_path());
+            }
+            continue;
+        }
+
+        let path = docker_state.get_path();
+        if connected {
+            log::warn!("Lost connection to Docker at {}", path);
+            docker_state.clear_client();
+            connected = false;
+            let _ = app_handle.emit("docker-disconnected", path.clone());
+        }
+
+        // Single-flight so a command reconnecting at the same time shares this attempt
+        if docker_state
+            .connect_single_flight(|| reconnect(&path))
+            .await
+            .is_ok()
+        {
+            let new_path = docker_state.get_path();
+            log::info!("Reconnected to Docker at {}", new_path);
+            docker_state.reset_reconnect_cooldown();
+            connected = true;
+            let _ = app_handle.emit("docker-connected", new_path);
+        }
+    }
+}
+
+/// Raw lines kept per log session so timestamps can be toggled without restarting
+const LOG_REPLAY_BUFFER_LINES: usize = 5000;
+
+/// Per-session log display state shared between the stream task and `set_log_timestamps`
+struct LogDisplay {
+    timestamps: bool,
+    /// Most recent lines as received, each with its daemon timestamp prefix
+    lines: std::collections::VecDeque<String>,
+}
+
+struct LogSession {
+    handle: tauri::async_runtime::JoinHandle<()>,
+    display: Arc<Mutex<LogDisplay>>,
+    strip_ansi: bool,
 }
 
-struct LogState(Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>);
+struct LogState(Mutex<HashMap<String, LogSession>>);
+
+/// Follow tasks of merged multi-container log streams, one per container, by session id
+struct MultiLogState(Mutex<HashMap<String, Vec<tauri::async_runtime::JoinHandle<()>>>>);
+
+/// Docker events subscriptions by session id
+struct EventsState(Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>);
 
 struct ExecSession {
     handle: tauri::async_runtime::JoinHandle<()>,
     input_tx: mpsc::Sender<String>,
     docker: Docker,
     exec_id: Arc<Mutex<String>>,
+    /// What `restart_exec` needs to start the same exec again
+    container_id: String,
+    exec_opts: CreateExecOptions<String>,
+    cols: u16,
+    rows: u16,
+    raw_bytes: bool,
 }
 
 struct ExecState(Mutex<HashMap<String, ExecSession>>);
 
-struct PullState(Mutex<HashMap<String, AbortHandle>>);
+struct PullSession {
+    abort: AbortHandle,
+    docker: Docker,
+    /// The reference being pulled, i.e. the mirrored one when going through a mirror
+    image: String,
+    /// Whether the image was already present locally before this pull started
+    existed_before: bool,
+}
+
+struct PullState(Mutex<HashMap<String, PullSession>>);
 
 #[derive(Deserialize)]
 struct StartLogsOptions {
+    /// Show timestamps initially; change later with `set_log_timestamps`
     timestamps: Option<bool>,
+    /// Remove ANSI escape sequences (colors, cursor movement) before emitting
+    strip_ansi: Option<bool>,
+    /// How many existing lines to replay before following: a number or "all".
+    /// "0" follows from now, e.g. when resuming after scrolling back.
+    tail: Option<String>,
+    /// Include the extra attributes logging drivers attach (`docker logs --details`).
+    /// Lines are then emitted as `DetailedLogLine` instead of plain strings, and a
+    /// failing docker CLI is reported on `logs-error-{session_id}`.
+    details: Option<bool>,
+    /// Stop following after this many lines (replayed tail included) and emit
+    /// `logs-complete-{session_id}`. None follows until stopped.
+    max_lines: Option<usize>,
+}
+
+/// A log line with the attributes the daemon attached via `--details`, e.g. the
+/// labels or env vars selected with `--log-opt labels=...`
+#[derive(Serialize, Clone)]
+struct DetailedLogLine {
+    line: String,
+    attributes: HashMap<String, String>,
+}
+
+/// Default number of existing lines `start_logs` replays
+const LOGS_DEFAULT_TAIL: &str = "100";
+
+/// Validate a logs `tail` value: "all" or a non-negative line count
+fn validate_log_tail(tail: &str) -> Result<(), String> {
+    if tail == "all"
+        || (!tail.is_empty() && tail.len() <= 9 && tail.chars().all(|c| c.is_ascii_digit()))
+    {
+        Ok(())
+    } else {
+        Err(format!("Invalid log tail: {}", tail))
+    }
+}
+
+/// Split the RFC 3339 timestamp the daemon prefixes to each line when `timestamps`
+/// is set, e.g. "2024-05-01T10:00:00.123456789Z message". Lines without one are
+/// returned unchanged.
+fn split_log_timestamp(line: &str) -> (Option<&str>, &str) {
+    match log_timestamp_len(line.as_bytes()) {
+        Some(len) => (Some(&line[..len - 1]), &line[len..]),
+        None => (None, line),
+    }
+}
+
+/// Length of the timestamp prefix `split_log_timestamp` splits off, including the
+/// space after it
+fn log_timestamp_len(line: &[u8]) -> Option<usize> {
+    let end = line.iter().position(|&b| b == b' ')?;
+    let prefix = &line[..end];
+    let looks_like_timestamp = prefix.len() >= 20
+        && prefix[..4].iter().all(u8::is_ascii_digit)
+        && prefix[4] == b'-'
+        && prefix[10] == b'T';
+    looks_like_timestamp.then_some(end + 1)
+}
+
+/// Decode a `url.QueryEscape`d attribute key or value: `+` is a space and `%XX` a byte
+fn query_unescape(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = |b: u8| (b as char).to_digit(16);
+                match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                    (Some(hi), Some(lo)) => {
+                        out.push((hi * 16 + lo) as u8);
+                        i += 2;
+                    }
+                    _ => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Split the `--details` attributes out of a log line. The daemon puts them between
+/// the timestamp and the message as `key=value,key2=value2 `, or a lone space when
+/// there are none. The line is returned with its timestamp but without attributes.
+fn split_log_details(line: &str) -> (HashMap<String, String>, String) {
+    let (timestamp, rest) = split_log_timestamp(line);
+    let (attrs, message) = rest.split_once(' ').unwrap_or(("", rest));
+    let attributes = attrs
+        .split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (query_unescape(key), query_unescape(value)))
+        .collect();
+    let line = match timestamp {
+        Some(ts) => format!("{} {}", ts, message),
+        None => message.to_string(),
+    };
+    (attributes, line)
+}
+
+/// Render a buffered line for display
+fn format_log_line(line: &str, timestamps: bool, strip: bool) -> String {
+    let line = if timestamps {
+        line
+    } else {
+        split_log_timestamp(line).1
+    };
+    if strip {
+        strip_ansi(line)
+    } else {
+        line.to_string()
+    }
+}
+
+/// Remove ANSI escape sequences: CSI (`ESC [ ... final`), OSC (`ESC ] ... BEL` or
+/// `ESC ] ... ESC \`) and two-character escapes. Other text is left untouched.
+pub fn strip_ansi(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates until a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or ST (ESC \)
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // nF escape such as ESC ( B: intermediates in 0x20..0x2F, then one final byte
+            Some(' '..='/') => {
+                for c in chars.by_ref() {
+                    if !(' '..='/').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // Two-character escape such as ESC =
+            Some(_) | None => {}
+        }
+    }
+    out
+}
+
+/// Which output stream a log line came from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+    /// TTY containers have a single raw stream
+    Console,
+}
+
+/// Reassembles log chunks into whole lines per stream.
+///
+/// Non-TTY containers multiplex stdout/stderr as framed chunks; a frame may end
+/// mid-line or even mid-UTF-8 character, so bytes are buffered per stream and only
+/// decoded once a newline arrives. TTY containers have no frames, so everything is
+/// treated as one console stream regardless of how the chunk was labelled.
+///
+/// With `timestamps` every chunk starts with its own timestamp, so the one on a
+/// chunk continuing a line is dropped rather than left in the middle of it.
+pub struct LogLineAssembler {
+    tty: bool,
+    timestamps: bool,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+/// Longest partial line held while waiting for its newline; past this the pending
+/// bytes are emitted as a line of their own
+const LOG_MAX_LINE_BYTES: usize = 256 * 1024;
+
+impl LogLineAssembler {
+    pub fn new(tty: bool, timestamps: bool) -> Self {
+        Self {
+            tty,
+            timestamps,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        }
+    }
+
+    /// Feed one chunk; returns every line it completed, each with its trailing newline.
+    pub fn push(&mut self, output: LogOutput) -> Vec<(LogStream, String)> {
+        let stream = match (&output, self.tty) {
+            (_, true) => LogStream::Console,
+            (LogOutput::StdErr { .. }, false) => LogStream::Stderr,
+            _ => LogStream::Stdout,
+        };
+        let buffer = match stream {
+            LogStream::Stderr => &mut self.stderr,
+            _ => &mut self.stdout,
+        };
+        let mut chunk: &[u8] = output.as_ref();
+        if self.timestamps && !buffer.is_empty() {
+            if let Some(len) = log_timestamp_len(chunk) {
+                chunk = &chunk[len..];
+            }
+        }
+        buffer.extend_from_slice(chunk);
+
+        let mut lines = Vec::new();
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            lines.push((stream, String::from_utf8_lossy(&line).into_owned()));
+        }
+        if buffer.len() > LOG_MAX_LINE_BYTES {
+            let line = std::mem::take(buffer);
+            lines.push((stream, String::from_utf8_lossy(&line).into_owned()));
+        }
+        lines
+    }
+
+    /// Flush any trailing partial lines once the stream has ended
+    pub fn finish(&mut self) -> Vec<(LogStream, String)> {
+        let stdout_stream = if self.tty {
+            LogStream::Console
+        } else {
+            LogStream::Stdout
+        };
+        [
+            (stdout_stream, std::mem::take(&mut self.stdout)),
+            (LogStream::Stderr, std::mem::take(&mut self.stderr)),
+        ]
+        .into_iter()
+        .filter(|(_, rest)| !rest.is_empty())
+        .map(|(stream, rest)| (stream, String::from_utf8_lossy(&rest).into_owned()))
+        .collect()
+    }
+}
+
+/// Follow logs with `--details` through the docker CLI. The daemon accepts `details`
+/// on the container logs endpoint, but bollard's `LogsOptions` doesn't expose it.
+/// The child is killed when the session task is aborted or `emit_lines` returns false.
+/// Fails with the CLI's own error output if it can't be started or exits unsuccessfully.
+async fn follow_detailed_logs(
+    id: &str,
+    tail: &str,
+    path: &str,
+    emit_lines: impl Fn(Vec<(LogStream, String)>) -> bool,
+) -> Result<(), String> {
+    use tokio::io::AsyncBufReadExt;
+
+    let mut cmd = tokio::process::Command::new("docker");
+    if let Some(host) = docker_cli_host(path) {
+        cmd.arg("-H").arg(host);
+    }
+    cmd.args([
+        "logs",
+        "--details",
+        "--timestamps",
+        "--follow",
+        "--tail",
+        tail,
+        id,
+    ])
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .kill_on_drop(true);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to run docker logs --details: {}", e))?;
+    let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        return Err("Failed to read docker logs output".to_string());
+    };
+
+    // The CLI demultiplexes for us: container stderr arrives on its stderr, next to
+    // the CLI's own errors. Container lines carry the timestamp asked for, those don't.
+    let mut stdout = tokio::io::BufReader::new(stdout).lines();
+    let mut stderr = tokio::io::BufReader::new(stderr).lines();
+    let (mut stdout_open, mut stderr_open) = (true, true);
+    let mut cli_errors = Vec::new();
+    while stdout_open || stderr_open {
+        tokio::select! {
+            line = stdout.next_line(), if stdout_open => match line {
+                Ok(Some(line)) => {
+                    if !emit_lines(vec![(LogStream::Stdout, line)]) {
+                        return Ok(());
+                    }
+                }
+                _ => stdout_open = false,
+            },
+            line = stderr.next_line(), if stderr_open => match line {
+                Ok(Some(line)) if split_log_timestamp(&line).0.is_none() => cli_errors.push(line),
+                Ok(Some(line)) => {
+                    if !emit_lines(vec![(LogStream::Stderr, line)]) {
+                        return Ok(());
+                    }
+                }
+                _ => stderr_open = false,
+            },
+        }
+    }
+    match child.wait().await {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) if cli_errors.is_empty() => Err(format!("docker logs exited with {}", status)),
+        Ok(_) => Err(cli_errors.join("\n")),
+        Err(e) => Err(format!("Failed to run docker logs --details: {}", e)),
+    }
 }
 
 #[tauri::command]
-fn start_logs(
+async fn start_logs(
     id: String,
     session_id: String,
     options: StartLogsOptions,
     app_handle: tauri::AppHandle,
     docker_state: State<'_, DockerState>,
     state: State<'_, LogState>,
-) -> CommandResponse<()> {
-    let docker = match docker_state.client() {
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
         Ok(d) => d,
-        Err(e) => return CommandResponse::err(e),
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
     };
     if let Err(e) = validate_docker_id(&id) {
-        return CommandResponse::err(e);
+        return Ok(CommandResponse::err(e));
+    }
+    let tail = options
+        .tail
+        .unwrap_or_else(|| LOGS_DEFAULT_TAIL.to_string());
+    if let Err(e) = validate_log_tail(&tail) {
+        return Ok(CommandResponse::err(e));
     }
 
+    let details = options.details.unwrap_or(false);
+    let max_lines = options.max_lines;
+    if max_lines == Some(0) {
+        return Ok(CommandResponse::err("max_lines must be positive"));
+    }
+    // Always ask for timestamps and strip them locally, so they can be toggled later
     let logs_options = Some(LogsOptions {
         follow: true,
         stdout: true,
         stderr: true,
-        timestamps: options.timestamps.unwrap_or(false),
-        tail: "100".to_string(),
+        timestamps: true,
+        tail: tail.clone(),
         ..Default::default()
     });
+    let path = docker_state.get_path();
 
+    let strip = options.strip_ansi.unwrap_or(false);
+    let display = Arc::new(Mutex::new(LogDisplay {
+        timestamps: options.timestamps.unwrap_or(false),
+        lines: std::collections::VecDeque::new(),
+    }));
+    let display_clone = display.clone();
     let session_id_clone = session_id.clone();
     let handle = tauri::async_runtime::spawn(async move {
-        let mut stream = docker.logs(&id, logs_options);
-        while let Some(Ok(log_output)) = stream.next().await {
-            let log_str = log_output.to_string();
-            let event_name = format!("logs-{}", session_id_clone);
-            let _ = app_handle.emit(&event_name, log_str);
+        // TTY containers stream raw output, everything else is multiplexed
+        let tty = docker
+            .inspect_container(&id, None)
+            .await
+            .ok()
+            .and_then(|info| info.config)
+            .and_then(|config| config.tty)
+            .unwrap_or(false);
+        let mut assembler = LogLineAssembler::new(tty, true);
+
+        let event_name = format!("logs-{}", session_id_clone);
+        let emitted = std::sync::atomic::AtomicUsize::new(0);
+        let limit_reached = || {
+            max_lines.is_some_and(|max| emitted.load(std::sync::atomic::Ordering::SeqCst) >= max)
+        };
+        // Returns false once `max_lines` have been emitted and following should stop
+        let emit_lines = |lines: Vec<(LogStream, String)>| {
+            // Hold the lock while emitting so a concurrent replay can't interleave
+            let mut display = display_clone.lock().unwrap();
+            for (_, line) in lines {
+                let line = if details {
+                    // Only the message is kept for replay, the attributes aren't buffered
+                    let (attributes, line) = split_log_details(&line);
+                    let _ = app_handle.emit(
+                        &event_name,
+                        DetailedLogLine {
+                            line: format_log_line(&line, display.timestamps, strip),
+                            attributes,
+                        },
+                    );
+                    line
+                } else {
+                    let _ = app_handle.emit(
+                        &event_name,
+                        format_log_line(&line, display.timestamps, strip),
+                    );
+                    line
+                };
+                if display.lines.len() == LOG_REPLAY_BUFFER_LINES {
+                    display.lines.pop_front();
+                }
+                display.lines.push_back(line);
+                emitted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                if limit_reached() {
+                    return false;
+                }
+            }
+            true
+        };
+
+        if details {
+            if let Err(e) = follow_detailed_logs(&id, &tail, &path, &emit_lines).await {
+                log::warn!("Following detailed logs of {} failed: {}", id, e);
+                let _ = app_handle.emit(&format!("logs-error-{}", session_id_clone), e);
+            }
+        } else {
+            let mut stream = docker.logs(&id, logs_options);
+            let mut following = true;
+            while following {
+                match stream.next().await {
+                    Some(Ok(log_output)) => following = emit_lines(assembler.push(log_output)),
+                    _ => break,
+                }
+            }
+            if following {
+                emit_lines(assembler.finish());
+            }
+        }
+
+        if limit_reached() {
+            let _ = app_handle.emit(
+                &format!("logs-complete-{}", session_id_clone),
+                emitted.load(std::sync::atomic::Ordering::SeqCst),
+            );
         }
     });
 
     let mut lock = state.inner().0.lock().unwrap();
-    if let Some(old_handle) = lock.insert(session_id, handle) {
-        old_handle.abort();
+    if let Some(old_session) = lock.insert(
+        session_id,
+        LogSession {
+            handle,
+            display,
+            strip_ansi: strip,
+        },
+    ) {
+        old_session.handle.abort();
     }
 
-    CommandResponse {
-        success: true,
-        data: None,
-        error: None,
-    }
+    Ok(CommandResponse::ok_empty())
 }
 
 #[tauri::command]
 fn stop_logs(session_id: String, state: State<'_, LogState>) -> CommandResponse<()> {
     let mut lock = state.inner().0.lock().unwrap();
-    if let Some(handle) = lock.remove(&session_id) {
-        handle.abort();
+    if let Some(session) = lock.remove(&session_id) {
+        session.handle.abort();
     }
     CommandResponse {
         success: true,
         data: None,
         error: None,
+        error_code: None,
     }
 }
 
+/// A line of a merged log stream, tagged with the container it came from
+#[derive(Serialize, Clone)]
+struct MultiLogLine {
+    container_id: String,
+    line: String,
+    stream: LogStream,
+}
+
+/// Follow the logs of several containers at once, like `docker compose logs -f`.
+/// Lines from all of them are interleaved on `multi-logs-{session_id}` in arrival
+/// order, each tagged with its container. `tail` applies per container.
 #[tauri::command]
-fn start_exec(
+async fn start_multi_logs(
     session_id: String,
-    container_id: String,
-    cols: u16,
-    rows: u16,
+    ids: Vec<String>,
+    tail: Option<String>,
     app_handle: tauri::AppHandle,
     docker_state: State<'_, DockerState>,
-    state: State<'_, ExecState>,
-) -> CommandResponse<()> {
-    let docker = match docker_state.client() {
+    state: State<'_, MultiLogState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
         Ok(d) => d,
-        Err(e) => return CommandResponse::err(e),
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
     };
-    if let Err(e) = validate_docker_id(&container_id) {
-        return CommandResponse::err(e);
+    if ids.is_empty() {
+        return Ok(CommandResponse::err("No containers given".to_string()));
+    }
+    for id in &ids {
+        if let Err(e) = validate_docker_id(id) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+    let tail = tail.unwrap_or_else(|| LOGS_DEFAULT_TAIL.to_string());
+    if let Err(e) = validate_log_tail(&tail) {
+        return Ok(CommandResponse::err(e));
     }
-    let cols = if cols == 0 { 80 } else { cols };
-    let rows = if rows == 0 { 24 } else { rows };
-
-    let session_id_clone = session_id.clone();
-    let docker_for_resize = docker.clone();
-
-    let (input_tx, mut input_rx) = mpsc::channel::<String>(100);
-    let exec_id_shared: Arc<Mutex<String>> = Arc::new(Mutex::new(String::new()));
-    let exec_id_writer = exec_id_shared.clone();
-
-    let handle = tauri::async_runtime::spawn(async move {
-        // Create exec instance
-        let exec_opts = CreateExecOptions {
-            attach_stdout: Some(true),
-            attach_stderr: Some(true),
-            attach_stdin: Some(true),
-            tty: Some(true),
-            cmd: Some(vec![
-                "/bin/sh",
-                "-c",
-                "if command -v bash > /dev/null; then exec bash; else exec sh; fi",
-            ]),
-            ..Default::default()
-        };
-
-        let exec = match docker.create_exec(&container_id, exec_opts).await {
-            Ok(e) => e,
-            Err(e) => {
-                let event_name = format!("exec-{}", session_id_clone);
-                let _ = app_handle.emit(&event_name, format!("\r\nError creating exec: {}\r\n", e));
-                return;
-            }
-        };
-
-        // Store exec_id for later resize calls
-        *exec_id_writer.lock().unwrap() = exec.id.clone();
-
-        // Start exec
-        let start_result = docker.start_exec(&exec.id, None).await;
-
-        match start_result {
-            Ok(StartExecResults::Attached {
-                mut output,
-                mut input,
-            }) => {
-                // Resize TTY in background so it doesn't block starting the session
-                let docker_c = docker.clone();
-                let exec_id_c = exec.id.clone();
-                tauri::async_runtime::spawn(async move {
-                    let _ = docker_c.resize_exec(&exec_id_c, ResizeExecOptions { width: cols, height: rows }).await;
-                });
-
-                let event_name = format!("exec-{}", session_id_clone);
-                let app_clone = app_handle.clone();
 
-                // Spawn task to read output
-                let output_task = tauri::async_runtime::spawn(async move {
-                    while let Some(Ok(msg)) = output.next().await {
-                        let data = msg.to_string();
-                        let _ = app_clone.emit(&event_name, data);
+    let event_name = format!("multi-logs-{}", session_id);
+    let handles = ids
+        .into_iter()
+        .map(|id| {
+            let docker = docker.clone();
+            let app_handle = app_handle.clone();
+            let event_name = event_name.clone();
+            let logs_options = Some(LogsOptions {
+                follow: true,
+                stdout: true,
+                stderr: true,
+                tail: tail.clone(),
+                ..Default::default()
+            });
+            tauri::async_runtime::spawn(async move {
+                let tty = docker
+                    .inspect_container(&id, None)
+                    .await
+                    .ok()
+                    .and_then(|info| info.config)
+                    .and_then(|config| config.tty)
+                    .unwrap_or(false);
+                let mut assembler = LogLineAssembler::new(tty, false);
+                let emit_lines = |lines: Vec<(LogStream, String)>| {
+                    for (stream, line) in lines {
+                        let _ = app_handle.emit(
+                            &event_name,
+                            MultiLogLine {
+                                container_id: id.clone(),
+                                line,
+                                stream,
+                            },
+                        );
                     }
-                });
+                };
 
-                // Read input from channel and send to container
-                while let Some(data) = input_rx.recv().await {
-                    use tokio::io::AsyncWriteExt;
-                    if input.write_all(data.as_bytes()).await.is_err() {
-                        break;
-                    }
+                let mut stream = docker.logs(&id, logs_options);
+                while let Some(Ok(log_output)) = stream.next().await {
+                    emit_lines(assembler.push(log_output));
                 }
-
-                output_task.abort();
-            }
-            Ok(StartExecResults::Detached) => {
-                let event_name = format!("exec-{}", session_id_clone);
-                let _ = app_handle.emit(
-                    &event_name,
-                    "\r\nExec started in detached mode\r\n".to_string(),
-                );
-            }
-            Err(e) => {
-                let event_name = format!("exec-{}", session_id_clone);
-                let _ = app_handle.emit(&event_name, format!("\r\nError starting exec: {}\r\n", e));
-            }
-        }
-    });
+                emit_lines(assembler.finish());
+            })
+        })
+        .collect();
 
     let mut lock = state.inner().0.lock().unwrap();
-    if let Some(old_session) = lock.insert(session_id, ExecSession { handle, input_tx, docker: docker_for_resize, exec_id: exec_id_shared }) {
-        old_session.handle.abort();
-    }
-
-    CommandResponse {
-        success: true,
-        data: None,
-        error: None,
+    if let Some(old_handles) = lock.insert(session_id, handles) {
+        for handle in old_handles {
+            handle.abort();
+        }
     }
+    Ok(CommandResponse::ok_empty())
 }
 
 #[tauri::command]
-fn exec_input(
-    session_id: String,
-    data: String,
-    state: State<'_, ExecState>,
-) -> CommandResponse<()> {
-    let lock = state.inner().0.lock().unwrap();
-    if let Some(session) = lock.get(&session_id) {
-        let tx = session.input_tx.clone();
-        drop(lock);
-        let _ = tx.blocking_send(data);
-    }
-    CommandResponse {
-        success: true,
-        data: None,
-        error: None,
+fn stop_multi_logs(session_id: String, state: State<'_, MultiLogState>) -> CommandResponse<()> {
+    if let Some(handles) = state.inner().0.lock().unwrap().remove(&session_id) {
+        for handle in handles {
+            handle.abort();
+        }
     }
+    CommandResponse::ok_empty()
 }
 
+/// Show or hide timestamps on a running log stream without restarting it. The
+/// buffered lines (up to `LOG_REPLAY_BUFFER_LINES`) are re-rendered and sent as one
+/// `logs-replay-{session_id}` event so the UI can replace what it shows.
 #[tauri::command]
-fn exec_resize(
+fn set_log_timestamps(
     session_id: String,
-    cols: u16,
-    rows: u16,
-    state: State<'_, ExecState>,
+    enabled: bool,
+    app_handle: tauri::AppHandle,
+    state: State<'_, LogState>,
 ) -> CommandResponse<()> {
-    let (docker, exec_id) = {
-        let lock = state.inner().0.lock().unwrap();
-        match lock.get(&session_id) {
-            Some(s) => {
-                let eid = s.exec_id.lock().unwrap().clone();
-                if eid.is_empty() {
-                    return CommandResponse::ok_empty();
-                }
-                (s.docker.clone(), eid)
-            }
-            None => return CommandResponse::ok_empty(),
-        }
+    let (display, strip) = match state.inner().0.lock().unwrap().get(&session_id) {
+        Some(session) => (session.display.clone(), session.strip_ansi),
+        None => return CommandResponse::err(format!("No log session: {}", session_id)),
     };
-    tauri::async_runtime::spawn(async move {
-        let _ = docker.resize_exec(&exec_id, ResizeExecOptions { width: cols, height: rows }).await;
-    });
-    CommandResponse::ok_empty()
-}
 
-#[tauri::command]
-fn stop_exec(session_id: String, state: State<'_, ExecState>) -> CommandResponse<()> {
-    let mut lock = state.inner().0.lock().unwrap();
-    if let Some(session) = lock.remove(&session_id) {
-        session.handle.abort();
-    }
-    CommandResponse {
-        success: true,
-        data: None,
-        error: None,
-    }
-}
+    let mut display = display.lock().unwrap();
+    display.timestamps = enabled;
+    let replay: Vec<String> = display
+        .lines
+        .iter()
+        .map(|line| format_log_line(line, enabled, strip))
+        .collect();
+    let _ = app_handle.emit(&format!("logs-replay-{}", session_id), replay);
 
-/// Validate a Docker resource identifier (container ID/name, image ref, volume name).
-/// Allows hex IDs (12/64 chars), names with alphanumeric + `-_./:@`, and rejects
-/// anything with shell metacharacters or suspicious patterns.
-fn validate_docker_id(id: &str) -> Result<(), String> {
-    if id.is_empty() {
-        return Err("Identifier cannot be empty".to_string());
-    }
-    if id.len() > 256 {
-        return Err("Identifier too long".to_string());
-    }
-    // Allow: alphanumeric, hyphen, underscore, dot, slash, colon, @ (for image digests)
-    if !id
-        .chars()
-        .all(|c| c.is_ascii_alphanumeric() || "-_./:@".contains(c))
-    {
-        return Err(format!("Invalid identifier: {}", id));
-    }
-    Ok(())
+    CommandResponse::ok_empty()
 }
 
-#[derive(Serialize, PartialEq, Debug)]
-struct CommandResponse<T> {
-    success: bool,
-    data: Option<T>,
-    error: Option<String>,
-}
+/// Default number of trailing log lines `get_container_logs_stats` samples
+const LOG_STATS_DEFAULT_LINES: u32 = 1000;
 
-impl<T> CommandResponse<T> {
-    fn ok(data: T) -> Self {
-        Self { success: true, data: Some(data), error: None }
-    }
+/// Log volume over a container's recent output
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct LogVolume {
+    pub total_lines: u64,
+    pub stderr_lines: u64,
+    pub bytes: u64,
+}
 
-    fn ok_empty() -> Self {
-        Self { success: true, data: None, error: None }
-    }
+impl LogVolume {
+    /// Count one log chunk. A chunk without a trailing newline still counts as a line.
+    pub fn add(&mut self, output: &LogOutput) {
+        let message: &[u8] = output.as_ref();
+        if message.is_empty() {
+            return;
+        }
+        let newlines = message.iter().filter(|b| **b == b'\n').count() as u64;
+        let lines = newlines + u64::from(!message.ends_with(b"\n"));
 
-    fn err(msg: impl Into<String>) -> Self {
-        Self { success: false, data: None, error: Some(msg.into()) }
+        self.total_lines += lines;
+        if matches!(output, LogOutput::StdErr { .. }) {
+            self.stderr_lines += lines;
+        }
+        self.bytes += message.len() as u64;
     }
 }
 
 #[derive(Serialize)]
-struct ContainerStatsResult {
+struct ContainerLogVolume {
     id: String,
     success: bool,
-    data: Option<ContainerStatsResponse>,
     error: Option<String>,
+    #[serde(flatten)]
+    volume: LogVolume,
 }
 
+/// Sample the last `max_lines` log lines of each container (no follow) and report
+/// how much they log, for spotting noisy containers.
 #[tauri::command]
-async fn get_batch_stats(
+async fn get_container_logs_stats(
     ids: Vec<String>,
+    max_lines: Option<u32>,
     docker_state: State<'_, DockerState>,
-) -> Result<CommandResponse<Vec<ContainerStatsResult>>, String> {
-    let docker = match docker_state.client() {
+) -> Result<CommandResponse<Vec<ContainerLogVolume>>, String> {
+    let docker = match docker_state.client().await {
         Ok(d) => d,
-        Err(e) => return Ok(CommandResponse::err(e)),
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
     };
     for id in &ids {
         if let Err(e) = validate_docker_id(id) {
             return Ok(CommandResponse::err(e));
         }
     }
+    let max_lines = max_lines.unwrap_or(LOG_STATS_DEFAULT_LINES);
+    if max_lines == 0 {
+        return Ok(CommandResponse::err("max_lines must be positive"));
+    }
 
     let mut futures = FuturesUnordered::new();
 
     for id in ids {
         let docker_clone = docker.clone();
         futures.push(async move {
-            let mut stream = docker_clone.stats(
+            let mut stream = docker_clone.logs(
                 &id,
-                Some(StatsOptions {
-                    stream: false,
+                Some(LogsOptions {
+                    follow: false,
+                    stdout: true,
+                    stderr: true,
+                    tail: max_lines.to_string(),
                     ..Default::default()
                 }),
             );
-            match stream.next().await {
-                Some(Ok(stats)) => ContainerStatsResult {
-                    id,
-                    success: true,
-                    data: Some(stats),
-                    error: None,
-                },
-                Some(Err(e)) => ContainerStatsResult {
-                    id,
-                    success: false,
-                    data: None,
-                    error: Some(e.to_string()),
-                },
-                None => ContainerStatsResult {
-                    id,
-                    success: false,
-                    data: None,
-                    error: Some("No stats found".to_string()),
-                },
+
+            let mut volume = LogVolume::default();
+            let mut error = None;
+            while let Some(res) = stream.next().await {
+                match res {
+                    Ok(output) => volume.add(&output),
+                    Err(e) => {
+                        error = Some(e.to_string());
+                        break;
+                    }
+                }
+            }
+            ContainerLogVolume {
+                id,
+                success: error.is_none(),
+                error,
+                volume,
             }
         });
     }
@@ -453,298 +1240,5730 @@ async fn get_batch_stats(
     Ok(CommandResponse::ok(results))
 }
 
-#[tauri::command]
-async fn list_containers(
-    docker_state: State<'_, DockerState>,
-) -> Result<CommandResponse<Vec<bollard::models::ContainerSummary>>, String> {
-    let docker = match docker_state.client() {
-        Ok(d) => d,
-        Err(e) => return Ok(CommandResponse::err(e)),
-    };
+/// How long exec output may sit in the buffer before it is emitted (about one frame)
+const EXEC_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(16);
 
-    let options = Some(ListContainersOptions {
-        all: true,
-        ..Default::default()
-    });
+/// Emit buffered exec output immediately once it grows past this many bytes
+const EXEC_FLUSH_BYTES: usize = 32 * 1024;
 
-    match docker.list_containers(options).await {
-        Ok(containers) => Ok(CommandResponse::ok(containers)),
-        Err(e) => Ok(CommandResponse::err(e.to_string())),
-    }
+/// Payload of the `exec-exit-{session_id}` event emitted when an interactive session ends.
+/// `exit_code` is None if the exec could not be inspected.
+#[derive(Serialize, Clone)]
+struct ExecExitEvent {
+    exit_code: Option<i64>,
+    running: bool,
 }
 
-#[tauri::command]
-async fn container_action(
-    id: String,
-    action: String,
-    docker_state: State<'_, DockerState>,
-) -> Result<CommandResponse<()>, String> {
-    let docker = match docker_state.client() {
-        Ok(d) => d,
-        Err(e) => return Ok(CommandResponse::err(e)),
-    };
-    if let Err(e) = validate_docker_id(&id) {
-        return Ok(CommandResponse::err(e));
-    }
+/// Optional settings for an interactive exec session
+#[derive(Deserialize, Default)]
+struct StartExecSessionOptions {
+    /// User to run the shell as, e.g. "0" for root or "1000:1000"
+    user: Option<String>,
+    /// Absolute directory to start the shell in
+    working_dir: Option<String>,
+    /// Extra environment as `KEY=value` entries, e.g. `TERM=xterm-256color`
+    #[serde(default)]
+    env: Vec<String>,
+    /// Absolute path of the shell to run; None auto-detects bash, falling back to sh
+    shell: Option<String>,
+    /// Emit output as base64 `ExecOutputChunk`s instead of strings, so binary and
+    /// non-UTF-8 output reaches the terminal byte for byte
+    #[serde(default)]
+    raw_bytes: bool,
+}
 
-    let res = match action.as_str() {
-        "start" => docker.start_container(&id, None).await,
-        "stop" => docker.stop_container(&id, None).await,
-        "restart" => docker.restart_container(&id, None).await,
-        "remove" => docker.remove_container(&id, None).await,
-        _ => {
-            return Ok(CommandResponse::err("Invalid action"))
-        }
-    };
+/// Raw exec output, emitted on `exec-{session_id}` for sessions started with `raw_bytes`
+#[derive(Serialize, Clone)]
+struct ExecOutputChunk {
+    /// Base64 of the output bytes
+    data: String,
+    /// Always true, so the frontend can tell chunks from plain string output
+    base64: bool,
+}
 
-    match res {
-        Ok(_) => Ok(CommandResponse::ok_empty()),
-        Err(e) => Ok(CommandResponse::err(e.to_string())),
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard base64 with padding
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
     }
+    out
 }
 
-#[tauri::command]
-async fn list_images(
-    docker_state: State<'_, DockerState>,
-) -> Result<CommandResponse<Vec<bollard::models::ImageSummary>>, String> {
-    let docker = match docker_state.client() {
-        Ok(d) => d,
-        Err(e) => return Ok(CommandResponse::err(e)),
-    };
-
-    match docker.list_images(None::<ListImagesOptions>).await {
-        Ok(images) => Ok(CommandResponse::ok(images)),
-        Err(e) => Ok(CommandResponse::err(e.to_string())),
+/// Length of the prefix of `bytes` that doesn't end inside a UTF-8 sequence. A lead
+/// byte near the end whose continuation bytes haven't arrived yet is held back, so
+/// a character split across chunks isn't decoded as two replacement characters.
+/// Invalid bytes are not held back.
+pub fn utf8_complete_len(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let i = bytes.len() - back;
+        let needed = match bytes[i] {
+            0x80..=0xBF => continue,
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => 1,
+        };
+        return if needed > back { i } else { bytes.len() };
     }
+    bytes.len()
 }
 
-#[tauri::command]
-async fn list_volumes(
-    docker_state: State<'_, DockerState>,
-) -> Result<CommandResponse<Vec<bollard::models::Volume>>, String> {
-    let docker = match docker_state.client() {
-        Ok(d) => d,
-        Err(e) => return Ok(CommandResponse::err(e)),
+/// Emit exec output as a string, or as an `ExecOutputChunk` for `raw_bytes` sessions
+fn emit_exec_output(
+    app_handle: &tauri::AppHandle,
+    event_name: &str,
+    bytes: &[u8],
+    raw_bytes: bool,
+) {
+    let _ = if raw_bytes {
+        app_handle.emit(
+            event_name,
+            ExecOutputChunk {
+                data: base64_encode(bytes),
+                base64: true,
+            },
+        )
+    } else {
+        app_handle.emit(event_name, String::from_utf8_lossy(bytes).into_owned())
     };
+}
 
-    let mut volumes = match docker.list_volumes(None::<ListVolumesOptions>).await {
-        Ok(res) => res.volumes.unwrap_or_default(),
-        Err(e) => return Ok(CommandResponse::err(e.to_string())),
+/// Create an exec with `exec_opts`, attach to it and pump output to `exec-{session_id}`
+/// and input from `input_rx` until the process exits, then emit `exec-exit-{session_id}`.
+/// The exec id is published through `exec_id` so `exec_resize` can reach it.
+#[allow(clippy::too_many_arguments)]
+async fn run_attached_exec(
+    app_handle: tauri::AppHandle,
+    docker: Docker,
+    container_id: String,
+    session_id: String,
+    exec_opts: CreateExecOptions<String>,
+    (cols, rows): (u16, u16),
+    mut input_rx: mpsc::Receiver<String>,
+    exec_id_writer: Arc<Mutex<String>>,
+    raw_bytes: bool,
+) {
+    let event_name = format!("exec-{}", session_id);
+    let exec = match docker.create_exec(&container_id, exec_opts).await {
+        Ok(e) => e,
+        Err(e) => {
+            let message = format!("\r\nError creating exec: {}\r\n", e);
+            emit_exec_output(&app_handle, &event_name, message.as_bytes(), raw_bytes);
+            return;
+        }
     };
 
-    // Use CLI directly for usage data as API is unreliable for this specific data
-    log::info!("Fetching volume usage data via CLI");
-    let path = docker_state.get_path();
-    let mut cmd = std::process::Command::new("docker");
-    
-    if path != "default" && !path.is_empty() {
-            cmd.arg("-H").arg(format!("unix://{}", path));
-    }
-
-    cmd.args(&["system", "df", "-v", "--format", "{{json .Volumes}}"]);
+    // Store exec_id for later resize calls
+    *exec_id_writer.lock().unwrap() = exec.id.clone();
 
-    match cmd.output() {
-        Ok(output) => {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                // Output is a JSON array of objects
-                if let Ok(cli_items) = serde_json::from_str::<Vec<serde_json::Value>>(&stdout) {
-                    log::info!("Found {} volume usage items via CLI", cli_items.len());
-                    for item in cli_items {
-                            let name = item.get("Name")
-                            .or_else(|| item.get("name"))
-                            .and_then(|v| v.as_str());
+    // Start exec
+    let start_result = docker.start_exec(&exec.id, None).await;
 
-                        let usage_data = item.get("UsageData")
-                            .or_else(|| item.get("usageData"));
-                        
-                        // Also check for "Size" directly if UsageData is not nested
-                        // CLI format might put Size at top level
-                        let direct_size = item.get("Size").and_then(|v| v.as_str());
+    match start_result {
+        Ok(StartExecResults::Attached {
+            mut output,
+            mut input,
+        }) => {
+            // Resize TTY in background so it doesn't block starting the session
+            let docker_c = docker.clone();
+            let exec_id_c = exec.id.clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = docker_c
+                    .resize_exec(
+                        &exec_id_c,
+                        ResizeExecOptions {
+                            width: cols,
+                            height: rows,
+                        },
+                    )
+                    .await;
+            });
 
-                        if let Some(name) = name {
-                            if let Some(vol) = volumes.iter_mut().find(|v| v.name == name) {
-                                if let Some(usage_val) = usage_data {
-                                    if let Ok(usage) = serde_json::from_value::<bollard::models::VolumeUsageData>(usage_val.clone()) {
-                                        vol.usage_data = Some(usage);
-                                    }
-                                } else if let Some(size_str) = direct_size {
-                                    let size_bytes = parse_docker_size(size_str);
-                                    // Try to get ref count too
-                                    let ref_count = item.get("Links")
-                                        .and_then(|v| v.as_i64())
-                                        .unwrap_or(-1);
+            let app_clone = app_handle.clone();
 
-                                    let usage = bollard::models::VolumeUsageData {
-                                        size: size_bytes,
-                                        ref_count,
-                                    };
-                                    vol.usage_data = Some(usage);
-                                }
+            // Spawn task to read output, coalescing chunks so heavy output
+            // doesn't flood the webview with thousands of tiny events
+            let mut output_task = tauri::async_runtime::spawn(async move {
+                let mut buffer: Vec<u8> = Vec::new();
+                let mut flush_at: Option<tokio::time::Instant> = None;
+                // Emit everything up to the last complete character; the rest waits
+                // for the next chunk
+                let flush = |buffer: &mut Vec<u8>| {
+                    let complete = utf8_complete_len(buffer);
+                    if complete > 0 {
+                        emit_exec_output(&app_clone, &event_name, &buffer[..complete], raw_bytes);
+                        buffer.drain(..complete);
+                    }
+                };
+                loop {
+                    let deadline = flush_at.unwrap_or_else(tokio::time::Instant::now);
+                    tokio::select! {
+                        msg = output.next() => {
+                            let Some(Ok(msg)) = msg else { break };
+                            buffer.extend_from_slice(msg.as_ref());
+                            if buffer.len() >= EXEC_FLUSH_BYTES {
+                                flush(&mut buffer);
+                                flush_at = None;
+                            } else if flush_at.is_none() {
+                                flush_at =
+                                    Some(tokio::time::Instant::now() + EXEC_FLUSH_INTERVAL);
                             }
                         }
+                        _ = tokio::time::sleep_until(deadline), if flush_at.is_some() => {
+                            flush(&mut buffer);
+                            flush_at = None;
+                        }
+                    }
+                }
+                // Flush whatever is left so the last lines before exit aren't lost
+                if !buffer.is_empty() {
+                    emit_exec_output(&app_clone, &event_name, &buffer, raw_bytes);
+                }
+            });
+
+            // Read input from channel and send to container until the shell exits
+            loop {
+                tokio::select! {
+                    _ = &mut output_task => break,
+                    data = input_rx.recv() => {
+                        let Some(data) = data else { break };
+                        use tokio::io::AsyncWriteExt;
+                        if input.write_all(data.as_bytes()).await.is_err() {
+                            break;
+                        }
                     }
-                } else {
-                    log::warn!("Failed to parse CLI JSON output: {}", stdout);
                 }
-            } else {
-                    log::warn!("CLI command failed: {}", String::from_utf8_lossy(&output.stderr));
             }
-        },
+
+            output_task.abort();
+
+            // Tell the frontend why the session ended
+            let exit = match docker.inspect_exec(&exec.id).await {
+                Ok(info) => ExecExitEvent {
+                    exit_code: info.exit_code,
+                    running: info.running.unwrap_or(false),
+                },
+                Err(e) => {
+                    log::warn!("Failed to inspect exec {}: {}", exec.id, e);
+                    ExecExitEvent {
+                        exit_code: None,
+                        running: false,
+                    }
+                }
+            };
+            let _ = app_handle.emit(&format!("exec-exit-{}", session_id), exit);
+        }
+        Ok(StartExecResults::Detached) => {
+            emit_exec_output(
+                &app_handle,
+                &event_name,
+                b"\r\nExec started in detached mode\r\n",
+                raw_bytes,
+            );
+        }
         Err(e) => {
-            log::warn!("Failed to execute docker CLI: {}", e);
+            let message = format!("\r\nError starting exec: {}\r\n", e);
+            emit_exec_output(&app_handle, &event_name, message.as_bytes(), raw_bytes);
+        }
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn start_exec(
+    session_id: String,
+    container_id: String,
+    cols: u16,
+    rows: u16,
+    options: Option<StartExecSessionOptions>,
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+    state: State<'_, ExecState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&container_id) {
+        return Ok(CommandResponse::err(e));
+    }
+    let StartExecSessionOptions {
+        user,
+        working_dir,
+        env,
+        shell,
+        raw_bytes,
+    } = options.unwrap_or_default();
+    if let Some(ref sh) = shell {
+        if let Err(e) = validate_shell_path(sh) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+    if let Some(ref u) = user {
+        if let Err(e) = validate_exec_user(u) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+    if let Some(ref dir) = working_dir {
+        if let Err(e) = validate_exec_working_dir(dir) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+    for entry in &env {
+        if let Err(e) = validate_env_entry(entry) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+    let cols = if cols == 0 { 80 } else { cols };
+    let rows = if rows == 0 { 24 } else { rows };
+
+    let session_id_clone = session_id.clone();
+    let docker_for_resize = docker.clone();
+    let container_id_clone = container_id.clone();
+
+    let (input_tx, input_rx) = mpsc::channel::<String>(100);
+    let exec_id_shared: Arc<Mutex<String>> = Arc::new(Mutex::new(String::new()));
+    let exec_id_writer = exec_id_shared.clone();
+
+    let cmd = match shell {
+        Some(ref sh) => vec![sh.as_str()],
+        None => vec![
+            "/bin/sh",
+            "-c",
+            "if command -v bash > /dev/null; then exec bash; else exec sh; fi",
+        ],
+    };
+
+    // Create exec instance
+    let exec_opts = CreateExecOptions {
+        attach_stdout: Some(true),
+        attach_stderr: Some(true),
+        attach_stdin: Some(true),
+        tty: Some(true),
+        cmd: Some(cmd.into_iter().map(str::to_string).collect()),
+        user,
+        working_dir,
+        env: (!env.is_empty()).then_some(env),
+        ..Default::default()
+    };
+    let session_exec_opts = exec_opts.clone();
+
+    let handle = tauri::async_runtime::spawn(async move {
+        // An explicitly requested shell must exist; don't silently fall back to another one
+        if let Some(ref sh) = shell {
+            let probe = CreateExecOptions {
+                cmd: Some(vec![sh.clone(), "-c".to_string(), "exit 0".to_string()]),
+                user: exec_opts.user.clone(),
+                ..Default::default()
+            };
+            let event_name = format!("exec-{}", session_id_clone);
+            match exec_to_completion(&docker, &container_id, probe).await {
+                // 126/127: the runtime couldn't execute the binary
+                Ok(out) if out.exit_code == 126 || out.exit_code == 127 => {
+                    let message = format!("\r\nShell {} not found in container\r\n", sh);
+                    emit_exec_output(&app_handle, &event_name, message.as_bytes(), raw_bytes);
+                    return;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    let message = format!("\r\nError creating exec: {}\r\n", e);
+                    emit_exec_output(&app_handle, &event_name, message.as_bytes(), raw_bytes);
+                    return;
+                }
+            }
         }
+
+        run_attached_exec(
+            app_handle,
+            docker,
+            container_id,
+            session_id_clone,
+            exec_opts,
+            (cols, rows),
+            input_rx,
+            exec_id_writer,
+            raw_bytes,
+        )
+        .await;
+    });
+
+    let session = ExecSession {
+        handle,
+        input_tx,
+        docker: docker_for_resize,
+        exec_id: exec_id_shared,
+        container_id: container_id_clone,
+        exec_opts: session_exec_opts,
+        cols,
+        rows,
+        raw_bytes,
+    };
+    let mut lock = state.inner().0.lock().unwrap();
+    if let Some(old_session) = lock.insert(session_id, session) {
+        old_session.handle.abort();
     }
 
-    Ok(CommandResponse::ok(volumes))
+    Ok(CommandResponse::ok_empty())
 }
 
-/// Helper to parse Docker's human-readable size strings (e.g. "10MB", "5.5GB", "1024B")
-fn parse_docker_size(s: &str) -> i64 {
-    let s = s.trim();
-    if s.is_empty() { return 0; }
+/// Start an exec session running `cmd` instead of an interactive shell. Output streams on
+/// `exec-{session_id}` and the session accepts `exec_input`/`exec_resize`/`stop_exec`
+/// like one opened with `start_exec`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn start_exec_command(
+    session_id: String,
+    container_id: String,
+    cmd: Vec<String>,
+    tty: bool,
+    cols: u16,
+    rows: u16,
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+    state: State<'_, ExecState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&container_id) {
+        return Ok(CommandResponse::err(e));
+    }
+    if cmd.first().is_none_or(|c| c.trim().is_empty()) {
+        return Ok(CommandResponse::err(
+            "Command must not be empty".to_string(),
+        ));
+    }
+    let cols = if cols == 0 { 80 } else { cols };
+    let rows = if rows == 0 { 24 } else { rows };
 
-    let digits: String = s.chars()
-        .take_while(|c| c.is_digit(10) || *c == '.')
-        .collect();
-    
-    let unit = s[digits.len()..].trim();
-    let val: f64 = digits.parse().unwrap_or(0.0);
+    let docker_for_resize = docker.clone();
+    let (input_tx, input_rx) = mpsc::channel::<String>(100);
+    let exec_id_shared: Arc<Mutex<String>> = Arc::new(Mutex::new(String::new()));
+    let exec_id_writer = exec_id_shared.clone();
 
-    let multiplier: f64 = match unit {
-        "B" => 1.0,
-        "kB" | "KB" | "kb" => 1000.0,
-        "MB" | "mb" => 1000.0 * 1000.0,
-        "GB" | "gb" => 1000.0 * 1000.0 * 1000.0,
-        "TB" | "tb" => 1000.0 * 1000.0 * 1000.0 * 1000.0,
-        "KiB" | "KIB" => 1024.0,
-        "MiB" | "MIB" => 1024.0 * 1024.0,
-        "GiB" | "GIB" => 1024.0 * 1024.0 * 1024.0,
-        "TiB" | "TIB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
-        _ => 1.0,
+    let exec_opts = CreateExecOptions {
+        attach_stdout: Some(true),
+        attach_stderr: Some(true),
+        attach_stdin: Some(true),
+        tty: Some(tty),
+        cmd: Some(cmd),
+        ..Default::default()
+    };
+    let handle = tauri::async_runtime::spawn(run_attached_exec(
+        app_handle,
+        docker,
+        container_id.clone(),
+        session_id.clone(),
+        exec_opts.clone(),
+        (cols, rows),
+        input_rx,
+        exec_id_writer,
+        false,
+    ));
+
+    let mut lock = state.inner().0.lock().unwrap();
+    if let Some(old_session) = lock.insert(
+        session_id,
+        ExecSession {
+            handle,
+            input_tx,
+            docker: docker_for_resize,
+            exec_id: exec_id_shared,
+            container_id,
+            exec_opts,
+            cols,
+            rows,
+            raw_bytes: false,
+        },
+    ) {
+        old_session.handle.abort();
+    }
+
+    Ok(CommandResponse::ok_empty())
+}
+
+#[tauri::command]
+fn exec_input(
+    session_id: String,
+    data: String,
+    state: State<'_, ExecState>,
+) -> CommandResponse<()> {
+    let lock = state.inner().0.lock().unwrap();
+    if let Some(session) = lock.get(&session_id) {
+        let tx = session.input_tx.clone();
+        drop(lock);
+        let _ = tx.blocking_send(data);
+    }
+    CommandResponse {
+        success: true,
+        data: None,
+        error: None,
+        error_code: None,
+    }
+}
+
+/// Bytes a terminal sends for a control key. With a TTY the kernel's line discipline
+/// in the container turns them into the signal (or EOF) for the foreground process.
+fn exec_control_sequence(signal: &str) -> Result<&'static str, String> {
+    match signal {
+        "SIGINT" => Ok("\x03"),
+        "EOF" => Ok("\x04"),
+        "SIGTSTP" => Ok("\x1a"),
+        "SIGQUIT" => Ok("\x1c"),
+        _ => Err(format!(
+            "Unknown signal: {} (expected SIGINT, EOF, SIGTSTP or SIGQUIT)",
+            signal
+        )),
+    }
+}
+
+/// Send a named control key (Ctrl-C, Ctrl-D, Ctrl-Z, Ctrl-\) to an exec session's
+/// input, so the UI doesn't have to encode control bytes itself
+#[tauri::command]
+fn exec_signal(
+    session_id: String,
+    signal: String,
+    state: State<'_, ExecState>,
+) -> CommandResponse<()> {
+    let bytes = match exec_control_sequence(&signal) {
+        Ok(b) => b,
+        Err(e) => return CommandResponse::err(e),
+    };
+    let tx = match state.inner().0.lock().unwrap().get(&session_id) {
+        Some(session) => session.input_tx.clone(),
+        None => {
+            return CommandResponse::err_coded(
+                "NOT_FOUND",
+                format!("No exec session {}", session_id),
+            )
+        }
+    };
+    let _ = tx.blocking_send(bytes.to_string());
+    CommandResponse::ok_empty()
+}
+
+#[tauri::command]
+fn exec_resize(
+    session_id: String,
+    cols: u16,
+    rows: u16,
+    state: State<'_, ExecState>,
+) -> CommandResponse<()> {
+    let (docker, exec_id) = {
+        let mut lock = state.inner().0.lock().unwrap();
+        match lock.get_mut(&session_id) {
+            Some(s) => {
+                // Remembered so a restarted shell comes back at the current size
+                s.cols = cols;
+                s.rows = rows;
+                let eid = s.exec_id.lock().unwrap().clone();
+                if eid.is_empty() {
+                    return CommandResponse::ok_empty();
+                }
+                (s.docker.clone(), eid)
+            }
+            None => return CommandResponse::ok_empty(),
+        }
+    };
+    tauri::async_runtime::spawn(async move {
+        let _ = docker.resize_exec(&exec_id, ResizeExecOptions { width: cols, height: rows }).await;
+    });
+    CommandResponse::ok_empty()
+}
+
+#[tauri::command]
+fn stop_exec(session_id: String, state: State<'_, ExecState>) -> CommandResponse<()> {
+    let mut lock = state.inner().0.lock().unwrap();
+    if let Some(session) = lock.remove(&session_id) {
+        session.handle.abort();
+    }
+    CommandResponse {
+        success: true,
+        data: None,
+        error: None,
+        error_code: None,
+    }
+}
+
+/// State of an exec session as seen by `get_exec_status`
+#[derive(Serialize, Default)]
+struct ExecStatus {
+    /// The session exists and its process hasn't exited (or is still starting)
+    alive: bool,
+    /// The session exists but its exec hasn't been created yet
+    starting: bool,
+    running: bool,
+    exit_code: Option<i64>,
+    pid: Option<i64>,
+}
+
+/// Whether an exec session is still alive, so the UI can notice shells that died
+/// without `stop_exec`. Unknown sessions report `alive: false` rather than an error.
+#[tauri::command]
+async fn get_exec_status(
+    session_id: String,
+    state: State<'_, ExecState>,
+) -> Result<CommandResponse<ExecStatus>, String> {
+    let (docker, exec_id) = match state.inner().0.lock().unwrap().get(&session_id) {
+        Some(s) => (s.docker.clone(), s.exec_id.lock().unwrap().clone()),
+        None => return Ok(CommandResponse::ok(ExecStatus::default())),
+    };
+    if exec_id.is_empty() {
+        return Ok(CommandResponse::ok(ExecStatus {
+            alive: true,
+            starting: true,
+            ..Default::default()
+        }));
+    }
+
+    match docker.inspect_exec(&exec_id).await {
+        Ok(info) => {
+            let running = info.running.unwrap_or(false);
+            Ok(CommandResponse::ok(ExecStatus {
+                alive: running,
+                starting: false,
+                running,
+                exit_code: info.exit_code,
+                pid: info.pid,
+            }))
+        }
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Start the session's exec again on the same container, at the last known size,
+/// after the shell exited or hung. Output keeps flowing on `exec-{session_id}`, so
+/// the frontend only needs to listen once; a banner marks where the new shell begins.
+#[tauri::command]
+async fn restart_exec(
+    session_id: String,
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+    state: State<'_, ExecState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    let mut lock = state.inner().0.lock().unwrap();
+    let Some(session) = lock.get_mut(&session_id) else {
+        return Ok(CommandResponse::err_coded(
+            "NOT_FOUND",
+            format!("No exec session {}", session_id),
+        ));
+    };
+    session.handle.abort();
+
+    let (input_tx, input_rx) = mpsc::channel::<String>(100);
+    let exec_id_shared: Arc<Mutex<String>> = Arc::new(Mutex::new(String::new()));
+
+    emit_exec_output(
+        &app_handle,
+        &format!("exec-{}", session_id),
+        b"\r\n--- Reconnecting shell ---\r\n",
+        session.raw_bytes,
+    );
+    session.handle = tauri::async_runtime::spawn(run_attached_exec(
+        app_handle,
+        docker.clone(),
+        session.container_id.clone(),
+        session_id.clone(),
+        session.exec_opts.clone(),
+        (session.cols, session.rows),
+        input_rx,
+        exec_id_shared.clone(),
+        session.raw_bytes,
+    ));
+    session.input_tx = input_tx;
+    session.docker = docker;
+    session.exec_id = exec_id_shared;
+
+    Ok(CommandResponse::ok_empty())
+}
+
+struct AttachSession {
+    handle: tauri::async_runtime::JoinHandle<()>,
+    input_tx: mpsc::Sender<String>,
+    docker: Docker,
+    container_id: String,
+}
+
+struct AttachState(Mutex<HashMap<String, AttachSession>>);
+
+/// Attach to a running container's main process instead of spawning a new one, e.g. to
+/// take over a REPL. Output streams on `attach-{session_id}`; `attach-closed-{session_id}`
+/// fires when the stream ends (process exited or detached with the container's detach keys).
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn attach_container(
+    session_id: String,
+    id: String,
+    cols: u16,
+    rows: u16,
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+    state: State<'_, AttachState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
     };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    let cols = if cols == 0 { 80 } else { cols };
+    let rows = if rows == 0 { 24 } else { rows };
+
+    let (input_tx, mut input_rx) = mpsc::channel::<String>(100);
+    let docker_clone = docker.clone();
+    let container_id = id.clone();
+    let session_id_clone = session_id.clone();
+
+    let handle = tauri::async_runtime::spawn(async move {
+        let event_name = format!("attach-{}", session_id_clone);
+        let options = bollard::query_parameters::AttachContainerOptions {
+            stream: true,
+            stdout: true,
+            stderr: true,
+            stdin: true,
+            ..Default::default()
+        };
+        let AttachContainerResults {
+            mut output,
+            mut input,
+        } = match docker_clone
+            .attach_container(&container_id, Some(options))
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                let _ = app_handle.emit(&event_name, format!("\r\nError attaching: {}\r\n", e));
+                return;
+            }
+        };
+
+        // Only TTY containers can be resized; the error for others is harmless
+        let _ = docker_clone
+            .resize_container_tty(
+                &container_id,
+                bollard::query_parameters::ResizeContainerTTYOptions {
+                    w: cols.into(),
+                    h: rows.into(),
+                },
+            )
+            .await;
+
+        let app_clone = app_handle.clone();
+        let output_event = event_name.clone();
+        let mut output_task = tauri::async_runtime::spawn(async move {
+            while let Some(Ok(msg)) = output.next().await {
+                let _ = app_clone.emit(&output_event, msg.to_string());
+            }
+        });
+
+        loop {
+            tokio::select! {
+                _ = &mut output_task => break,
+                data = input_rx.recv() => {
+                    let Some(data) = data else { break };
+                    use tokio::io::AsyncWriteExt;
+                    if input.write_all(data.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        output_task.abort();
+        let _ = app_handle.emit(&format!("attach-closed-{}", session_id_clone), ());
+    });
+
+    let mut lock = state.inner().0.lock().unwrap();
+    if let Some(old_session) = lock.insert(
+        session_id,
+        AttachSession {
+            handle,
+            input_tx,
+            docker,
+            container_id: id,
+        },
+    ) {
+        old_session.handle.abort();
+    }
+
+    Ok(CommandResponse::ok_empty())
+}
+
+#[tauri::command]
+fn attach_input(
+    session_id: String,
+    data: String,
+    state: State<'_, AttachState>,
+) -> CommandResponse<()> {
+    let tx = match state.inner().0.lock().unwrap().get(&session_id) {
+        Some(session) => session.input_tx.clone(),
+        None => return CommandResponse::ok_empty(),
+    };
+    let _ = tx.blocking_send(data);
+    CommandResponse::ok_empty()
+}
+
+#[tauri::command]
+fn attach_resize(
+    session_id: String,
+    cols: u16,
+    rows: u16,
+    state: State<'_, AttachState>,
+) -> CommandResponse<()> {
+    let (docker, container_id) = match state.inner().0.lock().unwrap().get(&session_id) {
+        Some(s) => (s.docker.clone(), s.container_id.clone()),
+        None => return CommandResponse::ok_empty(),
+    };
+    tauri::async_runtime::spawn(async move {
+        let options = bollard::query_parameters::ResizeContainerTTYOptions {
+            w: cols.into(),
+            h: rows.into(),
+        };
+        let _ = docker.resize_container_tty(&container_id, options).await;
+    });
+    CommandResponse::ok_empty()
+}
+
+/// Detach by dropping the connection; the container keeps running
+#[tauri::command]
+fn stop_attach(session_id: String, state: State<'_, AttachState>) -> CommandResponse<()> {
+    if let Some(session) = state.inner().0.lock().unwrap().remove(&session_id) {
+        session.handle.abort();
+    }
+    CommandResponse::ok_empty()
+}
+
+/// Output of a non-interactive exec run to completion
+#[derive(Serialize)]
+struct ExecOutput {
+    stdout: String,
+    stderr: String,
+    exit_code: i64,
+}
+
+#[tauri::command]
+async fn run_exec(
+    container_id: String,
+    cmd: Vec<String>,
+    user: Option<String>,
+    working_dir: Option<String>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<ExecOutput>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&container_id) {
+        return Ok(CommandResponse::err(e));
+    }
+    if cmd.is_empty() {
+        return Ok(CommandResponse::err("Command cannot be empty"));
+    }
+    if let Some(ref u) = user {
+        if let Err(e) = validate_exec_user(u) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+    if let Some(ref dir) = working_dir {
+        if let Err(e) = validate_exec_working_dir(dir) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+
+    let exec_opts = CreateExecOptions {
+        cmd: Some(cmd),
+        user,
+        working_dir,
+        ..Default::default()
+    };
+
+    match exec_to_completion(&docker, &container_id, exec_opts).await {
+        Ok(output) => Ok(CommandResponse::ok(output)),
+        Err(e) => Ok(CommandResponse::err(e)),
+    }
+}
+
+/// Run a non-TTY exec to completion and collect its output and exit code.
+/// Attach/TTY settings in `exec_opts` are overridden.
+async fn exec_to_completion(
+    docker: &Docker,
+    container_id: &str,
+    exec_opts: CreateExecOptions<String>,
+) -> Result<ExecOutput, String> {
+    let exec_opts = CreateExecOptions {
+        attach_stdout: Some(true),
+        attach_stderr: Some(true),
+        attach_stdin: Some(false),
+        tty: Some(false),
+        ..exec_opts
+    };
+
+    let exec = docker
+        .create_exec(container_id, exec_opts)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Collect raw bytes so multi-byte characters split across frames decode correctly
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    match docker.start_exec(&exec.id, None).await {
+        Ok(StartExecResults::Attached { mut output, .. }) => {
+            while let Some(msg) = output.next().await {
+                match msg.map_err(|e| e.to_string())? {
+                    LogOutput::StdOut { message } => stdout.extend_from_slice(&message),
+                    LogOutput::StdErr { message } => stderr.extend_from_slice(&message),
+                    _ => {}
+                }
+            }
+        }
+        Ok(StartExecResults::Detached) => return Err("Exec started in detached mode".to_string()),
+        Err(e) => return Err(e.to_string()),
+    }
+
+    // The exit code is only available from inspect once the output stream has ended
+    let info = docker
+        .inspect_exec(&exec.id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(ExecOutput {
+        stdout: String::from_utf8_lossy(&stdout).to_string(),
+        stderr: String::from_utf8_lossy(&stderr).to_string(),
+        exit_code: info.exit_code.unwrap_or(-1),
+    })
+}
+
+/// Shells `detect_container_shell` looks for, in order of preference
+const SHELL_CANDIDATES: [&str; 4] = ["bash", "sh", "ash", "zsh"];
+
+/// Shell used when none can be detected
+const FALLBACK_SHELL: &str = "/bin/sh";
+
+/// First absolute path in `command -v` output; builtins and aliases print bare names
+fn first_shell_path(stdout: &str) -> Option<String> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with('/') && validate_shell_path(line).is_ok())
+        .map(str::to_string)
+}
+
+/// The shell a terminal in this container would get: the first of bash, sh, ash and
+/// zsh that exists. Falls back to /bin/sh when detection fails, e.g. because the
+/// container is stopped.
+#[tauri::command]
+async fn detect_container_shell(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let script = format!(
+        "for s in {}; do command -v \"$s\" && break; done",
+        SHELL_CANDIDATES.join(" ")
+    );
+    let exec_opts = CreateExecOptions {
+        cmd: Some(vec!["/bin/sh".to_string(), "-c".to_string(), script]),
+        ..Default::default()
+    };
+    let shell = match exec_to_completion(&docker, &id, exec_opts).await {
+        Ok(out) => first_shell_path(&out.stdout),
+        Err(e) => {
+            log::warn!("Shell detection in {} failed: {}", id, e);
+            None
+        }
+    };
+    Ok(CommandResponse::ok(
+        shell.unwrap_or_else(|| FALLBACK_SHELL.to_string()),
+    ))
+}
+
+/// Validate a shell path for an exec session: absolute, no `..`, and no shell metacharacters.
+fn validate_shell_path(shell: &str) -> Result<(), String> {
+    if !shell.starts_with('/') {
+        return Err("Shell must be an absolute path".to_string());
+    }
+    if shell.len() > 256 {
+        return Err("Shell path too long".to_string());
+    }
+    if shell.contains("..")
+        || !shell
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/_-.+".contains(c))
+    {
+        return Err(format!("Invalid shell path: {}", shell));
+    }
+    Ok(())
+}
+
+/// Validate the user an exec runs as: a name or numeric uid, optionally followed by
+/// `:group` / `:gid` (e.g. `root`, `1000`, `1000:1000`).
+fn validate_exec_user(user: &str) -> Result<(), String> {
+    if user.is_empty() {
+        return Err("User cannot be empty".to_string());
+    }
+    if user.len() > 64 {
+        return Err("User too long".to_string());
+    }
+    let mut parts = user.split(':');
+    let valid_part = |p: &str| {
+        !p.is_empty()
+            && !p.starts_with('-')
+            && p.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c))
+    };
+    let name_ok = parts.next().is_some_and(valid_part);
+    let group_ok = parts.next().is_none_or(valid_part);
+    if !name_ok || !group_ok || parts.next().is_some() {
+        return Err(format!("Invalid user: {}", user));
+    }
+    Ok(())
+}
+
+/// Validate an exec working directory: must be an absolute path without control characters.
+fn validate_exec_working_dir(dir: &str) -> Result<(), String> {
+    if !dir.starts_with('/') {
+        return Err("Working directory must be an absolute path".to_string());
+    }
+    if dir.len() > 4096 {
+        return Err("Working directory too long".to_string());
+    }
+    if dir.chars().any(|c| c.is_control()) {
+        return Err(format!("Invalid working directory: {}", dir));
+    }
+    Ok(())
+}
+
+/// Validate an environment entry of the form `KEY=value`.
+/// Keys follow the POSIX shell rules (letters, digits, underscore; not starting with a digit).
+fn validate_env_entry(entry: &str) -> Result<(), String> {
+    let Some((key, value)) = entry.split_once('=') else {
+        return Err(format!(
+            "Invalid environment entry (expected KEY=value): {}",
+            entry
+        ));
+    };
+    let mut chars = key.chars();
+    let first_ok = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if !first_ok || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("Invalid environment variable name: {}", key));
+    }
+    if value.contains('\0') {
+        return Err(format!("Invalid value for environment variable {}", key));
+    }
+    Ok(())
+}
+
+/// Validate a Docker resource identifier (container ID/name, image ref, volume name).
+/// Allows hex IDs (12/64 chars), names with alphanumeric + `-_./:@`, and rejects
+/// anything with shell metacharacters or suspicious patterns: a leading `-` (flag-like),
+/// `..` (path traversal), empty path segments (`//`) and a dangling trailing `:`.
+fn validate_docker_id(id: &str) -> Result<(), String> {
+    if id.is_empty() {
+        return Err("Identifier cannot be empty".to_string());
+    }
+    if id.len() > 256 {
+        return Err("Identifier too long".to_string());
+    }
+    // Allow: alphanumeric, hyphen, underscore, dot, slash, colon, @ (for image digests)
+    if !id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_./:@".contains(c))
+    {
+        return Err(format!("Invalid identifier: {}", id));
+    }
+    if id.starts_with('-') || id.contains("..") || id.contains("//") || id.ends_with(':') {
+        return Err(format!("Invalid identifier: {}", id));
+    }
+    Ok(())
+}
+
+/// Components of an image reference: `[registry[:port]/]repository[:tag][@digest]`
+#[derive(PartialEq, Debug)]
+struct ImageRef {
+    registry: Option<String>,
+    repository: String,
+    tag: Option<String>,
+    digest: Option<String>,
+}
+
+/// Parse an image reference following the distribution reference grammar.
+/// The first path component is treated as a registry when it contains `.` or `:`
+/// or is `localhost`, matching how the docker CLI decides.
+fn parse_image_ref(reference: &str) -> Result<ImageRef, String> {
+    let invalid = || format!("Invalid image reference: {}", reference);
+
+    if reference.is_empty() {
+        return Err("Image reference cannot be empty".to_string());
+    }
+    if reference.len() > 256 {
+        return Err("Image reference too long".to_string());
+    }
+
+    let (name_and_tag, digest) = match reference.split_once('@') {
+        Some((n, d)) => (n, Some(d)),
+        None => (reference, None),
+    };
+    if let Some(digest) = digest {
+        let (algorithm, encoded) = digest.split_once(':').ok_or_else(invalid)?;
+        let algorithm_ok = !algorithm.is_empty()
+            && algorithm
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+        let encoded_ok = !encoded.is_empty() && encoded.chars().all(|c| c.is_ascii_hexdigit());
+        if !algorithm_ok || !encoded_ok {
+            return Err(invalid());
+        }
+    }
+
+    // A tag is a colon after the last slash; earlier colons belong to a registry port
+    let last_slash = name_and_tag.rfind('/').map_or(0, |i| i + 1);
+    let (name, tag) = match name_and_tag[last_slash..].rfind(':') {
+        Some(i) => (
+            &name_and_tag[..last_slash + i],
+            Some(&name_and_tag[last_slash + i + 1..]),
+        ),
+        None => (name_and_tag, None),
+    };
+    if let Some(tag) = tag {
+        let mut chars = tag.chars();
+        let first_ok = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !first_ok
+            || tag.len() > 128
+            || !chars.all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c))
+        {
+            return Err(format!("Invalid image tag: {}", tag));
+        }
+    }
+
+    let mut components: Vec<&str> = name.split('/').collect();
+    let registry = if components.len() > 1
+        && (components[0].contains('.')
+            || components[0].contains(':')
+            || components[0] == "localhost")
+    {
+        let registry = components.remove(0);
+        let (host, port) = match registry.split_once(':') {
+            Some((h, p)) => (h, Some(p)),
+            None => (registry, None),
+        };
+        let host_ok = !host.is_empty()
+            && !host.starts_with(['-', '.'])
+            && host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-.".contains(c));
+        let port_ok = port.is_none_or(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()));
+        if !host_ok || !port_ok {
+            return Err(invalid());
+        }
+        Some(registry.to_string())
+    } else {
+        None
+    };
+
+    // Path components: lowercase alphanumerics joined by `.`, `_`, `__` or runs of `-`
+    let component_ok = |c: &str| {
+        let bytes = c.as_bytes();
+        !c.is_empty()
+            && bytes[0].is_ascii_alphanumeric()
+            && bytes[bytes.len() - 1].is_ascii_alphanumeric()
+            && c.chars()
+                .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || "._-".contains(ch))
+            && !c.contains("..")
+            && !c.contains("___")
+            && !c.contains("._")
+            && !c.contains("_.")
+    };
+    if !components.iter().all(|c| component_ok(c)) {
+        return Err(invalid());
+    }
+
+    Ok(ImageRef {
+        registry,
+        repository: components.join("/"),
+        tag: tag.map(str::to_string),
+        digest: digest.map(str::to_string),
+    })
+}
+
+/// Validate an image reference (for pull/tag/remove). Container and volume names
+/// go through `validate_docker_id` instead.
+fn validate_image_ref(reference: &str) -> Result<(), String> {
+    parse_image_ref(reference).map(|_| ())
+}
+
+/// Validate a registry mirror prefix: a registry host with optional port and path,
+/// without scheme, tag or digest, e.g. "mirror.corp" or "mirror.corp:5000/hub"
+fn validate_registry_mirror(mirror: &str) -> Result<(), String> {
+    let invalid = || format!("Invalid registry mirror: {}", mirror);
+    if mirror.contains("://") || mirror.contains('@') || mirror.ends_with('/') {
+        return Err(invalid());
+    }
+    match parse_image_ref(&format!("{}/image", mirror)) {
+        Ok(parsed) if parsed.registry.is_some() && parsed.tag.is_none() => Ok(()),
+        _ => Err(invalid()),
+    }
+}
+
+/// Rewrite a Docker Hub reference to pull through `mirror`, the way a pull-through
+/// cache expects: `nginx:1.27` becomes `mirror.corp/library/nginx:1.27`. References
+/// naming another registry are left alone and return None.
+fn mirrored_image_ref(image: &str, mirror: &str) -> Option<String> {
+    let parsed = parse_image_ref(image).ok()?;
+    if parsed
+        .registry
+        .as_deref()
+        .is_some_and(|r| r != "docker.io" && r != "index.docker.io")
+    {
+        return None;
+    }
+    let mut mirrored = if parsed.repository.contains('/') {
+        format!("{}/{}", mirror, parsed.repository)
+    } else {
+        format!("{}/library/{}", mirror, parsed.repository)
+    };
+    if let Some(tag) = parsed.tag {
+        mirrored = format!("{}:{}", mirrored, tag);
+    }
+    if let Some(digest) = parsed.digest {
+        mirrored = format!("{}@{}", mirrored, digest);
+    }
+    Some(mirrored)
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+struct CommandResponse<T> {
+    success: bool,
+    data: Option<T>,
+    error: Option<String>,
+    /// Machine-readable error category (see `docker_error_code`) so the UI can
+    /// react to e.g. "CONFLICT" without parsing `error`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_code: Option<String>,
+}
+
+impl<T> CommandResponse<T> {
+    fn ok(data: T) -> Self {
+        Self {
+            success: true,
+            data: Some(data),
+            error: None,
+            error_code: None,
+        }
+    }
+
+    fn ok_empty() -> Self {
+        Self {
+            success: true,
+            data: None,
+            error: None,
+            error_code: None,
+        }
+    }
+
+    fn err(msg: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(msg.into()),
+            error_code: None,
+        }
+    }
+
+    fn err_coded(code: impl Into<String>, msg: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(msg.into()),
+            error_code: Some(code.into()),
+        }
+    }
+
+    /// Error response for a failed Docker API call, tagged with its category
+    fn docker_err(e: bollard::errors::Error) -> Self {
+        Self::err_coded(docker_error_code(&e), e.to_string())
+    }
+
+    /// Error response for when no Docker client could be had, i.e. the daemon is
+    /// unreachable
+    fn connection_err(msg: impl Into<String>) -> Self {
+        Self::err_coded("CONNECTION", msg)
+    }
+}
+
+/// Categorise a Docker API error: "NOT_FOUND", "CONFLICT", "PERMISSION_DENIED",
+/// "BAD_REQUEST", "NOT_MODIFIED", "DAEMON_ERROR", "CONNECTION", "TIMEOUT",
+/// "INVALID_RESPONSE" or "UNKNOWN".
+fn docker_error_code(e: &bollard::errors::Error) -> &'static str {
+    use bollard::errors::Error;
+
+    match e {
+        Error::DockerResponseServerError { status_code, .. } => match status_code {
+            304 => "NOT_MODIFIED",
+            400 => "BAD_REQUEST",
+            401 | 403 => "PERMISSION_DENIED",
+            404 => "NOT_FOUND",
+            409 => "CONFLICT",
+            500..=599 => "DAEMON_ERROR",
+            _ => "UNKNOWN",
+        },
+        Error::IOError { err } if err.kind() == std::io::ErrorKind::PermissionDenied => {
+            "PERMISSION_DENIED"
+        }
+        Error::IOError { .. }
+        | Error::SocketNotFoundError(_)
+        | Error::HyperResponseError { .. }
+        | Error::HyperLegacyError { .. } => "CONNECTION",
+        Error::RequestTimeoutError => "TIMEOUT",
+        Error::JsonDataError { .. } | Error::JsonSerdeError { .. } => "INVALID_RESPONSE",
+        _ => "UNKNOWN",
+    }
+}
+
+/// Kind of the I/O error behind `e`, looking through the errors hyper wraps it in
+fn io_error_kind(e: &bollard::errors::Error) -> Option<std::io::ErrorKind> {
+    if let bollard::errors::Error::IOError { err } = e {
+        return Some(err.kind());
+    }
+    let mut source = std::error::Error::source(e);
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            return Some(io.kind());
+        }
+        source = err.source();
+    }
+    None
+}
+
+/// Whether `e` means the socket to the daemon went stale (broken pipe, reset or closed
+/// connection) rather than the daemon being down or refusing the request. On macOS
+/// this happens after App Nap or sleep suspended the app while the connection idled.
+fn is_stale_connection_error(e: &bollard::errors::Error) -> bool {
+    use bollard::errors::Error;
+    use std::io::ErrorKind;
+
+    let stale_kind = |kind: ErrorKind| {
+        matches!(
+            kind,
+            ErrorKind::BrokenPipe
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::NotConnected
+                | ErrorKind::UnexpectedEof
+        )
+    };
+    match e {
+        Error::IOError { err } => stale_kind(err.kind()),
+        Error::HyperResponseError { .. } | Error::HyperLegacyError { .. } => {
+            if io_error_kind(e).is_some_and(stale_kind) {
+                return true;
+            }
+            let msg = e.to_string().to_lowercase();
+            ["connection closed", "broken pipe", "connection reset"]
+                .iter()
+                .any(|needle| msg.contains(needle))
+        }
+        _ => false,
+    }
+}
+
+/// Run `op` with the Docker client; if it fails on a stale connection, drop the cached
+/// client, reconnect and run it once more before reporting the error
+async fn with_retry<T, R, F, Fut>(
+    docker_state: &DockerState,
+    op: F,
+) -> Result<T, CommandResponse<R>>
+where
+    F: Fn(Docker) -> Fut,
+    Fut: std::future::Future<Output = Result<T, bollard::errors::Error>>,
+{
+    let docker = docker_state
+        .client()
+        .await
+        .map_err(CommandResponse::connection_err)?;
+    match op(docker).await {
+        Err(e) if is_stale_connection_error(&e) => {
+            log::warn!("Docker connection went stale, reconnecting: {}", e);
+            let docker = docker_state
+                .reconnect_saved()
+                .await
+                .map_err(CommandResponse::connection_err)?;
+            op(docker).await.map_err(CommandResponse::docker_err)
+        }
+        result => result.map_err(CommandResponse::docker_err),
+    }
+}
+
+#[derive(Serialize)]
+struct ContainerStatsResult {
+    id: String,
+    success: bool,
+    data: Option<ContainerStatsResponse>,
+    error: Option<String>,
+    cpu_percent: f64,
+    mem_percent: f64,
+    limits: Option<ContainerLimits>,
+}
+
+/// Memory and CPU ceilings a container's usage should be drawn against.
+/// Unlimited containers get the host totals, flagged by `*_limited: false`.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct ContainerLimits {
+    pub memory_bytes: i64,
+    pub memory_limited: bool,
+    pub cpus: f64,
+    pub cpu_limited: bool,
+}
+
+/// Work out a container's limits from its inspect `HostConfig`
+pub fn container_limits(
+    host_config: Option<&bollard::models::HostConfig>,
+    host_memory_bytes: i64,
+    host_cpus: f64,
+) -> ContainerLimits {
+    let memory = host_config.and_then(|h| h.memory).filter(|m| *m > 0);
+    let nano_cpus = host_config.and_then(|h| h.nano_cpus).filter(|n| *n > 0);
+    let quota = host_config
+        .and_then(|h| h.cpu_quota.zip(h.cpu_period))
+        .filter(|(quota, period)| *quota > 0 && *period > 0);
+    let cpus = match (nano_cpus, quota) {
+        (Some(n), _) => Some(n as f64 / 1e9),
+        (None, Some((quota, period))) => Some(quota as f64 / period as f64),
+        (None, None) => None,
+    };
+
+    ContainerLimits {
+        memory_bytes: memory.unwrap_or(host_memory_bytes),
+        memory_limited: memory.is_some(),
+        cpus: cpus.unwrap_or(host_cpus),
+        cpu_limited: cpus.is_some(),
+    }
+}
+
+/// Limits read once per container via `inspect_container`, plus the host totals
+/// from `docker.info()`, so stats polling doesn't inspect on every frame.
+struct StatsLimitsCache {
+    containers: Mutex<HashMap<String, ContainerLimits>>,
+    host: Mutex<Option<(i64, f64)>>,
+}
+
+impl StatsLimitsCache {
+    fn new() -> Self {
+        Self {
+            containers: Mutex::new(HashMap::new()),
+            host: Mutex::new(None),
+        }
+    }
+
+    /// Forget everything, e.g. after switching to another daemon
+    fn clear(&self) {
+        self.containers.lock().unwrap().clear();
+        *self.host.lock().unwrap() = None;
+    }
+
+    /// Host memory and CPU count, fetched once. `info` gets the same deadline as a
+    /// stats sample, so a daemon that hangs on it can't stall a batch. A failed or
+    /// incomplete lookup isn't cached, or unlimited containers would keep a 0 limit.
+    async fn host_totals(&self, docker: &Docker, timeout: std::time::Duration) -> (i64, f64) {
+        if let Some(host) = *self.host.lock().unwrap() {
+            return host;
+        }
+        match tokio::time::timeout(timeout, docker.info()).await {
+            Ok(Ok(info)) => {
+                let host = (info.mem_total.unwrap_or(0), info.ncpu.unwrap_or(0) as f64);
+                if host.0 > 0 && host.1 > 0.0 {
+                    *self.host.lock().unwrap() = Some(host);
+                }
+                host
+            }
+            _ => (0, 0.0),
+        }
+    }
+}
+
+/// CPU usage as a percentage of one core, computed like `docker stats`:
+/// the container's share of the system CPU delta, scaled by the number of online CPUs.
+/// Returns 0.0 for the first sample, when `precpu_stats` is still empty.
+fn cpu_percent(stats: &ContainerStatsResponse) -> f64 {
+    let (Some(cpu), Some(precpu)) = (&stats.cpu_stats, &stats.precpu_stats) else {
+        return 0.0;
+    };
+    let total = |s: &bollard::models::ContainerCpuStats| {
+        s.cpu_usage
+            .as_ref()
+            .and_then(|u| u.total_usage)
+            .unwrap_or(0)
+    };
+
+    let cpu_delta = total(cpu) as f64 - total(precpu) as f64;
+    let system_delta =
+        cpu.system_cpu_usage.unwrap_or(0) as f64 - precpu.system_cpu_usage.unwrap_or(0) as f64;
+    // Without a previous system sample the delta is meaningless
+    if precpu.system_cpu_usage.unwrap_or(0) == 0 || system_delta <= 0.0 || cpu_delta <= 0.0 {
+        return 0.0;
+    }
+
+    let online_cpus = cpu
+        .online_cpus
+        .map(|n| n as f64)
+        .or_else(|| {
+            cpu.cpu_usage
+                .as_ref()
+                .and_then(|u| u.percpu_usage.as_ref())
+                .map(|p| p.len() as f64)
+        })
+        .filter(|n| *n > 0.0)
+        .unwrap_or(1.0);
+
+    (cpu_delta / system_delta) * online_cpus * 100.0
+}
+
+/// Memory usage as a percentage of the container's limit. Page cache is excluded the way
+/// `docker stats` does it (`inactive_file` on cgroup v2, `total_inactive_file` on v1).
+fn mem_percent(stats: &ContainerStatsResponse) -> f64 {
+    let Some(mem) = &stats.memory_stats else {
+        return 0.0;
+    };
+    let (Some(usage), Some(limit)) = (mem.usage, mem.limit) else {
+        return 0.0;
+    };
+    if limit == 0 {
+        return 0.0;
+    }
+
+    let cache = mem
+        .stats
+        .as_ref()
+        .and_then(|s| {
+            s.get("inactive_file")
+                .or_else(|| s.get("total_inactive_file"))
+        })
+        .copied()
+        .unwrap_or(0);
+
+    usage.saturating_sub(cache) as f64 / limit as f64 * 100.0
+}
+
+/// Per-container deadline for `get_batch_stats` unless the caller sets one
+const STATS_DEFAULT_TIMEOUT_SECS: u64 = 5;
+
+/// How many stats requests `get_batch_stats` keeps in flight unless the caller sets it
+const STATS_DEFAULT_CONCURRENCY: usize = 16;
+
+/// Take a single stats sample for one container and compute its usage percentages
+async fn fetch_container_stats(
+    docker: &Docker,
+    id: String,
+    limits: Option<ContainerLimits>,
+) -> ContainerStatsResult {
+    let mut stream = docker.stats(
+        &id,
+        Some(StatsOptions {
+            stream: false,
+            ..Default::default()
+        }),
+    );
+    match stream.next().await {
+        Some(Ok(stats)) => ContainerStatsResult {
+            id,
+            success: true,
+            cpu_percent: cpu_percent(&stats),
+            mem_percent: mem_percent(&stats),
+            data: Some(stats),
+            error: None,
+            limits,
+        },
+        Some(Err(e)) => ContainerStatsResult {
+            id,
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            cpu_percent: 0.0,
+            mem_percent: 0.0,
+            limits,
+        },
+        None => ContainerStatsResult {
+            id,
+            success: false,
+            data: None,
+            error: Some("No stats found".to_string()),
+            cpu_percent: 0.0,
+            mem_percent: 0.0,
+            limits,
+        },
+    }
+}
+
+/// Stats for a single container, for views that only show one. A stopped container
+/// has no live stats, which is reported as an error rather than zeroed numbers.
+#[tauri::command]
+async fn get_container_stats(
+    id: String,
+    docker_state: State<'_, DockerState>,
+    limits_cache: State<'_, StatsLimitsCache>,
+) -> Result<CommandResponse<ContainerStatsResult>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let info = match docker.inspect_container(&id, None).await {
+        Ok(info) => info,
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
+    };
+    let running = info.state.as_ref().and_then(|s| s.running).unwrap_or(false);
+    if !running {
+        return Ok(CommandResponse::err_coded(
+            "CONFLICT",
+            format!("Container {} is not running, so it has no stats", id),
+        ));
+    }
+    let timeout = std::time::Duration::from_secs(STATS_DEFAULT_TIMEOUT_SECS);
+    let (host_memory, host_cpus) = limits_cache.host_totals(&docker, timeout).await;
+    let limits = container_limits(info.host_config.as_ref(), host_memory, host_cpus);
+
+    let result =
+        match tokio::time::timeout(timeout, fetch_container_stats(&docker, id, Some(limits))).await
+        {
+            Ok(result) => result,
+            Err(_) => {
+                return Ok(CommandResponse::err_coded(
+                    "TIMEOUT",
+                    format!("Timed out after {}s", STATS_DEFAULT_TIMEOUT_SECS),
+                ))
+            }
+        };
+    match result.error {
+        Some(e) => Ok(CommandResponse::err(e)),
+        None => Ok(CommandResponse::ok(result)),
+    }
+}
+
+#[tauri::command]
+async fn get_batch_stats(
+    ids: Vec<String>,
+    timeout_secs: Option<u64>,
+    concurrency: Option<usize>,
+    docker_state: State<'_, DockerState>,
+    limits_cache: State<'_, StatsLimitsCache>,
+) -> Result<CommandResponse<Vec<ContainerStatsResult>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    for id in &ids {
+        if let Err(e) = validate_docker_id(id) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+
+    // Forget containers that are no longer being polled
+    limits_cache
+        .containers
+        .lock()
+        .unwrap()
+        .retain(|id, _| ids.contains(id));
+    let timeout_secs = timeout_secs.unwrap_or(STATS_DEFAULT_TIMEOUT_SECS);
+    if timeout_secs == 0 {
+        return Ok(CommandResponse::err("Timeout must be positive"));
+    }
+    let timeout = std::time::Duration::from_secs(timeout_secs);
+    let (host_memory, host_cpus) = limits_cache.host_totals(&docker, timeout).await;
+    let concurrency = concurrency.unwrap_or(STATS_DEFAULT_CONCURRENCY);
+    if concurrency == 0 {
+        return Ok(CommandResponse::err("Concurrency must be at least 1"));
+    }
+    // Hosts with hundreds of containers would otherwise open hundreds of streams at once
+    let permits = Arc::new(tokio::sync::Semaphore::new(concurrency));
+
+    let mut futures = FuturesUnordered::new();
+
+    for id in ids {
+        let docker_clone = docker.clone();
+        let permits = permits.clone();
+        let cached_limits = limits_cache.containers.lock().unwrap().get(&id).copied();
+        let timeout_id = id.clone();
+        let fetch = async move {
+            let limits = match cached_limits {
+                Some(l) => Some(l),
+                None => docker_clone
+                    .inspect_container(&id, None)
+                    .await
+                    .ok()
+                    .map(|info| {
+                        container_limits(info.host_config.as_ref(), host_memory, host_cpus)
+                    }),
+            };
+            fetch_container_stats(&docker_clone, id, limits).await
+        };
+        // An unresponsive container reports an error instead of holding up the batch
+        futures.push(async move {
+            // The deadline starts once a slot is free, not while queued
+            let _permit = permits.acquire_owned().await;
+            tokio::time::timeout(timeout, fetch)
+                .await
+                .unwrap_or_else(|_| ContainerStatsResult {
+                    id: timeout_id,
+                    success: false,
+                    data: None,
+                    error: Some(format!("Timed out after {}s", timeout_secs)),
+                    cpu_percent: 0.0,
+                    mem_percent: 0.0,
+                    limits: None,
+                })
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(res) = futures.next().await {
+        if let Some(limits) = res.limits {
+            limits_cache
+                .containers
+                .lock()
+                .unwrap()
+                .insert(res.id.clone(), limits);
+        }
+        results.push(res);
+    }
+
+    Ok(CommandResponse::ok(results))
+}
+
+/// Container states accepted by the daemon's `status` filter
+const CONTAINER_STATUSES: [&str; 7] = [
+    "created",
+    "restarting",
+    "running",
+    "removing",
+    "paused",
+    "exited",
+    "dead",
+];
+
+/// Validate a label filter: either `key` (label present) or `key=value` (label equals value).
+/// Keys may use reverse-DNS style (`com.docker.compose.project`).
+fn validate_label_filter(filter: &str) -> Result<(), String> {
+    let (key, value) = match filter.split_once('=') {
+        Some((k, v)) => (k, Some(v)),
+        None => (filter, None),
+    };
+    if key.is_empty() || key.len() > 256 {
+        return Err(format!("Invalid label filter: {}", filter));
+    }
+    if !key
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_./".contains(c))
+    {
+        return Err(format!("Invalid label key: {}", key));
+    }
+    if value.is_some_and(|v| v.chars().any(|c| c.is_control())) {
+        return Err(format!("Invalid label value in filter: {}", filter));
+    }
+    Ok(())
+}
+
+/// Add `label` entries to a filters map. Bollard sends filters as JSON
+/// `{"label": ["key", "key=value", ...]}`, i.e. a `HashMap<String, Vec<String>>`
+/// where multiple values under one key must all match.
+fn add_label_filters(
+    filters: &mut HashMap<String, Vec<String>>,
+    labels: Option<Vec<String>>,
+) -> Result<(), String> {
+    let Some(labels) = labels.filter(|l| !l.is_empty()) else {
+        return Ok(());
+    };
+    for label in &labels {
+        validate_label_filter(label)?;
+    }
+    filters
+        .entry("label".to_string())
+        .or_default()
+        .extend(labels);
+    Ok(())
+}
+
+/// Label the app puts on every container, volume and network it creates, so they can
+/// be told apart from resources made by the CLI or compose
+const OPENTAINER_CREATED_LABEL: &str = "io.opentainer.created";
+
+/// Validate labels to set on a new resource. Keys follow Docker's recommended format:
+/// lowercase alphanumerics separated by single `.` or `-`, in reverse-DNS style such
+/// as `com.example.role`. The `io.opentainer.` namespace is reserved for the app.
+fn validate_labels(labels: &HashMap<String, String>) -> Result<(), String> {
+    for (key, value) in labels {
+        let valid_key = !key.is_empty()
+            && key.len() <= 256
+            && key.split(['.', '-']).all(|part| {
+                !part.is_empty()
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            });
+        if !valid_key {
+            return Err(format!("Invalid label key: {}", key));
+        }
+        if key.starts_with("io.opentainer.") {
+            return Err(format!("Label key {} is reserved", key));
+        }
+        if value.chars().any(|c| c.is_control()) {
+            return Err(format!("Invalid value for label {}", key));
+        }
+    }
+    Ok(())
+}
+
+/// The labels to create a resource with: the caller's plus the app's own marker
+fn resource_labels(
+    labels: Option<HashMap<String, String>>,
+) -> Result<HashMap<String, String>, String> {
+    let mut labels = labels.unwrap_or_default();
+    validate_labels(&labels)?;
+    labels.insert(OPENTAINER_CREATED_LABEL.to_string(), "true".to_string());
+    Ok(labels)
+}
+
+/// Build the `filters` map for listing containers from the optional status, name and label filters.
+fn container_filters(
+    status: Option<Vec<String>>,
+    name: Option<String>,
+    label_filters: Option<Vec<String>>,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    let mut filters = HashMap::new();
+    add_label_filters(&mut filters, label_filters)?;
+
+    if let Some(status) = status.filter(|s| !s.is_empty()) {
+        if let Some(bad) = status
+            .iter()
+            .find(|s| !CONTAINER_STATUSES.contains(&s.as_str()))
+        {
+            return Err(format!("Invalid status filter: {}", bad));
+        }
+        filters.insert("status".to_string(), status);
+    }
+
+    if let Some(name) = name.filter(|n| !n.is_empty()) {
+        validate_docker_id(&name)?;
+        filters.insert("name".to_string(), vec![name]);
+    }
+
+    Ok(filters)
+}
+
+/// Check a list limit is positive and convert it to what the daemon accepts
+fn validate_list_limit(limit: isize) -> Result<i32, String> {
+    if limit < 1 {
+        return Err("Limit must be positive".to_string());
+    }
+    i32::try_from(limit).map_err(|_| "Limit too large".to_string())
+}
+
+/// `limit` returns only the most recently created containers; `size` adds filesystem
+/// sizes, which makes the daemon walk every container's layer and is off by default.
+#[tauri::command]
+async fn list_containers(
+    status: Option<Vec<String>>,
+    name: Option<String>,
+    label_filters: Option<Vec<String>>,
+    limit: Option<isize>,
+    size: Option<bool>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<bollard::models::ContainerSummary>>, String> {
+    let filters = match container_filters(status, name, label_filters) {
+        Ok(f) => f,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+    let limit = match limit.map(validate_list_limit).transpose() {
+        Ok(l) => l,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+
+    let options = Some(ListContainersOptions {
+        all: true,
+        limit,
+        size: size.unwrap_or(false),
+        filters: (!filters.is_empty()).then_some(filters),
+    });
+
+    let list = |docker: Docker| {
+        let options = options.clone();
+        async move { docker.list_containers(options).await }
+    };
+    match with_retry(&docker_state, list).await {
+        Ok(containers) => Ok(CommandResponse::ok(containers)),
+        Err(resp) => Ok(resp),
+    }
+}
+
+/// Container lifecycle events `watch_containers` forwards
+const WATCHED_CONTAINER_ACTIONS: [&str; 4] = ["start", "stop", "die", "destroy"];
+
+/// A single container row changing state
+#[derive(Serialize, Clone, Debug, PartialEq)]
+struct ContainerChange {
+    id: String,
+    name: Option<String>,
+    action: String,
+    /// State the container is in after the event; "removed" once destroyed
+    state: String,
+}
+
+/// Simplify a daemon event into a `ContainerChange`, ignoring anything that isn't
+/// one of `WATCHED_CONTAINER_ACTIONS` on a container
+fn container_change(event: &bollard::models::EventMessage) -> Option<ContainerChange> {
+    if event.typ != Some(bollard::models::EventMessageTypeEnum::CONTAINER) {
+        return None;
+    }
+    let action = event.action.as_deref()?;
+    let state = match action {
+        "start" => "running",
+        "stop" | "die" => "exited",
+        "destroy" => "removed",
+        _ => return None,
+    };
+    let actor = event.actor.as_ref()?;
+    Some(ContainerChange {
+        id: actor.id.clone()?,
+        name: actor
+            .attributes
+            .as_ref()
+            .and_then(|a| a.get("name"))
+            .cloned(),
+        action: action.to_string(),
+        state: state.to_string(),
+    })
+}
+
+/// Subscribe to container lifecycle events and emit each change on
+/// `container-changes-{session_id}`, so the list can update a single row. When the
+/// daemon closes the subscription (e.g. on restart), `container-changes-end-{session_id}`
+/// is emitted with the error, if any, so the UI can resubscribe.
+#[tauri::command]
+async fn watch_containers(
+    session_id: String,
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+    state: State<'_, EventsState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+
+    let options = bollard::query_parameters::EventsOptions {
+        filters: Some(HashMap::from([
+            ("type".to_string(), vec!["container".to_string()]),
+            (
+                "event".to_string(),
+                WATCHED_CONTAINER_ACTIONS
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect(),
+            ),
+        ])),
+        ..Default::default()
+    };
+
+    let event_name = format!("container-changes-{}", session_id);
+    let session_id_clone = session_id.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        let mut stream = docker.events(Some(options));
+        let error = loop {
+            match stream.next().await {
+                Some(Ok(event)) => {
+                    if let Some(change) = container_change(&event) {
+                        let _ = app_handle.emit(&event_name, change);
+                    }
+                }
+                Some(Err(e)) => break Some(e.to_string()),
+                None => break None,
+            }
+        };
+        log::info!("Container events subscription ended: {:?}", error);
+        let _ = app_handle.emit(
+            &format!("container-changes-end-{}", session_id_clone),
+            error,
+        );
+    });
+
+    let mut lock = state.inner().0.lock().unwrap();
+    if let Some(old_handle) = lock.insert(session_id, handle) {
+        old_handle.abort();
+    }
+
+    Ok(CommandResponse::ok_empty())
+}
+
+#[tauri::command]
+fn stop_watch_containers(session_id: String, state: State<'_, EventsState>) -> CommandResponse<()> {
+    if let Some(handle) = state.inner().0.lock().unwrap().remove(&session_id) {
+        handle.abort();
+    }
+    CommandResponse::ok_empty()
+}
+
+/// Filesystem size of a single container
+#[derive(Serialize)]
+struct ContainerSize {
+    size_rw: Option<i64>,
+    size_root_fs: Option<i64>,
+}
+
+/// Fetch one container's size on demand, so the list can skip the costly `size: true`
+#[tauri::command]
+async fn get_container_size(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<ContainerSize>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let options = Some(InspectContainerOptions { size: true });
+    match docker.inspect_container(&id, options).await {
+        Ok(info) => Ok(CommandResponse::ok(ContainerSize {
+            size_rw: info.size_rw,
+            size_root_fs: info.size_root_fs,
+        })),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Label Compose sets on every container it creates with the project name
+const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+/// Label Compose sets with the service a container belongs to
+const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+/// Group for containers that weren't created by Compose
+const STANDALONE_PROJECT: &str = "standalone";
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ComposeService {
+    pub name: String,
+    pub id: String,
+    pub state: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ComposeProject {
+    pub project: String,
+    pub services: Vec<ComposeService>,
+}
+
+/// Group containers by their Compose project label. Containers without it end up in
+/// a trailing "standalone" group under their container name.
+pub fn group_compose_projects(
+    containers: Vec<bollard::models::ContainerSummary>,
+) -> Vec<ComposeProject> {
+    let mut projects: HashMap<Option<String>, Vec<ComposeService>> = HashMap::new();
+    for c in containers {
+        let labels = c.labels.unwrap_or_default();
+        let project = labels.get(COMPOSE_PROJECT_LABEL).cloned();
+        let name = match labels.get(COMPOSE_SERVICE_LABEL) {
+            Some(service) if project.is_some() => service.clone(),
+            _ => c
+                .names
+                .and_then(|n| n.into_iter().next())
+                .map(|n| n.trim_start_matches('/').to_string())
+                .unwrap_or_default(),
+        };
+        projects.entry(project).or_default().push(ComposeService {
+            name,
+            id: c.id.unwrap_or_default(),
+            state: c.state.map(|s| s.to_string()).unwrap_or_default(),
+        });
+    }
+
+    let mut grouped: Vec<(Option<String>, Vec<ComposeService>)> = projects.into_iter().collect();
+    // Named projects alphabetically, standalone last
+    grouped.sort_by(|(a, _), (b, _)| a.is_none().cmp(&b.is_none()).then_with(|| a.cmp(b)));
+    grouped
+        .into_iter()
+        .map(|(project, mut services)| {
+            services.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+            ComposeProject {
+                project: project.unwrap_or_else(|| STANDALONE_PROJECT.to_string()),
+                services,
+            }
+        })
+        .collect()
+}
+
+/// List all containers grouped into Compose projects, for a compose-aware tree view
+#[tauri::command]
+async fn list_compose_projects(
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<ComposeProject>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+
+    let options = Some(ListContainersOptions {
+        all: true,
+        ..Default::default()
+    });
+
+    match docker.list_containers(options).await {
+        Ok(containers) => Ok(CommandResponse::ok(group_compose_projects(containers))),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+#[tauri::command]
+async fn container_action(
+    id: String,
+    action: String,
+    timeout: Option<i64>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    let action = match parse_container_action(&action) {
+        Ok(a) => a,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+    if let Some(t) = timeout {
+        if let Err(e) = validate_stop_timeout(t) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+
+    match apply_container_action(&docker, &id, action, timeout).await {
+        Ok(_) => Ok(CommandResponse::ok_empty()),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Outcome of a `remove_container_force` call, sent as the completion event
+#[derive(Serialize, Clone)]
+struct ContainerRemovedEvent {
+    success: bool,
+    error: Option<String>,
+}
+
+/// Force-remove a container, optionally with its anonymous volumes.
+/// Emits `container-removing-{id}` before the call and `container-removed-{id}`
+/// when the daemon is done, since large writable layers can take a while. `{id}` is
+/// always the full container ID, as names may hold characters event names can't.
+#[tauri::command]
+async fn remove_container_force(
+    id: String,
+    remove_volumes: bool,
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    let id = match docker.inspect_container(&id, None).await {
+        Ok(info) => info.id.unwrap_or(id),
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
+    };
+
+    let _ = app_handle.emit(&format!("container-removing-{}", id), ());
+
+    let options = RemoveContainerOptions {
+        force: true,
+        v: remove_volumes,
+        ..Default::default()
+    };
+    let res = docker.remove_container(&id, Some(options)).await;
+
+    let error = res.as_ref().err().map(|e| e.to_string());
+    let _ = app_handle.emit(
+        &format!("container-removed-{}", id),
+        ContainerRemovedEvent {
+            success: error.is_none(),
+            error: error.clone(),
+        },
+    );
+
+    match res {
+        Ok(_) => Ok(CommandResponse::ok_empty()),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Everything needed to create a replacement for an existing container
+#[derive(Debug)]
+pub struct RecreatePlan {
+    pub name: Option<String>,
+    pub body: ContainerCreateBody,
+    /// Networks besides the one in `network_mode`, connected after the container is created
+    pub extra_networks: Vec<(String, EndpointSettings)>,
+}
+
+/// Remove from `value` whatever equals the old image's default, so a new image's own
+/// default applies instead of the stale one baked into the container config.
+fn drop_image_default<T: PartialEq>(value: &mut Option<T>, image_default: &Option<T>) {
+    if value.is_some() && value == image_default {
+        *value = None;
+    }
+}
+
+/// Work out how to recreate a container from its inspect output.
+///
+/// When `new_image` is given, settings the container only inherited from its old
+/// image (cmd, entrypoint, env entries, labels, ...) are dropped so the new image's
+/// defaults take effect. Anonymous volumes are re-attached by name so their data
+/// survives, and network endpoints keep their aliases and static IPs but not the
+/// addresses the daemon assigned.
+pub fn recreate_plan(
+    info: &ContainerInspectResponse,
+    old_image_config: Option<&ImageConfig>,
+    new_image: Option<&str>,
+) -> RecreatePlan {
+    let id = info.id.clone().unwrap_or_default();
+    let mut config = info.config.clone().unwrap_or_default();
+
+    if let Some(image) = new_image {
+        config.image = Some(image.to_string());
+        if let Some(old) = old_image_config {
+            drop_image_default(&mut config.cmd, &old.cmd);
+            drop_image_default(&mut config.entrypoint, &old.entrypoint);
+            drop_image_default(&mut config.working_dir, &old.working_dir);
+            drop_image_default(&mut config.user, &old.user);
+            drop_image_default(&mut config.stop_signal, &old.stop_signal);
+            drop_image_default(&mut config.healthcheck, &old.healthcheck);
+            drop_image_default(&mut config.shell, &old.shell);
+            if let (Some(env), Some(old_env)) = (config.env.as_mut(), old.env.as_ref()) {
+                env.retain(|e| !old_env.contains(e));
+            }
+            if let (Some(labels), Some(old_labels)) = (config.labels.as_mut(), old.labels.as_ref())
+            {
+                labels.retain(|k, v| old_labels.get(k) != Some(v));
+            }
+            if let (Some(ports), Some(old_ports)) =
+                (config.exposed_ports.as_mut(), old.exposed_ports.as_ref())
+            {
+                ports.retain(|p| !old_ports.contains(p));
+            }
+            if let (Some(volumes), Some(old_volumes)) =
+                (config.volumes.as_mut(), old.volumes.as_ref())
+            {
+                volumes.retain(|v| !old_volumes.contains(v));
+            }
+        }
+    }
+
+    // The daemon defaults the hostname to the short container id; let the new one get its own
+    if config
+        .hostname
+        .as_deref()
+        .is_some_and(|h| !h.is_empty() && id.starts_with(h))
+    {
+        config.hostname = None;
+    }
+
+    let mut host_config = info.host_config.clone().unwrap_or_default();
+
+    // Keep anonymous volumes: mount them by name at their old destination
+    let mut binds = host_config.binds.clone().unwrap_or_default();
+    let mut targets: Vec<String> = binds
+        .iter()
+        .filter_map(|b| b.split(':').nth(1).map(str::to_string))
+        .collect();
+    targets.extend(
+        host_config
+            .mounts
+            .iter()
+            .flatten()
+            .filter_map(|m| m.target.clone()),
+    );
+    for mount in info.mounts.iter().flatten() {
+        if mount.typ != Some(MountPointTypeEnum::VOLUME) {
+            continue;
+        }
+        let (Some(name), Some(destination)) = (&mount.name, &mount.destination) else {
+            continue;
+        };
+        if targets.contains(destination) {
+            continue;
+        }
+        let mode = if mount.rw == Some(false) { ":ro" } else { "" };
+        binds.push(format!("{}:{}{}", name, destination, mode));
+        targets.push(destination.clone());
+    }
+    if !binds.is_empty() {
+        host_config.binds = Some(binds);
+    }
+
+    let network_mode = host_config
+        .network_mode
+        .clone()
+        .unwrap_or_else(|| "default".to_string());
+    let shares_stack =
+        matches!(network_mode.as_str(), "host" | "none") || network_mode.starts_with("container:");
+    let primary_network = if network_mode == "default" {
+        "bridge".to_string()
+    } else {
+        network_mode
+    };
+
+    let mut networking_config = None;
+    let mut extra_networks = Vec::new();
+    if !shares_stack {
+        let short_id = id.get(..12).unwrap_or(&id);
+        let networks = info
+            .network_settings
+            .as_ref()
+            .and_then(|n| n.networks.clone())
+            .unwrap_or_default();
+        let mut networks: Vec<(String, EndpointSettings)> = networks.into_iter().collect();
+        networks.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (network, endpoint) in networks {
+            let endpoint = EndpointSettings {
+                ipam_config: endpoint.ipam_config,
+                links: endpoint.links,
+                aliases: endpoint
+                    .aliases
+                    .map(|a| a.into_iter().filter(|a| a != short_id).collect()),
+                driver_opts: endpoint.driver_opts,
+                gw_priority: endpoint.gw_priority,
+                ..Default::default()
+            };
+            if network == primary_network {
+                networking_config = Some(NetworkingConfig {
+                    endpoints_config: Some(HashMap::from([(network, endpoint)])),
+                });
+            } else {
+                extra_networks.push((network, endpoint));
+            }
+        }
+    }
+
+    let body = ContainerCreateBody {
+        hostname: config.hostname,
+        domainname: config.domainname,
+        user: config.user,
+        attach_stdin: config.attach_stdin,
+        attach_stdout: config.attach_stdout,
+        attach_stderr: config.attach_stderr,
+        exposed_ports: config.exposed_ports,
+        tty: config.tty,
+        open_stdin: config.open_stdin,
+        stdin_once: config.stdin_once,
+        env: config.env,
+        cmd: config.cmd,
+        healthcheck: config.healthcheck,
+        args_escaped: config.args_escaped,
+        image: config.image,
+        volumes: config.volumes,
+        working_dir: config.working_dir,
+        entrypoint: config.entrypoint,
+        network_disabled: config.network_disabled,
+        on_build: config.on_build,
+        labels: config.labels,
+        stop_signal: config.stop_signal,
+        stop_timeout: config.stop_timeout,
+        shell: config.shell,
+        host_config: Some(host_config),
+        networking_config,
+    };
+
+    RecreatePlan {
+        name: info
+            .name
+            .as_deref()
+            .map(|n| n.trim_start_matches('/').to_string()),
+        body,
+        extra_networks,
+    }
+}
+
+/// Put the original container back after a failed recreate: drop the half-made
+/// replacement, restore the old name, and restart it if it was running.
+async fn restore_replaced_container(
+    docker: &Docker,
+    old_id: &str,
+    name: &str,
+    new_id: Option<&str>,
+    was_running: bool,
+) {
+    if let Some(new_id) = new_id {
+        let options = RemoveContainerOptions {
+            force: true,
+            ..Default::default()
+        };
+        let _ = docker.remove_container(new_id, Some(options)).await;
+    }
+    let rename = RenameContainerOptions {
+        name: name.to_string(),
+    };
+    if let Err(e) = docker.rename_container(old_id, rename).await {
+        log::warn!("Failed to restore name of container {}: {}", old_id, e);
+    }
+    if was_running {
+        let _ = docker.start_container(old_id, None).await;
+    }
+}
+
+/// Swap a container for a new one created from `plan`. The old container is stopped
+/// and renamed aside until the new one is running, and is restored if any step fails.
+/// Returns the new container id.
+async fn replace_container(
+    docker: &Docker,
+    info: &ContainerInspectResponse,
+    plan: RecreatePlan,
+) -> Result<String, String> {
+    let (Some(old_id), Some(name)) = (info.id.clone(), plan.name.clone()) else {
+        return Err("Container has no id or name".to_string());
+    };
+    let was_running = info.state.as_ref().and_then(|s| s.running).unwrap_or(false);
+
+    if was_running {
+        if let Err(e) = docker.stop_container(&old_id, None).await {
+            return Err(format!("Failed to stop container: {}", e));
+        }
+    }
+
+    // Move the old container aside so the replacement can take its name
+    let backup = RenameContainerOptions {
+        name: format!("{}-replaced-{}", name, &old_id[..12.min(old_id.len())]),
+    };
+    if let Err(e) = docker.rename_container(&old_id, backup).await {
+        if was_running {
+            let _ = docker.start_container(&old_id, None).await;
+        }
+        return Err(format!("Failed to rename container: {}", e));
+    }
+
+    let create_options = CreateContainerOptions {
+        name: Some(name.clone()),
+        ..Default::default()
+    };
+    let new_id = match docker
+        .create_container(Some(create_options), plan.body)
+        .await
+    {
+        Ok(res) => res.id,
+        Err(e) => {
+            restore_replaced_container(docker, &old_id, &name, None, was_running).await;
+            return Err(format!("Failed to create container: {}", e));
+        }
+    };
+
+    for (network, endpoint) in plan.extra_networks {
+        let request = NetworkConnectRequest {
+            container: new_id.clone(),
+            endpoint_config: Some(endpoint),
+        };
+        if let Err(e) = docker.connect_network(&network, request).await {
+            restore_replaced_container(docker, &old_id, &name, Some(&new_id), was_running).await;
+            return Err(format!("Failed to connect network {}: {}", network, e));
+        }
+    }
+
+    if let Err(e) = docker.start_container(&new_id, None).await {
+        restore_replaced_container(docker, &old_id, &name, Some(&new_id), was_running).await;
+        return Err(format!("Failed to start container: {}", e));
+    }
+
+    // Anonymous volumes now belong to the new container, so keep them
+    if let Err(e) = docker.remove_container(&old_id, None).await {
+        log::warn!(
+            "Recreated {} but failed to remove the old container: {}",
+            name,
+            e
+        );
+    }
+
+    Ok(new_id)
+}
+
+/// Create (but don't start) a container from `image`, tagged with `labels` and the
+/// app's own label. Returns the new container id.
+#[tauri::command]
+async fn create_container(
+    image: String,
+    name: Option<String>,
+    labels: Option<HashMap<String, String>>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_image_ref(&image) {
+        return Ok(CommandResponse::err(e));
+    }
+    if let Some(ref n) = name {
+        if let Err(e) = validate_docker_id(n) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+    let labels = match resource_labels(labels) {
+        Ok(l) => l,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+
+    let options = CreateContainerOptions {
+        name,
+        ..Default::default()
+    };
+    let body = ContainerCreateBody {
+        image: Some(image),
+        labels: Some(labels),
+        ..Default::default()
+    };
+    match docker.create_container(Some(options), body).await {
+        Ok(res) => Ok(CommandResponse::ok(res.id)),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Replace a container with a fresh one built from the same settings, optionally
+/// from a different image. Returns the new container id.
+#[tauri::command]
+async fn recreate_container(
+    id: String,
+    new_image: Option<String>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    if let Some(image) = &new_image {
+        if let Err(e) = validate_image_ref(image) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+
+    let info = match docker.inspect_container(&id, None).await {
+        Ok(i) => i,
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
+    };
+    let old_image_config = match (&new_image, &info.image) {
+        (Some(_), Some(old_image)) => docker
+            .inspect_image(old_image)
+            .await
+            .ok()
+            .and_then(|i| i.config),
+        _ => None,
+    };
+
+    let plan = recreate_plan(&info, old_image_config.as_ref(), new_image.as_deref());
+    match replace_container(&docker, &info, plan).await {
+        Ok(new_id) => Ok(CommandResponse::ok(new_id)),
+        Err(e) => Ok(CommandResponse::err(e)),
+    }
+}
+
+/// Overlay `updates` on a `KEY=value` list: existing keys are replaced in place,
+/// new keys are appended in name order.
+pub fn merge_env(existing: Vec<String>, updates: &HashMap<String, String>) -> Vec<String> {
+    let mut merged: Vec<String> = existing
+        .into_iter()
+        .map(|entry| {
+            let key = entry.split_once('=').map_or(entry.as_str(), |(k, _)| k);
+            match updates.get(key) {
+                Some(value) => format!("{}={}", key, value),
+                None => entry,
+            }
+        })
+        .collect();
+
+    let mut added: Vec<(&String, &String)> = updates
+        .iter()
+        .filter(|(key, _)| {
+            !merged
+                .iter()
+                .any(|e| e.split_once('=').map_or(e.as_str(), |(k, _)| k) == key.as_str())
+        })
+        .collect();
+    added.sort();
+    merged.extend(added.into_iter().map(|(k, v)| format!("{}={}", k, v)));
+    merged
+}
+
+/// Recreate a container with some environment variables changed, keeping the rest
+/// of its configuration. Returns the new container id.
+#[tauri::command]
+async fn restart_with_env(
+    id: String,
+    env_updates: HashMap<String, String>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    if env_updates.is_empty() {
+        return Ok(CommandResponse::err("No environment changes given"));
+    }
+    for (key, value) in &env_updates {
+        if let Err(e) = validate_env_entry(&format!("{}={}", key, value)) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+
+    let info = match docker.inspect_container(&id, None).await {
+        Ok(i) => i,
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
+    };
+
+    let mut plan = recreate_plan(&info, None, None);
+    let env = plan.body.env.take().unwrap_or_default();
+    plan.body.env = Some(merge_env(env, &env_updates));
+
+    match replace_container(&docker, &info, plan).await {
+        Ok(new_id) => Ok(CommandResponse::ok(new_id)),
+        Err(e) => Ok(CommandResponse::err(e)),
+    }
+}
+
+/// Actions accepted by `container_action` and `bulk_container_action`
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ContainerAction {
+    Start,
+    Stop,
+    Restart,
+    Remove,
+}
+
+/// Map an action name from the UI to a `ContainerAction`
+fn parse_container_action(action: &str) -> Result<ContainerAction, String> {
+    match action {
+        "start" => Ok(ContainerAction::Start),
+        "stop" => Ok(ContainerAction::Stop),
+        "restart" => Ok(ContainerAction::Restart),
+        "remove" => Ok(ContainerAction::Remove),
+        _ => Err("Invalid action".to_string()),
+    }
+}
+
+/// Validate a stop grace period in seconds (0 stops immediately)
+fn validate_stop_timeout(timeout: i64) -> Result<(), String> {
+    if timeout < 0 {
+        return Err("Stop timeout cannot be negative".to_string());
+    }
+    if timeout > i32::MAX as i64 {
+        return Err("Stop timeout too large".to_string());
+    }
+    Ok(())
+}
+
+/// Run an action. `stop_timeout` is the grace period before SIGKILL for "stop";
+/// `None` keeps the daemon's default. Callers validate it first. The error is kept
+/// as is so callers can report its code, e.g. CONFLICT to offer a forced remove.
+async fn apply_container_action(
+    docker: &Docker,
+    id: &str,
+    action: ContainerAction,
+    stop_timeout: Option<i64>,
+) -> Result<(), bollard::errors::Error> {
+    match action {
+        ContainerAction::Start => docker.start_container(id, None).await,
+        ContainerAction::Stop => {
+            let options = stop_timeout.map(|t| StopContainerOptions {
+                t: Some(t as i32),
+                ..Default::default()
+            });
+            docker.stop_container(id, options).await
+        }
+        ContainerAction::Restart => docker.restart_container(id, None).await,
+        ContainerAction::Remove => docker.remove_container(id, None).await,
+    }
+}
+
+#[derive(Serialize)]
+struct ContainerActionResult {
+    id: String,
+    success: bool,
+    error: Option<String>,
+    /// Category of the failure, as in `CommandResponse::error_code`
+    error_code: Option<String>,
+}
+
+/// Apply one action to several containers concurrently, reporting each outcome
+#[tauri::command]
+async fn bulk_container_action(
+    ids: Vec<String>,
+    action: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<ContainerActionResult>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    let action = match parse_container_action(&action) {
+        Ok(a) => a,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+    for id in &ids {
+        if let Err(e) = validate_docker_id(id) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+
+    let mut futures = FuturesUnordered::new();
+
+    for id in ids {
+        let docker_clone = docker.clone();
+        futures.push(async move {
+            let res = apply_container_action(&docker_clone, &id, action, None).await;
+            ContainerActionResult {
+                id,
+                success: res.is_ok(),
+                error: res.as_ref().err().map(|e| e.to_string()),
+                error_code: res.err().map(|e| docker_error_code(&e).to_string()),
+            }
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(res) = futures.next().await {
+        results.push(res);
+    }
+
+    Ok(CommandResponse::ok(results))
+}
+
+/// A published port in a render-friendly shape
+#[derive(Serialize, Debug, PartialEq)]
+pub struct PortMapping {
+    pub private_port: u16,
+    pub public_port: u16,
+    #[serde(rename = "type")]
+    pub protocol: String,
+    pub ip: String,
+}
+
+/// Flatten an inspect `PortMap` ("80/tcp" → bindings) into published mappings.
+/// Docker reports the same binding once per address family, so entries with the same
+/// private port, public port and protocol are collapsed, keeping the IPv4 address.
+pub fn normalize_ports(ports: &bollard::models::PortMap) -> Vec<PortMapping> {
+    let mut mappings: Vec<PortMapping> = Vec::new();
+
+    for (key, bindings) in ports {
+        let (port, protocol) = key.split_once('/').unwrap_or((key.as_str(), "tcp"));
+        let Ok(private_port) = port.parse::<u16>() else {
+            continue;
+        };
+        for binding in bindings.iter().flatten() {
+            let Some(public_port) = binding
+                .host_port
+                .as_deref()
+                .and_then(|p| p.parse::<u16>().ok())
+            else {
+                continue;
+            };
+            let ip = binding.host_ip.clone().unwrap_or_default();
+            let duplicate = mappings.iter_mut().find(|m| {
+                m.private_port == private_port
+                    && m.public_port == public_port
+                    && m.protocol == protocol
+            });
+            match duplicate {
+                Some(existing) => {
+                    if existing.ip.contains(':') && !ip.contains(':') {
+                        existing.ip = ip;
+                    }
+                }
+                None => mappings.push(PortMapping {
+                    private_port,
+                    public_port,
+                    protocol: protocol.to_string(),
+                    ip,
+                }),
+            }
+        }
+    }
+
+    mappings.sort_by(|a, b| {
+        (a.private_port, &a.protocol, a.public_port).cmp(&(
+            b.private_port,
+            &b.protocol,
+            b.public_port,
+        ))
+    });
+    mappings
+}
+
+#[tauri::command]
+async fn get_container_ports(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<PortMapping>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    match docker.inspect_container(&id, None).await {
+        Ok(info) => {
+            let ports = info
+                .network_settings
+                .and_then(|n| n.ports)
+                .map(|p| normalize_ports(&p))
+                .unwrap_or_default();
+            Ok(CommandResponse::ok(ports))
+        }
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Container ports that almost always serve HTTP(S); listed first by `container_urls`
+const COMMON_HTTP_PORTS: [u16; 4] = [80, 443, 3000, 8080];
+
+/// A browsable URL for one published port
+#[derive(Serialize, Debug, PartialEq)]
+struct ContainerUrl {
+    private_port: u16,
+    public_port: u16,
+    url: String,
+}
+
+/// The host published ports are reachable on for a connection path: the daemon's host
+/// for remote (`tcp://`, `ssh://`, ...) connections, localhost otherwise
+fn url_host_for_path(path: &str) -> String {
+    let Some((_, rest)) = path.split_once("://") else {
+        return "localhost".to_string();
+    };
+    if path.starts_with("unix://") || path.starts_with("npipe://") {
+        return "localhost".to_string();
+    }
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let host_port = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    let host = match host_port.strip_prefix('[') {
+        // Bracketed IPv6 literal: keep the brackets for use in a URL
+        Some(v6) => v6
+            .split_once(']')
+            .map_or(host_port.to_string(), |(h, _)| format!("[{}]", h)),
+        None => host_port.split(':').next().unwrap_or(host_port).to_string(),
+    };
+    if host.is_empty() {
+        "localhost".to_string()
+    } else {
+        host
+    }
+}
+
+/// Candidate URLs for a container's published TCP ports, common HTTP ports first
+fn container_urls(ports: &[PortMapping], host: &str) -> Vec<ContainerUrl> {
+    let mut urls: Vec<ContainerUrl> = Vec::new();
+    for port in ports.iter().filter(|p| p.protocol == "tcp") {
+        if urls.iter().any(|u| u.public_port == port.public_port) {
+            continue;
+        }
+        let scheme = if matches!(port.private_port, 443 | 8443) {
+            "https"
+        } else {
+            "http"
+        };
+        urls.push(ContainerUrl {
+            private_port: port.private_port,
+            public_port: port.public_port,
+            url: format!("{}://{}:{}", scheme, host, port.public_port),
+        });
+    }
+    urls.sort_by_key(|u| !COMMON_HTTP_PORTS.contains(&u.private_port));
+    urls
+}
+
+/// URLs for opening a container's published ports in the browser
+#[tauri::command]
+async fn get_container_urls(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<ContainerUrl>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    match docker.inspect_container(&id, None).await {
+        Ok(info) => {
+            let ports = info
+                .network_settings
+                .and_then(|n| n.ports)
+                .map(|p| normalize_ports(&p))
+                .unwrap_or_default();
+            let host = url_host_for_path(&docker_state.get_path());
+            Ok(CommandResponse::ok(container_urls(&ports, &host)))
+        }
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// A container mount as shown in the mounts tab
+#[derive(Serialize, Debug, PartialEq)]
+struct MountInfo {
+    /// "bind", "volume", "tmpfs", ...
+    #[serde(rename = "type")]
+    kind: String,
+    /// Volume name for named volumes
+    name: Option<String>,
+    /// Host path (or the volume's data directory)
+    source: String,
+    destination: String,
+    mode: String,
+    rw: bool,
+}
+
+fn normalize_mounts(mounts: &[bollard::models::MountPoint]) -> Vec<MountInfo> {
+    mounts
+        .iter()
+        .map(|m| MountInfo {
+            kind: m.typ.map(|t| t.to_string()).unwrap_or_default(),
+            name: m.name.clone().filter(|n| !n.is_empty()),
+            source: m.source.clone().unwrap_or_default(),
+            destination: m.destination.clone().unwrap_or_default(),
+            mode: m.mode.clone().unwrap_or_default(),
+            rw: m.rw.unwrap_or(true),
+        })
+        .collect()
+}
+
+#[tauri::command]
+async fn get_container_mounts(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<MountInfo>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    match docker.inspect_container(&id, None).await {
+        Ok(info) => {
+            let mounts = info
+                .mounts
+                .map(|m| normalize_mounts(&m))
+                .unwrap_or_default();
+            Ok(CommandResponse::ok(mounts))
+        }
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// One network a container is attached to, for the networking tab
+#[derive(Serialize, Debug, PartialEq)]
+struct ContainerNetwork {
+    network_name: String,
+    network_id: String,
+    ip_address: String,
+    gateway: String,
+    mac_address: String,
+    aliases: Vec<String>,
+}
+
+/// Flatten inspect `NetworkSettings.Networks`, sorted by network name
+fn normalize_networks(
+    networks: &HashMap<String, bollard::models::EndpointSettings>,
+) -> Vec<ContainerNetwork> {
+    let mut normalized: Vec<ContainerNetwork> = networks
+        .iter()
+        .map(|(name, endpoint)| ContainerNetwork {
+            network_name: name.clone(),
+            network_id: endpoint.network_id.clone().unwrap_or_default(),
+            ip_address: endpoint.ip_address.clone().unwrap_or_default(),
+            gateway: endpoint.gateway.clone().unwrap_or_default(),
+            mac_address: endpoint.mac_address.clone().unwrap_or_default(),
+            aliases: endpoint.aliases.clone().unwrap_or_default(),
+        })
+        .collect();
+    normalized.sort_by(|a, b| a.network_name.cmp(&b.network_name));
+    normalized
+}
+
+#[tauri::command]
+async fn get_container_networks(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<ContainerNetwork>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    match docker.inspect_container(&id, None).await {
+        Ok(info) => {
+            let networks = info
+                .network_settings
+                .and_then(|n| n.networks)
+                .map(|n| normalize_networks(&n))
+                .unwrap_or_default();
+            Ok(CommandResponse::ok(networks))
+        }
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Check a local destination for an exported archive: an absolute path whose parent
+/// directory exists and that isn't itself a directory
+fn validate_export_path(dest: &str) -> Result<std::path::PathBuf, String> {
+    let path = std::path::PathBuf::from(dest);
+    if !path.is_absolute() {
+        return Err(format!("Destination must be an absolute path: {}", dest));
+    }
+    if path.is_dir() {
+        return Err(format!("Destination is a directory: {}", dest));
+    }
+    if !path.parent().is_some_and(|p| p.is_dir()) {
+        return Err(format!("Destination directory does not exist: {}", dest));
+    }
+    Ok(path)
+}
+
+/// Chunks queued between a streaming export and its file writer
+const EXPORT_WRITE_QUEUE: usize = 16;
+
+/// A local file filled from a stream on a blocking thread, so multi-gigabyte exports
+/// never stall an async worker. The partial file is removed unless `finish` succeeds.
+struct ExportFile<T> {
+    path: std::path::PathBuf,
+    tx: mpsc::Sender<T>,
+    writer: tokio::task::JoinHandle<std::io::Result<()>>,
+}
+
+impl<T: AsRef<[u8]> + Send + 'static> ExportFile<T> {
+    fn create(path: std::path::PathBuf) -> Result<Self, String> {
+        let file = std::fs::File::create(&path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let (tx, mut rx) = mpsc::channel::<T>(EXPORT_WRITE_QUEUE);
+        let writer = tokio::task::spawn_blocking(move || {
+            use std::io::Write;
+            let mut writer = std::io::BufWriter::new(file);
+            while let Some(chunk) = rx.blocking_recv() {
+                writer.write_all(chunk.as_ref())?;
+            }
+            writer.flush()
+        });
+        Ok(Self { path, tx, writer })
+    }
+
+    /// Queue a chunk; `false` once the writer has stopped on an error `finish` reports
+    async fn write(&self, chunk: T) -> bool {
+        self.tx.send(chunk).await.is_ok()
+    }
+
+    /// Flush and close the file, removing it if a write failed
+    async fn finish(self) -> Result<(), String> {
+        drop(self.tx);
+        let result = match self.writer.await {
+            Ok(result) => result.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+        .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&self.path);
+        }
+        result
+    }
+
+    /// Stop writing and remove the partial file
+    async fn abort(self) {
+        drop(self.tx);
+        let _ = self.writer.await;
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Write a container's flattened filesystem to a local tar at `dest` and return the
+/// number of bytes written. A partially written file is removed on failure.
+#[tauri::command]
+async fn export_container(
+    id: String,
+    dest: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<u64>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    let path = match validate_export_path(&dest) {
+        Ok(p) => p,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+
+    let file = match ExportFile::create(path) {
+        Ok(f) => f,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+
+    let mut written: u64 = 0;
+    let mut stream = docker.export_container(&id);
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            Ok(chunk) => {
+                written += chunk.len() as u64;
+                if !file.write(chunk).await {
+                    break;
+                }
+            }
+            Err(e) => {
+                file.abort().await;
+                return Ok(CommandResponse::docker_err(e));
+            }
+        }
+    }
+
+    match file.finish().await {
+        Ok(()) => Ok(CommandResponse::ok(written)),
+        Err(e) => Ok(CommandResponse::err(e)),
+    }
+}
+
+/// Largest file `read_container_file` returns
+const MAX_CONTAINER_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Validate a path inside a container: absolute and without `..` segments
+fn validate_container_path(path: &str) -> Result<(), String> {
+    if !path.starts_with('/') {
+        return Err(format!("Path must be absolute: {}", path));
+    }
+    if path.len() > 4096 || path.contains('\0') || path.split('/').any(|segment| segment == "..") {
+        return Err(format!("Invalid path: {}", path));
+    }
+    Ok(())
+}
+
+/// Read a text file (up to `MAX_CONTAINER_FILE_BYTES`) from a container, e.g. to
+/// peek at a config without opening a shell. Works on stopped containers too.
+#[tauri::command]
+async fn read_container_file(
+    id: String,
+    path: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    if let Err(e) = validate_container_path(&path) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let options = bollard::query_parameters::DownloadFromContainerOptions { path: path.clone() };
+    let mut stream = docker.download_from_container(&id, Some(options));
+    let mut archive = Vec::new();
+    let mut entry = None;
+    // Read only as far as the file's own data, checking the header as soon as it arrives
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            Ok(bytes) => archive.extend_from_slice(&bytes),
+            Err(e) => return Ok(CommandResponse::docker_err(e)),
+        }
+        if entry.is_none() {
+            entry = match tar::first_entry(&archive) {
+                Ok(e) => e,
+                Err(e) => return Ok(CommandResponse::err(e)),
+            };
+            match entry {
+                Some(ref e) if e.kind == tar::TarEntryKind::Directory => {
+                    return Ok(CommandResponse::err(format!("{} is a directory", path)));
+                }
+                Some(ref e) if e.kind != tar::TarEntryKind::File => {
+                    return Ok(CommandResponse::err(format!(
+                        "{} is not a regular file",
+                        path
+                    )));
+                }
+                Some(ref e) if e.size > MAX_CONTAINER_FILE_BYTES => {
+                    return Ok(CommandResponse::err(format!(
+                        "{} is too large to show ({}, limit {})",
+                        path,
+                        format_bytes(i64::try_from(e.size).unwrap_or(i64::MAX)),
+                        format_bytes(MAX_CONTAINER_FILE_BYTES as i64)
+                    )));
+                }
+                _ => {}
+            }
+        }
+        if let Some(ref e) = entry {
+            if archive.len() >= e.data_offset + e.size as usize {
+                break;
+            }
+        }
+    }
+
+    let Some(entry) = entry else {
+        return Ok(CommandResponse::err(format!("{} could not be read", path)));
+    };
+    let end = entry.data_offset + entry.size as usize;
+    let Some(data) = archive.get(entry.data_offset..end) else {
+        return Ok(CommandResponse::err(format!("{} was truncated", path)));
+    };
+    match String::from_utf8(data.to_vec()) {
+        Ok(contents) => Ok(CommandResponse::ok(contents)),
+        Err(_) => Ok(CommandResponse::err(format!("{} is not a text file", path))),
+    }
+}
+
+/// Split an absolute container path into its directory and file name, or None when
+/// it doesn't name a file (no '/' or a trailing one)
+pub fn split_container_path(path: &str) -> Option<(&str, &str)> {
+    match path.rsplit_once('/') {
+        Some((_, "")) | None => None,
+        Some(("", name)) => Some(("/", name)),
+        Some((dir, name)) => Some((dir, name)),
+    }
+}
+
+/// Header of the file at `path` in a container, or None if nothing is there yet.
+/// Only the archive's first header is read, not the file itself.
+async fn container_file_entry(
+    docker: &Docker,
+    id: &str,
+    path: &str,
+) -> Result<Option<tar::TarEntry>, CommandResponse<()>> {
+    let options = bollard::query_parameters::DownloadFromContainerOptions {
+        path: path.to_string(),
+    };
+    let mut stream = docker.download_from_container(id, Some(options));
+    let mut archive = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            Ok(bytes) => archive.extend_from_slice(&bytes),
+            Err(bollard::errors::Error::DockerResponseServerError {
+                status_code: 404, ..
+            }) => return Ok(None),
+            Err(e) => return Err(CommandResponse::docker_err(e)),
+        }
+        if let Some(entry) = tar::first_entry(&archive).map_err(CommandResponse::err)? {
+            return Ok(Some(entry));
+        }
+    }
+    Err(CommandResponse::err(format!("{} could not be read", path)))
+}
+
+/// Create or replace a small text file (up to `MAX_CONTAINER_FILE_BYTES`) in a
+/// container, e.g. to fix a config from the UI. A replaced file keeps its mode and
+/// owner; a new one gets mode 0644 and root as owner. The parent directory must
+/// already exist.
+#[tauri::command]
+async fn write_container_file(
+    id: String,
+    path: String,
+    contents: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    if let Err(e) = validate_container_path(&path) {
+        return Ok(CommandResponse::err(e));
+    }
+    if contents.len() as u64 > MAX_CONTAINER_FILE_BYTES {
+        return Ok(CommandResponse::err(format!(
+            "Contents exceed the {} limit",
+            format_bytes(MAX_CONTAINER_FILE_BYTES as i64)
+        )));
+    }
+    let Some((dir, name)) = split_container_path(&path) else {
+        return Ok(CommandResponse::err(format!("Not a file path: {}", path)));
+    };
+    let (mode, uid, gid) = match container_file_entry(&docker, &id, &path).await {
+        Ok(None) => (0o644, 0, 0),
+        Ok(Some(e)) if e.kind == tar::TarEntryKind::File => (e.mode, e.uid, e.gid),
+        Ok(Some(_)) => {
+            return Ok(CommandResponse::err(format!(
+                "{} is not a regular file",
+                path
+            )))
+        }
+        Err(resp) => return Ok(resp),
+    };
+
+    let mtime = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let mut archive = tar::TarBuilder::new();
+    if let Err(e) = archive.append_owned_file(name, contents.as_bytes(), mode, uid, gid, mtime) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let options = bollard::query_parameters::UploadToContainerOptions {
+        path: dir.to_string(),
+        ..Default::default()
+    };
+    match docker
+        .upload_to_container(
+            &id,
+            Some(options),
+            bollard::body_full(archive.finish().into()),
+        )
+        .await
+    {
+        Ok(()) => Ok(CommandResponse::ok_empty()),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Write a container's full inspect output as pretty-printed JSON to `dest`, e.g. to
+/// attach to a bug report, and return the number of bytes written
+#[tauri::command]
+async fn export_container_inspect(
+    id: String,
+    dest: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<u64>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    let path = match validate_export_path(&dest) {
+        Ok(p) => p,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+
+    let info = match docker.inspect_container(&id, None).await {
+        Ok(info) => info,
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
+    };
+    let json = match serde_json::to_string_pretty(&info) {
+        Ok(json) => json,
+        Err(e) => return Ok(CommandResponse::err(e.to_string())),
+    };
+    match std::fs::write(&path, &json) {
+        Ok(()) => Ok(CommandResponse::ok(json.len() as u64)),
+        Err(e) => Ok(CommandResponse::err(format!(
+            "Failed to write {}: {}",
+            dest, e
+        ))),
+    }
+}
+
+/// Bytes written between `log-export-{session_id}` progress events
+const LOG_EXPORT_PROGRESS_BYTES: u64 = 1024 * 1024;
+
+/// Progress of an `export_logs` call
+#[derive(Serialize, Clone)]
+struct LogExportProgress {
+    bytes_written: u64,
+    done: bool,
+}
+
+/// Write a container's full log history (stdout and stderr, oldest first) to `dest`
+/// and return the number of bytes written. Output is streamed to the file chunk by
+/// chunk on a blocking thread, so multi-gigabyte logs never sit in memory or stall
+/// the runtime; progress is reported on
+/// `log-export-{session_id}`. A partially written file is removed on failure.
+#[tauri::command]
+async fn export_logs(
+    id: String,
+    dest: String,
+    session_id: String,
+    timestamps: Option<bool>,
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<u64>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    let path = match validate_export_path(&dest) {
+        Ok(p) => p,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+
+    let file = match ExportFile::create(path) {
+        Ok(f) => f,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+
+    let options = LogsOptions {
+        follow: false,
+        stdout: true,
+        stderr: true,
+        timestamps: timestamps.unwrap_or(false),
+        tail: "all".to_string(),
+        ..Default::default()
+    };
+    let event_name = format!("log-export-{}", session_id);
+    let mut written: u64 = 0;
+    let mut next_report = LOG_EXPORT_PROGRESS_BYTES;
+    let mut stream = docker.logs(&id, Some(options));
+    while let Some(chunk) = stream.next().await {
+        let chunk = match chunk {
+            Ok(output) => output.into_bytes(),
+            Err(e) => {
+                file.abort().await;
+                return Ok(CommandResponse::docker_err(e));
+            }
+        };
+        written += chunk.len() as u64;
+        if !file.write(chunk).await {
+            break;
+        }
+        if written >= next_report {
+            next_report = written + LOG_EXPORT_PROGRESS_BYTES;
+            let _ = app_handle.emit(
+                &event_name,
+                LogExportProgress {
+                    bytes_written: written,
+                    done: false,
+                },
+            );
+        }
+    }
+
+    match file.finish().await {
+        Ok(()) => {
+            let _ = app_handle.emit(
+                &event_name,
+                LogExportProgress {
+                    bytes_written: written,
+                    done: true,
+                },
+            );
+            Ok(CommandResponse::ok(written))
+        }
+        Err(e) => Ok(CommandResponse::err(e)),
+    }
+}
+
+/// Block until a container exits and return its exit code.
+/// The client is cloned out of `DockerState` so no lock is held while waiting.
+#[tauri::command]
+async fn wait_container(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<i64>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let mut stream = docker.wait_container(&id, None::<WaitContainerOptions>);
+    let mut status_code = None;
+    while let Some(res) = stream.next().await {
+        match res {
+            Ok(response) => status_code = Some(response.status_code),
+            // bollard reports non-zero exit codes as errors; for us they are a result
+            Err(bollard::errors::Error::DockerContainerWaitError { code, .. }) => {
+                status_code = Some(code)
+            }
+            Err(e) => return Ok(CommandResponse::docker_err(e)),
+        }
+    }
+
+    match status_code {
+        Some(code) => Ok(CommandResponse::ok(code)),
+        None => Ok(CommandResponse::err(
+            "Container wait ended without a status",
+        )),
+    }
+}
+
+/// Healthcheck state of a container; `status` is "none" when it has no healthcheck
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ContainerHealth {
+    pub status: String,
+    pub failing_streak: i64,
+    /// Output of the most recent probe, useful for explaining an unhealthy badge
+    pub last_output: Option<String>,
+    pub last_exit_code: Option<i64>,
+}
+
+/// Extract the structured health from an inspect `State`, instead of parsing
+/// "(healthy)" out of the list status string
+pub fn container_health(state: Option<&bollard::models::ContainerState>) -> ContainerHealth {
+    let health = state.and_then(|s| s.health.as_ref());
+    let status = health
+        .and_then(|h| h.status)
+        .filter(|s| *s != bollard::models::HealthStatusEnum::EMPTY)
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "none".to_string());
+    let last = health
+        .and_then(|h| h.log.as_ref())
+        .and_then(|log| log.last());
+    ContainerHealth {
+        status,
+        failing_streak: health.and_then(|h| h.failing_streak).unwrap_or(0),
+        last_output: last.and_then(|r| r.output.clone()),
+        last_exit_code: last.and_then(|r| r.exit_code),
+    }
+}
+
+/// Reliable health for one container, fetched on demand so listing stays a single call
+#[tauri::command]
+async fn get_container_health(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<ContainerHealth>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    match docker.inspect_container(&id, None).await {
+        Ok(info) => Ok(CommandResponse::ok(container_health(info.state.as_ref()))),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Why a container last exited. When `success` is false the inspect itself failed
+/// and `error` holds that failure instead of the container's own error.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ExitInfo {
+    pub id: String,
+    pub success: bool,
+    pub exit_code: Option<i64>,
+    pub error: Option<String>,
+    pub oom_killed: bool,
+    pub finished_at: Option<String>,
+}
+
+/// Timestamp the daemon reports for containers that never finished
+const ZERO_TIMESTAMP: &str = "0001-01-01T00:00:00Z";
+
+/// Pull the exit details out of an inspect `State`
+pub fn exit_info(id: String, state: Option<&bollard::models::ContainerState>) -> ExitInfo {
+    ExitInfo {
+        id,
+        success: true,
+        exit_code: state.and_then(|s| s.exit_code),
+        error: state
+            .and_then(|s| s.error.clone())
+            .filter(|e| !e.is_empty()),
+        oom_killed: state.and_then(|s| s.oom_killed).unwrap_or(false),
+        finished_at: state
+            .and_then(|s| s.finished_at.clone())
+            .filter(|t| !t.is_empty() && t != ZERO_TIMESTAMP),
+    }
+}
+
+/// Inspect several containers concurrently and report how each last exited,
+/// including whether it was OOM-killed.
+#[tauri::command]
+async fn get_exit_info(
+    ids: Vec<String>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<ExitInfo>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    for id in &ids {
+        if let Err(e) = validate_docker_id(id) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+
+    let mut futures = FuturesUnordered::new();
+    for id in ids {
+        let docker_clone = docker.clone();
+        futures.push(async move {
+            match docker_clone.inspect_container(&id, None).await {
+                Ok(info) => exit_info(id, info.state.as_ref()),
+                Err(e) => ExitInfo {
+                    id,
+                    success: false,
+                    exit_code: None,
+                    error: Some(e.to_string()),
+                    oom_killed: false,
+                    finished_at: None,
+                },
+            }
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(res) = futures.next().await {
+        results.push(res);
+    }
+
+    Ok(CommandResponse::ok(results))
+}
+
+/// How often a container has been restarted and when it last ran, for spotting
+/// crash loops. Timestamps are None for a container that never started or finished.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct RestartInfo {
+    pub restart_count: i64,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub status: Option<String>,
+}
+
+pub fn restart_info(info: &ContainerInspectResponse) -> RestartInfo {
+    let state = info.state.as_ref();
+    let timestamp =
+        |t: Option<&String>| t.filter(|t| !t.is_empty() && *t != ZERO_TIMESTAMP).cloned();
+    RestartInfo {
+        restart_count: info.restart_count.unwrap_or(0),
+        started_at: timestamp(state.and_then(|s| s.started_at.as_ref())),
+        finished_at: timestamp(state.and_then(|s| s.finished_at.as_ref())),
+        status: state.and_then(|s| s.status).map(|s| s.to_string()),
+    }
+}
+
+/// Restart count and last start/finish times of a container
+#[tauri::command]
+async fn get_container_restart_info(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<RestartInfo>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    match docker.inspect_container(&id, None).await {
+        Ok(info) => Ok(CommandResponse::ok(restart_info(&info))),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Map a restart policy name from the UI to the bollard enum
+fn parse_restart_policy(policy: &str) -> Result<RestartPolicyNameEnum, String> {
+    match policy {
+        "no" => Ok(RestartPolicyNameEnum::NO),
+        "always" => Ok(RestartPolicyNameEnum::ALWAYS),
+        "unless-stopped" => Ok(RestartPolicyNameEnum::UNLESS_STOPPED),
+        "on-failure" => Ok(RestartPolicyNameEnum::ON_FAILURE),
+        _ => Err(format!("Invalid restart policy: {}", policy)),
+    }
+}
+
+/// Change a container's restart policy in place. Invalid combinations such as
+/// `max_retries` with a policy other than "on-failure" are rejected by the daemon.
+#[tauri::command]
+async fn set_restart_policy(
+    id: String,
+    policy: String,
+    max_retries: Option<i64>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    let name = match parse_restart_policy(&policy) {
+        Ok(n) => n,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+
+    let config = ContainerUpdateBody {
+        restart_policy: Some(RestartPolicy {
+            name: Some(name),
+            maximum_retry_count: max_retries,
+        }),
+        ..Default::default()
+    };
+
+    match docker.update_container(&id, config).await {
+        Ok(_) => Ok(CommandResponse::ok_empty()),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Quote an argument for a POSIX shell, leaving plain words as they are
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_./:=@%+,-".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Reconstruct a `docker run` command line for a container: name, restart policy,
+/// network, published ports, binds, env, entrypoint and command. Env entries, an
+/// entrypoint and a command that only came from the image (`image_config`) are left
+/// out, as `docker run` applies them anyway. `--entrypoint` takes a single word, so
+/// the rest of a longer entrypoint goes ahead of the command; it also drops the
+/// image's command, which is then always spelled out. Best-effort: settings without
+/// a flag here (healthchecks, resource limits, `--mount`, ...) are not reproduced.
+pub fn run_command(info: &ContainerInspectResponse, image_config: Option<&ImageConfig>) -> String {
+    let config = info.config.clone().unwrap_or_default();
+    let host_config = info.host_config.clone().unwrap_or_default();
+    let mut args: Vec<String> = vec!["docker".into(), "run".into(), "-d".into()];
+    let mut flag = |name: &str, value: String| {
+        args.push(name.to_string());
+        args.push(value);
+    };
+
+    if let Some(name) = info.name.as_deref().map(|n| n.trim_start_matches('/')) {
+        if !name.is_empty() {
+            flag("--name", name.to_string());
+        }
+    }
+    if let Some(policy) = &host_config.restart_policy {
+        match policy.name {
+            Some(RestartPolicyNameEnum::ON_FAILURE) => match policy.maximum_retry_count {
+                Some(n) if n > 0 => flag("--restart", format!("on-failure:{}", n)),
+                _ => flag("--restart", "on-failure".to_string()),
+            },
+            Some(
+                name @ (RestartPolicyNameEnum::ALWAYS | RestartPolicyNameEnum::UNLESS_STOPPED),
+            ) => flag("--restart", name.to_string()),
+            _ => {}
+        }
+    }
+    if let Some(mode) = host_config
+        .network_mode
+        .filter(|m| !m.is_empty() && m != "default" && m != "bridge")
+    {
+        flag("--network", mode);
+    }
+
+    let mut ports: Vec<_> = host_config
+        .port_bindings
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    ports.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut published = Vec::new();
+    for (container_port, bindings) in ports {
+        let container_port = container_port.trim_end_matches("/tcp").to_string();
+        for binding in bindings.unwrap_or_default() {
+            let host_port = binding.host_port.unwrap_or_default();
+            // The daemon lists the same port for both IPv4 and IPv6 wildcards
+            let spec = match binding.host_ip.as_deref() {
+                Some(ip) if !ip.is_empty() && ip != "0.0.0.0" && ip != "::" => {
+                    format!("{}:{}:{}", ip, host_port, container_port)
+                }
+                _ if !host_port.is_empty() => format!("{}:{}", host_port, container_port),
+                _ => container_port.clone(),
+            };
+            if !published.contains(&spec) {
+                published.push(spec);
+            }
+        }
+    }
+    for spec in published {
+        flag("-p", spec);
+    }
+    for bind in host_config.binds.unwrap_or_default() {
+        flag("-v", bind);
+    }
+
+    let image_env = image_config.and_then(|c| c.env.clone()).unwrap_or_default();
+    for entry in config.env.unwrap_or_default() {
+        if !image_env.contains(&entry) {
+            flag("-e", entry);
+        }
+    }
+
+    let entrypoint = config.entrypoint.unwrap_or_default();
+    let image_entrypoint = image_config
+        .and_then(|c| c.entrypoint.clone())
+        .unwrap_or_default();
+    let entrypoint_changed = entrypoint != image_entrypoint;
+    if entrypoint_changed {
+        flag(
+            "--entrypoint",
+            entrypoint.first().cloned().unwrap_or_default(),
+        );
+    }
+
+    args.push(
+        config
+            .image
+            .or_else(|| info.image.clone())
+            .unwrap_or_default(),
+    );
+    if entrypoint_changed {
+        args.extend(entrypoint.into_iter().skip(1));
+    }
+    let image_cmd = image_config.and_then(|c| c.cmd.as_ref());
+    if let Some(cmd) = config
+        .cmd
+        .filter(|cmd| entrypoint_changed || Some(cmd) != image_cmd)
+    {
+        args.extend(cmd);
+    }
+
+    args.iter()
+        .map(|a| shell_quote(a))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// An approximate `docker run` command that recreates the container elsewhere.
+/// See `run_command` for what is and isn't covered.
+#[tauri::command]
+async fn get_run_command(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let info = match docker.inspect_container(&id, None).await {
+        Ok(info) => info,
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
+    };
+    // Without the image config every inherited env entry is spelled out, which is
+    // verbose but still correct
+    let image_config = match &info.image {
+        Some(image) => docker
+            .inspect_image(image)
+            .await
+            .ok()
+            .and_then(|i| i.config),
+        None => None,
+    };
+    Ok(CommandResponse::ok(run_command(
+        &info,
+        image_config.as_ref(),
+    )))
+}
+
+/// What a container runs on start. Empty fields mean the image sets none.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ContainerCommand {
+    pub entrypoint: Vec<String>,
+    pub cmd: Vec<String>,
+    pub working_dir: String,
+}
+
+pub fn container_command(info: &ContainerInspectResponse) -> ContainerCommand {
+    let config = info.config.clone().unwrap_or_default();
+    ContainerCommand {
+        entrypoint: config.entrypoint.unwrap_or_default(),
+        cmd: config.cmd.unwrap_or_default(),
+        working_dir: config.working_dir.unwrap_or_default(),
+    }
+}
+
+/// Entrypoint, cmd and working directory of a container, without the rest of inspect
+#[tauri::command]
+async fn get_container_command(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<ContainerCommand>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    match docker.inspect_container(&id, None).await {
+        Ok(info) => Ok(CommandResponse::ok(container_command(&info))),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Substrings that mark an environment variable name as holding a credential
+const SECRET_ENV_MARKERS: &[&str] = &["PASSWORD", "TOKEN", "KEY", "SECRET"];
+
+/// Mask the value of a `KEY=value` entry when the key looks like a secret
+fn redact_env_entry(entry: &str) -> String {
+    match entry.split_once('=') {
+        Some((key, _)) => {
+            let upper = key.to_ascii_uppercase();
+            if SECRET_ENV_MARKERS.iter().any(|m| upper.contains(m)) {
+                format!("{}=********", key)
+            } else {
+                entry.to_string()
+            }
+        }
+        None => entry.to_string(),
+    }
+}
+
+/// The environment a container was started with, optionally with secrets masked
+#[tauri::command]
+async fn get_container_env(
+    id: String,
+    redact: Option<bool>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<String>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    match docker.inspect_container(&id, None).await {
+        Ok(info) => {
+            let env = info.config.and_then(|c| c.env).unwrap_or_default();
+            let env = if redact.unwrap_or(false) {
+                env.iter().map(|e| redact_env_entry(e)).collect()
+            } else {
+                env
+            };
+            Ok(CommandResponse::ok(env))
+        }
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Dangling (`<none>:<none>`) images are hidden unless `show_dangling` is set;
+/// `all` also includes intermediate build layers.
+#[tauri::command]
+async fn list_images(
+    label_filters: Option<Vec<String>>,
+    show_dangling: Option<bool>,
+    all: Option<bool>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<bollard::models::ImageSummary>>, String> {
+    let mut filters = HashMap::new();
+    if let Err(e) = add_label_filters(&mut filters, label_filters) {
+        return Ok(CommandResponse::err(e));
+    }
+    // Hide untagged `<none>:<none>` images unless asked for
+    if !show_dangling.unwrap_or(false) {
+        filters.insert("dangling".to_string(), vec!["false".to_string()]);
+    }
+    let options = Some(ListImagesOptions {
+        all: all.unwrap_or(false),
+        filters: Some(filters),
+        ..Default::default()
+    });
+
+    let list = |docker: Docker| {
+        let options = options.clone();
+        async move { docker.list_images(options).await }
+    };
+    match with_retry(&docker_state, list).await {
+        Ok(images) => Ok(CommandResponse::ok(images)),
+        Err(resp) => Ok(resp),
+    }
+}
+
+/// A volume plus its usage size formatted for display
+#[derive(Serialize)]
+struct VolumeInfo {
+    #[serde(flatten)]
+    volume: bollard::models::Volume,
+    size_human: Option<String>,
+}
+
+#[tauri::command]
+async fn list_volumes(
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<VolumeInfo>>, String> {
+    let list =
+        |docker: Docker| async move { docker.list_volumes(None::<ListVolumesOptions>).await };
+    let volumes = match with_retry(&docker_state, list).await {
+        Ok(res) => res.volumes.unwrap_or_default(),
+        Err(resp) => return Ok(resp),
+    };
+
+    Ok(CommandResponse::ok(volume_infos(
+        volumes,
+        &docker_state.get_path(),
+    )))
+}
+
+/// Attach usage data (size, reference count) to volumes for display. `path` is the
+/// connection path, used to point the docker CLI at the same daemon.
+fn volume_infos(mut volumes: Vec<bollard::models::Volume>, path: &str) -> Vec<VolumeInfo> {
+    // Use CLI directly for usage data as API is unreliable for this specific data
+    log::info!("Fetching volume usage data via CLI");
+    let mut cmd = std::process::Command::new("docker");
+    
+    if let Some(host) = docker_cli_host(path) {
+        cmd.arg("-H").arg(host);
+    }
+
+    cmd.args(["system", "df", "-v", "--format", "{{json .Volumes}}"]);
+
+    match cmd.output() {
+        Ok(output) => {
+            if output.status.success() {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                // Output is a JSON array of objects
+                if let Ok(cli_items) = serde_json::from_str::<Vec<serde_json::Value>>(&stdout) {
+                    log::info!("Found {} volume usage items via CLI", cli_items.len());
+                    for item in cli_items {
+                            let name = item.get("Name")
+                            .or_else(|| item.get("name"))
+                            .and_then(|v| v.as_str());
+
+                        let usage_data = item.get("UsageData")
+                            .or_else(|| item.get("usageData"));
+                        
+                        // Also check for "Size" directly if UsageData is not nested
+                        // CLI format might put Size at top level
+                        let direct_size = item.get("Size").and_then(|v| v.as_str());
+
+                        if let Some(name) = name {
+                            if let Some(vol) = volumes.iter_mut().find(|v| v.name == name) {
+                                if let Some(usage_val) = usage_data {
+                                    if let Ok(usage) = serde_json::from_value::<bollard::models::VolumeUsageData>(usage_val.clone()) {
+                                        vol.usage_data = Some(usage);
+                                    }
+                                } else if let Some(size_str) = direct_size {
+                                    let size_bytes = parse_docker_size(size_str);
+                                    // Try to get ref count too
+                                    let ref_count = item.get("Links")
+                                        .and_then(|v| v.as_i64())
+                                        .unwrap_or(-1);
+
+                                    let usage = bollard::models::VolumeUsageData {
+                                        size: size_bytes,
+                                        ref_count,
+                                    };
+                                    vol.usage_data = Some(usage);
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    log::warn!("Failed to parse CLI JSON output: {}", stdout);
+                }
+            } else {
+                    log::warn!("CLI command failed: {}", String::from_utf8_lossy(&output.stderr));
+            }
+        },
+        Err(e) => {
+            log::warn!("Failed to execute docker CLI: {}", e);
+        }
+    }
+
+    volumes
+        .into_iter()
+        .map(|volume| {
+            // Docker reports -1 when the size hasn't been calculated
+            let size_human = volume
+                .usage_data
+                .as_ref()
+                .filter(|u| u.size >= 0)
+                .map(|u| format_bytes(u.size));
+            VolumeInfo { volume, size_human }
+        })
+        .collect()
+}
+
+/// Volumes no container references, with their sizes, so users can review what
+/// a volume prune would delete and how much space it would free
+#[tauri::command]
+async fn list_dangling_volumes(
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<VolumeInfo>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+
+    let options = ListVolumesOptions {
+        filters: Some(HashMap::from([(
+            "dangling".to_string(),
+            vec!["true".to_string()],
+        )])),
+    };
+    let volumes = match docker.list_volumes(Some(options)).await {
+        Ok(res) => res.volumes.unwrap_or_default(),
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
+    };
+
+    Ok(CommandResponse::ok(volume_infos(
+        volumes,
+        &docker_state.get_path(),
+    )))
+}
+
+/// Map a `DockerState` connection path to the `-H` value for the docker CLI.
+/// Returns None for the default connection so the CLI resolves it itself.
+fn docker_cli_host(path: &str) -> Option<String> {
+    if path == "default" || path.is_empty() {
+        return None;
+    }
+    let path = path.strip_prefix("podman:").unwrap_or(path);
+    if path.contains("://") {
+        return Some(path.to_string());
+    }
+    Some(format!("unix://{}", path))
+}
+
+/// Compose project names: lowercase letters, digits, `-` and `_`, starting with a letter or digit
+pub fn validate_compose_project(project: &str) -> Result<(), String> {
+    let valid = project.len() <= 128
+        && project
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && project
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("Invalid compose project name: {}", project));
+    }
+    Ok(())
+}
+
+/// Check the compose file exists and the project name (if any) is valid
+fn validate_compose_args(file_path: &str, project: Option<&str>) -> Result<(), String> {
+    if !std::path::Path::new(file_path).is_file() {
+        return Err(format!("Compose file not found: {}", file_path));
+    }
+    if let Some(project) = project {
+        validate_compose_project(project)?;
+    }
+    Ok(())
+}
+
+/// Forward each line of a compose output stream to `event_name`
+async fn forward_compose_output(
+    output: impl tokio::io::AsyncRead + Unpin,
+    app_handle: tauri::AppHandle,
+    event_name: String,
+) {
+    use tokio::io::AsyncBufReadExt;
+
+    let mut lines = tokio::io::BufReader::new(output).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let _ = app_handle.emit(&event_name, line);
+    }
+}
+
+/// Run `docker compose -f <file> [-p <project>] <args>` against the current connection,
+/// streaming stdout and stderr lines on `compose-{session_id}` until it exits.
+async fn run_compose(
+    file_path: &str,
+    project: Option<&str>,
+    args: &[&str],
+    session_id: &str,
+    app_handle: tauri::AppHandle,
+    path: &str,
+) -> Result<(), String> {
+    let mut cmd = tokio::process::Command::new("docker");
+    if let Some(host) = docker_cli_host(path) {
+        cmd.arg("-H").arg(host);
+    }
+    cmd.args(["compose", "-f", file_path]);
+    if let Some(project) = project {
+        cmd.args(["-p", project]);
+    }
+    cmd.args(args)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to run docker compose: {}", e))?;
+
+    // Compose reports progress on stderr, so forward both streams
+    let event_name = format!("compose-{}", session_id);
+    let mut forwarders = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        forwarders.push(tauri::async_runtime::spawn(forward_compose_output(
+            stdout,
+            app_handle.clone(),
+            event_name.clone(),
+        )));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forwarders.push(tauri::async_runtime::spawn(forward_compose_output(
+            stderr, app_handle, event_name,
+        )));
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("Failed to wait for docker compose: {}", e))?;
+    // Let the last lines reach the frontend before reporting the result
+    for forwarder in forwarders {
+        let _ = forwarder.await;
+    }
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("docker compose {} exited with {}", args[0], status))
+    }
+}
+
+/// `docker compose up -d` for a compose file, streaming output on `compose-{session_id}`
+#[tauri::command]
+async fn compose_up(
+    file_path: String,
+    project: Option<String>,
+    session_id: String,
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<()>, String> {
+    if let Err(e) = validate_compose_args(&file_path, project.as_deref()) {
+        return Ok(CommandResponse::err(e));
+    }
+    let path = docker_state.get_path();
+
+    match run_compose(
+        &file_path,
+        project.as_deref(),
+        &["up", "-d"],
+        &session_id,
+        app_handle,
+        &path,
+    )
+    .await
+    {
+        Ok(()) => Ok(CommandResponse::ok_empty()),
+        Err(e) => Ok(CommandResponse::err(e)),
+    }
+}
+
+/// `docker compose down` for a compose file, streaming output on `compose-{session_id}`
+#[tauri::command]
+async fn compose_down(
+    file_path: String,
+    project: Option<String>,
+    session_id: String,
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<()>, String> {
+    if let Err(e) = validate_compose_args(&file_path, project.as_deref()) {
+        return Ok(CommandResponse::err(e));
+    }
+    let path = docker_state.get_path();
+
+    match run_compose(
+        &file_path,
+        project.as_deref(),
+        &["down"],
+        &session_id,
+        app_handle,
+        &path,
+    )
+    .await
+    {
+        Ok(()) => Ok(CommandResponse::ok_empty()),
+        Err(e) => Ok(CommandResponse::err(e)),
+    }
+}
+
+/// What `prune_project` removed, plus the resources it failed to remove
+#[derive(Serialize, Default)]
+struct ProjectPruneSummary {
+    containers: Vec<String>,
+    networks: Vec<String>,
+    volumes: Vec<String>,
+    errors: Vec<String>,
+}
+
+/// Stop and remove every container and network labelled with a Compose project,
+/// and its volumes too when `volumes` is set, like `docker compose down [-v]` but
+/// through the API without the compose file. Failures are collected per resource
+/// instead of aborting, so one stuck container doesn't leave the rest behind.
+#[tauri::command]
+async fn prune_project(
+    project: String,
+    volumes: Option<bool>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<ProjectPruneSummary>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_compose_project(&project) {
+        return Ok(CommandResponse::err(e));
+    }
+    let remove_volumes = volumes.unwrap_or(false);
+    let filters = HashMap::from([(
+        "label".to_string(),
+        vec![format!("{}={}", COMPOSE_PROJECT_LABEL, project)],
+    )]);
+    let mut summary = ProjectPruneSummary::default();
+
+    let containers = match docker
+        .list_containers(Some(ListContainersOptions {
+            all: true,
+            filters: Some(filters.clone()),
+            ..Default::default()
+        }))
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
+    };
+    // Containers go down concurrently, so the stop timeouts don't add up
+    let mut futures = FuturesUnordered::new();
+    for container in containers {
+        let Some(id) = container.id else { continue };
+        let name = container
+            .names
+            .and_then(|n| n.into_iter().next())
+            .map(|n| n.trim_start_matches('/').to_string())
+            .unwrap_or_else(|| id.clone());
+        let docker_clone = docker.clone();
+        futures.push(async move {
+            // Stop first so the processes get their stop signal rather than a SIGKILL
+            if let Err(e) = docker_clone.stop_container(&id, None).await {
+                if docker_error_code(&e) != "NOT_MODIFIED" {
+                    log::warn!("Failed to stop {} before removing it: {}", name, e);
+                }
+            }
+            let options = RemoveContainerOptions {
+                force: true,
+                v: remove_volumes,
+                ..Default::default()
+            };
+            let res = docker_clone.remove_container(&id, Some(options)).await;
+            (name, res)
+        });
+    }
+    while let Some((name, res)) = futures.next().await {
+        match res {
+            Ok(_) => summary.containers.push(name),
+            Err(e) => summary.errors.push(format!("Container {}: {}", name, e)),
+        }
+    }
+
+    match docker
+        .list_networks(Some(ListNetworksOptions {
+            filters: Some(filters.clone()),
+        }))
+        .await
+    {
+        Ok(networks) => {
+            for name in networks.into_iter().filter_map(|n| n.name) {
+                match docker.remove_network(&name).await {
+                    Ok(_) => summary.networks.push(name),
+                    Err(e) => summary.errors.push(format!("Network {}: {}", name, e)),
+                }
+            }
+        }
+        Err(e) => summary
+            .errors
+            .push(format!("Failed to list networks: {}", e)),
+    }
+
+    if remove_volumes {
+        match docker
+            .list_volumes(Some(ListVolumesOptions {
+                filters: Some(filters),
+            }))
+            .await
+        {
+            Ok(res) => {
+                for volume in res.volumes.unwrap_or_default() {
+                    match docker
+                        .remove_volume(&volume.name, None::<RemoveVolumeOptions>)
+                        .await
+                    {
+                        Ok(_) => summary.volumes.push(volume.name),
+                        Err(e) => summary
+                            .errors
+                            .push(format!("Volume {}: {}", volume.name, e)),
+                    }
+                }
+            }
+            Err(e) => summary
+                .errors
+                .push(format!("Failed to list volumes: {}", e)),
+        }
+    }
+
+    Ok(CommandResponse::ok(summary))
+}
+
+/// Helper to parse Docker's human-readable size strings (e.g. "10MB", "5.5GB", "1024B")
+fn parse_docker_size(s: &str) -> i64 {
+    let s = s.trim();
+    if s.is_empty() { return 0; }
+
+    let digits: String = s.chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    
+    let unit = s[digits.len()..].trim();
+    let val: f64 = digits.parse().unwrap_or(0.0);
+
+    let multiplier: f64 = match unit {
+        "B" => 1.0,
+        "kB" | "KB" | "kb" => 1000.0,
+        "MB" | "mb" => 1000.0 * 1000.0,
+        "GB" | "gb" => 1000.0 * 1000.0 * 1000.0,
+        "TB" | "tb" => 1000.0 * 1000.0 * 1000.0 * 1000.0,
+        "KiB" | "KIB" => 1024.0,
+        "MiB" | "MIB" => 1024.0 * 1024.0,
+        "GiB" | "GIB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" | "TIB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => 1.0,
+    };
+
+    (val * multiplier) as i64
+}
+
+/// Format a byte count with binary units (e.g. 1536 -> "1.5 KiB"), the inverse of
+/// `parse_docker_size`. Values below 1 KiB are shown as whole bytes.
+pub fn format_bytes(n: i64) -> String {
+    const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+    let sign = if n < 0 { "-" } else { "" };
+    let bytes = n.unsigned_abs();
+    if bytes < 1024 {
+        return format!("{}{} B", sign, bytes);
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    // Promote when rounding to one decimal would print "1024.0" of the smaller unit
+    while (value * 10.0).round() / 10.0 >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{}{:.1} {}", sign, value, UNITS[unit])
+}
+
+#[tauri::command]
+async fn list_networks(
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<bollard::models::Network>>, String> {
+    let list =
+        |docker: Docker| async move { docker.list_networks(None::<ListNetworksOptions>).await };
+    match with_retry(&docker_state, list).await {
+        Ok(networks) => Ok(CommandResponse::ok(networks)),
+        Err(resp) => Ok(resp),
+    }
+}
+
+/// Create a network tagged with `labels` and the app's own label; `driver` defaults
+/// to the daemon's (bridge). Returns the new network id.
+#[tauri::command]
+async fn create_network(
+    name: String,
+    driver: Option<String>,
+    labels: Option<HashMap<String, String>>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&name) {
+        return Ok(CommandResponse::err(e));
+    }
+    if let Some(ref d) = driver {
+        if let Err(e) = validate_docker_id(d) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+    let labels = match resource_labels(labels) {
+        Ok(l) => l,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+
+    let request = bollard::models::NetworkCreateRequest {
+        name,
+        driver,
+        labels: Some(labels),
+        ..Default::default()
+    };
+    match docker.create_network(request).await {
+        Ok(res) => Ok(CommandResponse::ok(res.id)),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// A container referencing a volume or network
+#[derive(Serialize, Debug, PartialEq)]
+struct ResourceUser {
+    id: String,
+    name: String,
+    state: String,
+}
+
+fn resource_user(c: &bollard::models::ContainerSummary) -> ResourceUser {
+    ResourceUser {
+        id: c.id.clone().unwrap_or_default(),
+        name: c
+            .names
+            .as_ref()
+            .and_then(|n| n.first())
+            .map(|n| n.trim_start_matches('/').to_string())
+            .unwrap_or_default(),
+        state: c.state.map(|s| s.to_string()).unwrap_or_default(),
+    }
+}
+
+/// Containers with `volume` mounted
+fn volume_users(
+    containers: &[bollard::models::ContainerSummary],
+    volume: &str,
+) -> Vec<ResourceUser> {
+    containers
+        .iter()
+        .filter(|c| {
+            c.mounts
+                .iter()
+                .flatten()
+                .any(|m| m.name.as_deref() == Some(volume))
+        })
+        .map(resource_user)
+        .collect()
+}
+
+/// Containers attached to `network`, given by name, full id or id prefix
+fn network_users(
+    containers: &[bollard::models::ContainerSummary],
+    network: &str,
+) -> Vec<ResourceUser> {
+    containers
+        .iter()
+        .filter(|c| {
+            c.network_settings
+                .as_ref()
+                .and_then(|s| s.networks.as_ref())
+                .is_some_and(|networks| {
+                    networks.iter().any(|(name, endpoint)| {
+                        name == network
+                            || endpoint
+                                .network_id
+                                .as_deref()
+                                .is_some_and(|id| id.starts_with(network))
+                    })
+                })
+        })
+        .map(resource_user)
+        .collect()
+}
+
+async fn list_all_containers(
+    docker_state: &DockerState,
+) -> Result<Vec<bollard::models::ContainerSummary>, CommandResponse<Vec<ResourceUser>>> {
+    with_retry(docker_state, |docker: Docker| async move {
+        let options = Some(ListContainersOptions {
+            all: true,
+            ..Default::default()
+        });
+        docker.list_containers(options).await
+    })
+    .await
+}
+
+/// Containers (running or stopped) that mount the given volume, so the UI can warn
+/// before a removal fails with "volume is in use"
+#[tauri::command]
+async fn get_volume_users(
+    name: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<ResourceUser>>, String> {
+    if let Err(e) = validate_docker_id(&name) {
+        return Ok(CommandResponse::err(e));
+    }
+    match list_all_containers(&docker_state).await {
+        Ok(containers) => Ok(CommandResponse::ok(volume_users(&containers, &name))),
+        Err(resp) => Ok(resp),
+    }
+}
+
+/// Containers (running or stopped) attached to the given network
+#[tauri::command]
+async fn get_network_users(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<ResourceUser>>, String> {
+    if let Err(e) = validate_docker_id(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    match list_all_containers(&docker_state).await {
+        Ok(containers) => Ok(CommandResponse::ok(network_users(&containers, &id))),
+        Err(resp) => Ok(resp),
+    }
+}
+
+/// Dashboard counts gathered in one round trip. A field is None when its call failed,
+/// with the reason under the same key in `errors`.
+#[derive(Serialize, Default)]
+struct ResourceSummary {
+    containers_running: Option<usize>,
+    containers_total: Option<usize>,
+    images: Option<usize>,
+    volumes: Option<usize>,
+    networks: Option<usize>,
+    /// Bytes used by images, containers, volumes and build cache
+    disk_usage: Option<i64>,
+    /// Bytes used by images, only with `include_size`
+    images_size: Option<i64>,
+    images_size_human: Option<String>,
+    errors: HashMap<String, String>,
+}
+
+/// Sum the per-category totals of a `df` response; None if the daemon reported none
+fn disk_usage_total(df: &bollard::models::SystemDataUsageResponse) -> Option<i64> {
+    let totals = [
+        df.images_disk_usage.as_ref().and_then(|u| u.total_size),
+        df.containers_disk_usage.as_ref().and_then(|u| u.total_size),
+        df.volumes_disk_usage.as_ref().and_then(|u| u.total_size),
+        df.build_cache_disk_usage
+            .as_ref()
+            .and_then(|u| u.total_size),
+    ];
+    totals.into_iter().flatten().reduce(|a, b| a + b)
+}
+
+/// Disk space taken by images. The daemon's `df` figure counts shared layers once, so
+/// it is preferred; the fallback sums each image's reported size, which counts a
+/// layer again for every image built on it and so overstates the total.
+fn images_disk_usage(
+    df: Option<&bollard::models::SystemDataUsageResponse>,
+    images: Option<&[bollard::models::ImageSummary]>,
+) -> Option<i64> {
+    df.and_then(|df| df.images_disk_usage.as_ref())
+        .and_then(|u| u.total_size)
+        .or_else(|| images.map(|images| images.iter().map(|i| i.size).sum()))
+}
+
+/// Counts of containers, images, volumes and networks plus total disk usage, fetched
+/// concurrently so the dashboard needs a single call. With `include_size` the space
+/// taken by images is reported too, e.g. for "Images: 24 (3.1 GiB)".
+#[tauri::command]
+async fn get_resource_summary(
+    include_size: Option<bool>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<ResourceSummary>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+
+    let (containers, images, volumes, networks, df) = tokio::join!(
+        docker.list_containers(Some(ListContainersOptions {
+            all: true,
+            ..Default::default()
+        })),
+        docker.list_images(None::<ListImagesOptions>),
+        docker.list_volumes(None::<ListVolumesOptions>),
+        docker.list_networks(None::<ListNetworksOptions>),
+        docker.df(None::<bollard::query_parameters::DataUsageOptions>),
+    );
+
+    let mut summary = ResourceSummary::default();
+    if include_size.unwrap_or(false) {
+        summary.images_size = images_disk_usage(df.as_ref().ok(), images.as_deref().ok());
+        summary.images_size_human = summary.images_size.map(format_bytes);
+    }
+    match containers {
+        Ok(c) => {
+            summary.containers_total = Some(c.len());
+            summary.containers_running = Some(
+                c.iter()
+                    .filter(|c| {
+                        c.state == Some(bollard::models::ContainerSummaryStateEnum::RUNNING)
+                    })
+                    .count(),
+            );
+        }
+        Err(e) => {
+            summary
+                .errors
+                .insert("containers".to_string(), e.to_string());
+        }
+    }
+    match images {
+        Ok(i) => summary.images = Some(i.len()),
+        Err(e) => {
+            summary.errors.insert("images".to_string(), e.to_string());
+        }
+    }
+    match volumes {
+        Ok(v) => summary.volumes = Some(v.volumes.map_or(0, |v| v.len())),
+        Err(e) => {
+            summary.errors.insert("volumes".to_string(), e.to_string());
+        }
+    }
+    match networks {
+        Ok(n) => summary.networks = Some(n.len()),
+        Err(e) => {
+            summary.errors.insert("networks".to_string(), e.to_string());
+        }
+    }
+    match df {
+        Ok(df) => summary.disk_usage = disk_usage_total(&df),
+        Err(e) => {
+            summary
+                .errors
+                .insert("disk_usage".to_string(), e.to_string());
+        }
+    }
+
+    Ok(CommandResponse::ok(summary))
+}
+
+/// Whether `s` is a Go duration such as "24h", "1h30m" or "90s"
+fn is_go_duration(s: &str) -> bool {
+    const UNITS: [&str; 7] = ["ns", "us", "µs", "ms", "s", "m", "h"];
+    let mut rest = s;
+    if rest.is_empty() {
+        return false;
+    }
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        if number_len == 0 || rest[..number_len].parse::<f64>().is_err() {
+            return false;
+        }
+        rest = &rest[number_len..];
+        // Longest match first so "ms" isn't read as "m" followed by garbage
+        let Some(unit) = UNITS
+            .iter()
+            .filter(|u| rest.starts_with(*u))
+            .max_by_key(|u| u.len())
+        else {
+            return false;
+        };
+        rest = &rest[unit.len()..];
+    }
+    true
+}
+
+/// Validate a prune `until` value: a Go duration ("24h") or a timestamp, either Unix
+/// seconds ("1714557600") or RFC 3339 ("2024-05-01", "2024-05-01T10:00:00Z")
+fn validate_prune_until(until: &str) -> Result<(), String> {
+    let unix = !until.is_empty()
+        && until
+            .split_once('.')
+            .map_or(until, |(secs, _)| secs)
+            .chars()
+            .all(|c| c.is_ascii_digit())
+        && until.parse::<f64>().is_ok();
+    let bytes = until.as_bytes();
+    let rfc3339 = bytes.len() >= 10
+        && bytes.len() <= 35
+        && bytes[..4].iter().all(u8::is_ascii_digit)
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && until
+            .chars()
+            .all(|c| c.is_ascii_digit() || "-:T.Z+".contains(c));
+    if is_go_duration(until) || unix || rfc3339 {
+        Ok(())
+    } else {
+        Err(format!("Invalid until filter: {}", until))
+    }
+}
+
+/// Build the filters for `prune_images`. `all` prunes every unused image rather than
+/// only dangling ones; `until` keeps images created more recently than that.
+fn image_prune_filters(
+    until: Option<String>,
+    all: bool,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    let mut filters = HashMap::new();
+    if all {
+        filters.insert("dangling".to_string(), vec!["false".to_string()]);
+    }
+    if let Some(until) = until.filter(|u| !u.is_empty()) {
+        validate_prune_until(&until)?;
+        filters.insert("until".to_string(), vec![until]);
+    }
+    Ok(filters)
+}
+
+/// Remove unused images, optionally only those older than `until` (e.g. "24h")
+#[tauri::command]
+async fn prune_images(
+    until: Option<String>,
+    all: Option<bool>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<bollard::models::ImagePruneResponse>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    let filters = match image_prune_filters(until, all.unwrap_or(false)) {
+        Ok(f) => f,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+
+    let options = bollard::query_parameters::PruneImagesOptions {
+        filters: (!filters.is_empty()).then_some(filters),
+    };
+    match docker.prune_images(Some(options)).await {
+        Ok(res) => Ok(CommandResponse::ok(res)),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Remove an image. `force` also removes images used by stopped containers or
+/// carrying several tags; `no_prune` keeps untagged parents. Returns what was
+/// untagged vs deleted.
+#[tauri::command]
+async fn remove_image(
+    id: String,
+    force: Option<bool>,
+    no_prune: Option<bool>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<ImageDeleteResponseItem>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_image_ref(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let options = RemoveImageOptions {
+        force: force.unwrap_or(false),
+        noprune: no_prune.unwrap_or(false),
+        ..Default::default()
+    };
+
+    match docker.remove_image(&id, Some(options), None).await {
+        Ok(items) => Ok(CommandResponse::ok(items)),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// One filesystem layer of an image with the instruction that created it
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ImageLayer {
+    pub layer_id: String,
+    pub created_by: String,
+    pub size: i64,
+    pub size_human: String,
+}
+
+/// Dockerfile instructions that only change the image config and never add a layer
+const METADATA_INSTRUCTIONS: &[&str] = &[
+    "ARG",
+    "CMD",
+    "ENTRYPOINT",
+    "ENV",
+    "EXPOSE",
+    "HEALTHCHECK",
+    "LABEL",
+    "MAINTAINER",
+    "ONBUILD",
+    "SHELL",
+    "STOPSIGNAL",
+    "USER",
+    "VOLUME",
+];
+
+/// Whether a history entry added a layer, i.e. isn't `empty_layer` in the image config.
+/// The history API drops that flag, so it's recovered from the instruction: a step
+/// can add a layer of zero bytes, but metadata-only steps never add one.
+fn creates_layer(entry: &HistoryResponseItem) -> bool {
+    if entry.size > 0 {
+        return true;
+    }
+    // The classic builder records "/bin/sh -c #(nop)  ENV ..." for non-RUN steps
+    let step = entry.created_by.trim_start();
+    let step = step.strip_prefix("/bin/sh -c").unwrap_or(step).trim_start();
+    let step = step.strip_prefix("#(nop)").unwrap_or(step);
+    let instruction = step.split_whitespace().next().unwrap_or_default();
+    !METADATA_INSTRUCTIONS.contains(&instruction.to_ascii_uppercase().as_str())
+}
+
+/// Pair RootFS layer digests (oldest first) with `image_history` entries (newest first).
+/// History also lists metadata-only steps (ENV, CMD, ...) that add no layer; those are
+/// recognised by `creates_layer` and skipped. Pairing runs from the newest layer down,
+/// so when history is shorter than the layer list (squashed or imported images) the
+/// oldest layers are reported without an instruction or size. Sorted largest-first.
+pub fn image_layers(layers: &[String], history: &[HistoryResponseItem]) -> Vec<ImageLayer> {
+    let mut steps = history.iter().filter(|h| creates_layer(h));
+
+    let mut result: Vec<ImageLayer> = layers
+        .iter()
+        .rev()
+        .map(|layer_id| {
+            let (created_by, size) = steps
+                .next()
+                .map(|h| (h.created_by.clone(), h.size))
+                .unwrap_or_default();
+            ImageLayer {
+                layer_id: layer_id.clone(),
+                created_by,
+                size,
+                size_human: format_bytes(size),
+            }
+        })
+        .collect();
+
+    result.sort_by_key(|l| std::cmp::Reverse(l.size));
+    result
+}
+
+#[tauri::command]
+async fn get_image_layers(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<ImageLayer>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_image_ref(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let inspect = match docker.inspect_image(&id).await {
+        Ok(i) => i,
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
+    };
+    let history = match docker.image_history(&id).await {
+        Ok(h) => h,
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
+    };
+
+    let layers = inspect.root_fs.and_then(|r| r.layers).unwrap_or_default();
+    Ok(CommandResponse::ok(image_layers(&layers, &history)))
+}
+
+/// Immutable digests next to the mutable tags of an image
+#[derive(Serialize)]
+struct ImageDigests {
+    /// `repo@sha256:...` references; empty for images that were never pushed or pulled
+    repo_digests: Vec<String>,
+    repo_tags: Vec<String>,
+}
+
+#[tauri::command]
+async fn get_image_digests(
+    id: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<ImageDigests>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_image_ref(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    match docker.inspect_image(&id).await {
+        Ok(info) => Ok(CommandResponse::ok(ImageDigests {
+            repo_digests: info.repo_digests.unwrap_or_default(),
+            repo_tags: info.repo_tags.unwrap_or_default(),
+        })),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Registry and repository of a reference in canonical form, so `nginx`,
+/// `docker.io/library/nginx` and `index.docker.io/library/nginx` compare equal
+fn canonical_repository(reference: &str) -> Option<(String, String)> {
+    let parsed = parse_image_ref(reference).ok()?;
+    let registry = match parsed.registry.as_deref() {
+        None | Some("docker.io") | Some("index.docker.io") => "docker.io".to_string(),
+        Some(r) => r.to_string(),
+    };
+    let repository = if registry == "docker.io" && !parsed.repository.contains('/') {
+        format!("library/{}", parsed.repository)
+    } else {
+        parsed.repository
+    };
+    Some((registry, repository))
+}
+
+/// Pick the `RepoDigest` belonging to the repository `reference` names. An image
+/// pushed to several registries carries one digest per repository.
+fn matching_repo_digest(reference: &str, repo_digests: &[String]) -> Option<String> {
+    let wanted = canonical_repository(reference)?;
+    repo_digests
+        .iter()
+        .find(|digest| canonical_repository(digest).as_ref() == Some(&wanted))
+        .cloned()
+}
+
+/// Resolve a tag such as `nginx:latest` to the `repo@sha256:...` digest that was
+/// pulled for it, e.g. to pin a deployment. Locally built images that were never
+/// pushed or pulled have no digest and report NOT_FOUND.
+#[tauri::command]
+async fn resolve_image_digest(
+    reference: String,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_image_ref(&reference) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let info = match docker.inspect_image(&reference).await {
+        Ok(info) => info,
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
+    };
+    match matching_repo_digest(&reference, &info.repo_digests.unwrap_or_default()) {
+        Some(digest) => Ok(CommandResponse::ok(digest)),
+        None => Ok(CommandResponse::err_coded(
+            "NOT_FOUND",
+            format!("{} has no registry digest for its repository", reference),
+        )),
+    }
+}
+
+#[tauri::command]
+async fn tag_image(
+    id: String,
+    repo: String,
+    tag: Option<String>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_image_ref(&id) {
+        return Ok(CommandResponse::err(e));
+    }
+    let target = match tag {
+        Some(ref t) => format!("{}:{}", repo, t),
+        None => repo.clone(),
+    };
+    if let Err(e) = validate_image_ref(&target) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let options = TagImageOptions {
+        repo: Some(repo),
+        tag,
+    };
+    match docker.tag_image(&id, Some(options)).await {
+        Ok(_) => Ok(CommandResponse::ok_empty()),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Create a volume tagged with `labels` and the app's own label; `driver` defaults
+/// to the daemon's (local). Returns the volume name.
+#[tauri::command]
+async fn create_volume(
+    name: String,
+    driver: Option<String>,
+    labels: Option<HashMap<String, String>>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&name) {
+        return Ok(CommandResponse::err(e));
+    }
+    if let Some(ref d) = driver {
+        if let Err(e) = validate_docker_id(d) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+    let labels = match resource_labels(labels) {
+        Ok(l) => l,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+
+    let request = bollard::models::VolumeCreateRequest {
+        name: Some(name),
+        driver,
+        labels: Some(labels),
+        ..Default::default()
+    };
+    match docker.create_volume(request).await {
+        Ok(volume) => Ok(CommandResponse::ok(volume.name)),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// `force` removes the volume even when containers still reference it, like `docker volume rm -f`
+#[tauri::command]
+async fn remove_volume(
+    name: String,
+    force: Option<bool>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_docker_id(&name) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let force = force.unwrap_or(false);
+    match docker
+        .remove_volume(&name, Some(RemoveVolumeOptions { force }))
+        .await
+    {
+        Ok(_) => Ok(CommandResponse::ok_empty()),
+        // The daemon can still refuse a forced removal, e.g. while a running container uses it
+        Err(
+            e @ bollard::errors::Error::DockerResponseServerError {
+                status_code: 409, ..
+            },
+        ) => {
+            let code = docker_error_code(&e);
+            let msg = if force {
+                format!(
+                    "Volume {} could not be removed even with force: {}",
+                    name, e
+                )
+            } else {
+                format!("Volume {} is in use: {}", name, e)
+            };
+            Ok(CommandResponse::err_coded(code, msg))
+        }
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn pull_image(
+    image: String,
+    platform: Option<String>,
+    app_handle: tauri::AppHandle,
+    session_id: String,
+    docker_state: State<'_, DockerState>,
+    state: State<'_, PullState>,
+    preferences: State<'_, PreferencesState>,
+) -> Result<CommandResponse<()>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    if let Err(e) = validate_image_ref(&image) {
+        return Ok(CommandResponse::err(e));
+    }
+    if let Some(ref p) = platform {
+        if let Err(e) = validate_platform(p) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+
+    let mirror = preferences.0.lock().unwrap().registry_mirror.clone();
+    let mirrored = mirror
+        .as_deref()
+        .and_then(|m| mirrored_image_ref(&image, m));
+    let pull_image = mirrored.clone().unwrap_or_else(|| image.clone());
+    let existed_before = docker.inspect_image(&pull_image).await.is_ok();
+
+    let session_id_clone = session_id.clone();
+    let pull_docker = docker.clone();
+    let session_image = pull_image.clone();
+    let original_image = image.clone();
+    let handle = tokio::spawn(async move {
+        let docker = pull_docker;
+        let event_name = format!("pull-{}", session_id_clone);
+        if let Some(ref p) = platform {
+            let _ = app_handle.emit(
+                &event_name,
+                serde_json::json!({ "status": format!("Pulling for platform {}", p), "platform": p }),
+            );
+        }
+        if mirrored.is_some() {
+            let _ = app_handle.emit(
+                &event_name,
+                serde_json::json!({ "status": format!("Pulling through mirror as {}", pull_image) }),
+            );
+        }
+
+        // An empty platform leaves the choice to the daemon (the host's architecture)
+        let options = Some(CreateImageOptions {
+            from_image: Some(pull_image.clone()),
+            platform: platform.unwrap_or_default(),
+            ..Default::default()
+        });
+        let mut stream = docker.create_image(options, None, None);
+
+        while let Some(Ok(output)) = stream.next().await {
+            let _ = app_handle.emit(&event_name, output);
+        }
+
+        // Give an image pulled through the mirror the name the user asked for, and drop
+        // the mirror's name unless it was there before
+        if mirrored.is_some() {
+            if let Ok(parsed) = parse_image_ref(&original_image) {
+                let repo = match parsed.registry {
+                    Some(registry) => format!("{}/{}", registry, parsed.repository),
+                    None => parsed.repository,
+                };
+                let options = TagImageOptions {
+                    repo: Some(repo),
+                    tag: Some(parsed.tag.unwrap_or_else(|| "latest".to_string())),
+                };
+                if parsed.digest.is_none() {
+                    match docker.tag_image(&pull_image, Some(options)).await {
+                        Ok(()) if !existed_before => {
+                            // With the new tag in place this only untags
+                            if let Err(e) = docker
+                                .remove_image(&pull_image, None::<RemoveImageOptions>, None)
+                                .await
+                            {
+                                log::warn!("Failed to untag {}: {}", pull_image, e);
+                            }
+                        }
+                        Ok(()) => {}
+                        Err(e) => {
+                            log::warn!("Failed to tag {} as {}: {}", pull_image, original_image, e)
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    let session = PullSession {
+        abort: handle.abort_handle(),
+        docker,
+        image: session_image,
+        existed_before,
+    };
+    {
+        let mut lock = state.inner().0.lock().unwrap();
+        if let Some(old_session) = lock.insert(session_id.clone(), session) {
+            old_session.abort.abort();
+        }
+    }
+
+    let res = handle.await;
+
+    {
+        let mut lock = state.inner().0.lock().unwrap();
+        lock.remove(&session_id);
+    }
+
+    match res {
+        Ok(_) => Ok(CommandResponse::ok_empty()),
+        Err(_) => Ok(CommandResponse::err("Pull cancelled or failed")),
+    }
+}
+
+/// Validate a platform string of the form `os/arch[/variant]`, e.g. "linux/amd64"
+/// or "linux/arm64/v8"
+fn validate_platform(platform: &str) -> Result<(), String> {
+    let segments: Vec<&str> = platform.split('/').collect();
+    let valid = (2..=3).contains(&segments.len())
+        && segments.iter().all(|s| {
+            !s.is_empty()
+                && s.len() <= 32
+                && s.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        });
+    if !valid {
+        return Err(format!("Invalid platform: {}", platform));
+    }
+    Ok(())
+}
+
+/// Cancel a pull and tell the UI via a final `pull-{session_id}` event.
+/// With `cleanup`, an image the cancelled pull managed to create is removed again —
+/// but only if it wasn't present before the pull, so nothing the user had is touched.
+/// Dangling layers can't be attributed to a single pull, so they're left to `prune`.
+#[tauri::command]
+async fn stop_pull(
+    session_id: String,
+    cleanup: Option<bool>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, PullState>,
+) -> Result<CommandResponse<()>, String> {
+    let session = state.inner().0.lock().unwrap().remove(&session_id);
+    let Some(session) = session else {
+        return Ok(CommandResponse::ok_empty());
+    };
+    session.abort.abort();
+
+    if cleanup.unwrap_or(false)
+        && !session.existed_before
+        && session.docker.inspect_image(&session.image).await.is_ok()
+    {
+        // By reference, so an image that also carries another tag is only untagged
+        if let Err(e) = session
+            .docker
+            .remove_image(&session.image, None::<RemoveImageOptions>, None)
+            .await
+        {
+            log::warn!(
+                "Failed to clean up cancelled pull of {}: {}",
+                session.image,
+                e
+            );
+        }
+    }
+
+    let _ = app_handle.emit(
+        &format!("pull-{}", session_id),
+        serde_json::json!({ "status": "Cancelled", "cancelled": true }),
+    );
+    Ok(CommandResponse::ok_empty())
+}
+
+/// Progress line emitted on `build-{session_id}` while an image builds
+#[derive(Serialize, Clone)]
+struct BuildEvent {
+    stream: Option<String>,
+    status: Option<String>,
+    error: Option<String>,
+}
+
+/// Step counter emitted on `build-progress-{session_id}` alongside the raw output
+#[derive(Serialize, Clone, Debug, PartialEq)]
+struct BuildProgress {
+    current_step: u32,
+    total_steps: u32,
+    message: String,
+}
+
+/// Parse a `Step 3/7 : RUN npm ci` line. This is the classic builder's format, which
+/// `build_image` uses; BuildKit reports progress as structured trace messages instead.
+fn parse_build_step(line: &str) -> Option<BuildProgress> {
+    let rest = line.trim().strip_prefix("Step ")?;
+    let (counter, message) = rest.split_once(" : ").unwrap_or((rest, ""));
+    let (current, total) = counter.trim().split_once('/')?;
+    Some(BuildProgress {
+        current_step: current.parse().ok()?,
+        total_steps: total.parse().ok()?,
+        message: message.trim().to_string(),
+    })
+}
+
+/// Validate the Dockerfile path given to `build_image`: relative to the context and
+/// not escaping it
+fn validate_dockerfile_path(dockerfile: &str) -> Result<(), String> {
+    let path = std::path::Path::new(dockerfile);
+    let escapes = path.components().any(|c| {
+        !matches!(
+            c,
+            std::path::Component::Normal(_) | std::path::Component::CurDir
+        )
+    });
+    if dockerfile.is_empty() || escapes {
+        return Err(format!("Invalid Dockerfile path: {}", dockerfile));
+    }
+    Ok(())
+}
+
+/// Build an image from a local context directory. Paths excluded by the context's
+/// `.dockerignore` are left out of the uploaded tar, so large folders like
+/// `node_modules` don't slow down every build. Output streams on `build-{session_id}`
+/// and each new step on `build-progress-{session_id}`; returns the new image id.
+#[tauri::command]
+async fn build_image(
+    context_dir: String,
+    dockerfile: Option<String>,
+    tag: Option<String>,
+    session_id: String,
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    let context = std::path::PathBuf::from(&context_dir);
+    if !context.is_dir() {
+        return Ok(CommandResponse::err(format!(
+            "Build context is not a directory: {}",
+            context_dir
+        )));
+    }
+    let dockerfile = dockerfile.unwrap_or_else(|| "Dockerfile".to_string());
+    if let Err(e) = validate_dockerfile_path(&dockerfile) {
+        return Ok(CommandResponse::err(e));
+    }
+    if !context.join(&dockerfile).is_file() {
+        return Ok(CommandResponse::err(format!(
+            "Dockerfile not found in build context: {}",
+            dockerfile
+        )));
+    }
+    if let Some(ref t) = tag {
+        if let Err(e) = validate_image_ref(t) {
+            return Ok(CommandResponse::err(e));
+        }
+    }
+
+    let tar_dockerfile = dockerfile.clone();
+    let tar = match tokio::task::spawn_blocking(move || {
+        build_context::build_context_tar(&context, &tar_dockerfile)
+    })
+    .await
+    {
+        Ok(Ok(tar)) => tar,
+        Ok(Err(e)) => return Ok(CommandResponse::err(e)),
+        Err(e) => return Ok(CommandResponse::err(e.to_string())),
+    };
+
+    let options = BuildImageOptions {
+        dockerfile,
+        t: tag,
+        ..Default::default()
+    };
+    let mut stream = docker.build_image(options, None, Some(bollard::body_full(tar.into())));
+    let event_name = format!("build-{}", session_id);
+    let progress_event = format!("build-progress-{}", session_id);
+    let mut image_id = None;
+
+    while let Some(item) = stream.next().await {
+        let info = match item {
+            Ok(info) => info,
+            Err(e) => return Ok(CommandResponse::docker_err(e)),
+        };
+        let error = info.error_detail.and_then(|d| d.message);
+        if let Some(ref output) = info.stream {
+            for step in output.lines().filter_map(parse_build_step) {
+                let _ = app_handle.emit(&progress_event, step);
+            }
+        }
+        let _ = app_handle.emit(
+            &event_name,
+            BuildEvent {
+                stream: info.stream,
+                status: info.status,
+                error: error.clone(),
+            },
+        );
+        if let Some(e) = error {
+            return Ok(CommandResponse::err(e));
+        }
+        if let Some(id) = info.aux.and_then(|aux| aux.id) {
+            image_id = Some(id);
+        }
+    }
 
-    (val * multiplier) as i64
+    match image_id {
+        Some(id) => Ok(CommandResponse::ok(id)),
+        None => Ok(CommandResponse::err(
+            "Build finished without producing an image",
+        )),
+    }
 }
 
-#[tauri::command]
-async fn list_networks(
-    docker_state: State<'_, DockerState>,
-) -> Result<CommandResponse<Vec<bollard::models::Network>>, String> {
-    let docker = match docker_state.client() {
-        Ok(d) => d,
-        Err(e) => return Ok(CommandResponse::err(e)),
-    };
+/// A Docker CLI context as reported by `docker context ls`
+#[derive(Serialize, PartialEq, Debug)]
+struct DockerContext {
+    name: String,
+    description: String,
+    docker_endpoint: String,
+    current: bool,
+}
 
-    match docker.list_networks(None::<ListNetworksOptions>).await {
-        Ok(networks) => Ok(CommandResponse::ok(networks)),
-        Err(e) => Ok(CommandResponse::err(e.to_string())),
+/// Parse the line-delimited JSON printed by `docker context ls --format '{{json .}}'`
+fn parse_docker_contexts(stdout: &str) -> Vec<DockerContext> {
+    stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line.trim()).ok())
+        .filter_map(|item| {
+            let name = item.get("Name")?.as_str()?.to_string();
+            let field = |key: &str| {
+                item.get(key)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            Some(DockerContext {
+                name,
+                description: field("Description"),
+                docker_endpoint: field("DockerEndpoint"),
+                current: item
+                    .get("Current")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+/// Validate a Docker context name (same rules the docker CLI enforces).
+fn validate_context_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Context name cannot be empty".to_string());
+    }
+    if name.len() > 128 {
+        return Err("Context name too long".to_string());
     }
+    let mut chars = name.chars();
+    let first_ok = chars.next().is_some_and(|c| c.is_ascii_alphanumeric());
+    if !first_ok || !chars.all(|c| c.is_ascii_alphanumeric() || "_.+-".contains(c)) {
+        return Err(format!("Invalid context name: {}", name));
+    }
+    Ok(())
 }
 
 #[tauri::command]
-async fn remove_image(
-    id: String,
-    docker_state: State<'_, DockerState>,
-) -> Result<CommandResponse<()>, String> {
-    let docker = match docker_state.client() {
-        Ok(d) => d,
-        Err(e) => return Ok(CommandResponse::err(e)),
+async fn list_docker_contexts() -> Result<CommandResponse<Vec<DockerContext>>, String> {
+    let output = match std::process::Command::new("docker")
+        .args(["context", "ls", "--format", "{{json .}}"])
+        .output()
+    {
+        Ok(o) => o,
+        Err(e) => {
+            return Ok(CommandResponse::err(format!(
+                "Failed to execute docker CLI: {}",
+                e
+            )))
+        }
     };
-    if let Err(e) = validate_docker_id(&id) {
-        return Ok(CommandResponse::err(e));
-    }
 
-    match docker
-        .remove_image(&id, None::<RemoveImageOptions>, None)
-        .await
-    {
-        Ok(_) => Ok(CommandResponse::ok_empty()),
-        Err(e) => Ok(CommandResponse::err(e.to_string())),
+    if !output.status.success() {
+        return Ok(CommandResponse::err(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
     }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(CommandResponse::ok(parse_docker_contexts(&stdout)))
 }
 
 #[tauri::command]
-async fn remove_volume(
+async fn use_docker_context(
     name: String,
+    app_handle: tauri::AppHandle,
     docker_state: State<'_, DockerState>,
-) -> Result<CommandResponse<()>, String> {
-    let docker = match docker_state.client() {
-        Ok(d) => d,
-        Err(e) => return Ok(CommandResponse::err(e)),
-    };
-    if let Err(e) = validate_docker_id(&name) {
+) -> Result<CommandResponse<String>, String> {
+    if let Err(e) = validate_context_name(&name) {
         return Ok(CommandResponse::err(e));
     }
 
-    match docker
-        .remove_volume(&name, None::<RemoveVolumeOptions>)
-        .await
+    let output = match std::process::Command::new("docker")
+        .args([
+            "context",
+            "inspect",
+            &name,
+            "--format",
+            "{{.Endpoints.docker.Host}}",
+        ])
+        .output()
     {
-        Ok(_) => Ok(CommandResponse::ok_empty()),
-        Err(e) => Ok(CommandResponse::err(e.to_string())),
+        Ok(o) => o,
+        Err(e) => {
+            return Ok(CommandResponse::err(format!(
+                "Failed to execute docker CLI: {}",
+                e
+            )))
+        }
+    };
+
+    if !output.status.success() {
+        return Ok(CommandResponse::err(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
     }
-}
 
-#[tauri::command]
-async fn pull_image(
-    image: String,
-    app_handle: tauri::AppHandle,
-    session_id: String,
-    docker_state: State<'_, DockerState>,
-    state: State<'_, PullState>,
-) -> Result<CommandResponse<()>, String> {
-    let docker = match docker_state.client() {
+    let endpoint = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if endpoint.is_empty() {
+        return Ok(CommandResponse::err(format!(
+            "Context {} has no Docker endpoint",
+            name
+        )));
+    }
+
+    let docker = match Docker::connect_with_host(&endpoint) {
         Ok(d) => d,
-        Err(e) => return Ok(CommandResponse::err(e)),
+        Err(e) => return Ok(CommandResponse::docker_err(e)),
     };
-    if let Err(e) = validate_docker_id(&image) {
-        return Ok(CommandResponse::err(e));
+    // Don't swap out a working client for one that can't reach its daemon
+    if let Err(e) = ping_client(&docker).await {
+        return Ok(CommandResponse::docker_err(e));
     }
 
-    let session_id_clone = session_id.clone();
-    let handle = tokio::spawn(async move {
-        let options = Some(CreateImageOptions {
-            from_image: Some(image),
-            ..Default::default()
-        });
+    log::info!("Switched to Docker context {} ({})", name, endpoint);
+    docker_state.set_client(docker, endpoint.clone());
+    save_connection(&app_handle, &endpoint);
+    let _ = app_handle.emit("docker-connected", endpoint.clone());
 
-        let event_name = format!("pull-{}", session_id_clone);
-        let mut stream = docker.create_image(options, None, None);
+    Ok(CommandResponse::ok(endpoint))
+}
 
-        while let Some(Ok(output)) = stream.next().await {
-            let _ = app_handle.emit(&event_name, output);
-        }
-    });
+fn validate_request_timeout(secs: u64) -> Result<(), String> {
+    if !REQUEST_TIMEOUT_RANGE.contains(&secs) {
+        return Err(format!(
+            "Request timeout must be between {} and {} seconds",
+            REQUEST_TIMEOUT_RANGE.start(),
+            REQUEST_TIMEOUT_RANGE.end()
+        ));
+    }
+    Ok(())
+}
 
-    let abort_handle = handle.abort_handle();
-    {
-        let mut lock = state.inner().0.lock().unwrap();
-        if let Some(old_handle) = lock.insert(session_id.clone(), abort_handle) {
-            old_handle.abort();
+/// Set how long Docker API requests may take, e.g. longer for a slow remote daemon.
+/// Kept across reconnects for the rest of the session.
+#[tauri::command]
+fn set_request_timeout(secs: u64, docker_state: State<'_, DockerState>) -> CommandResponse<()> {
+    if let Err(e) = validate_request_timeout(secs) {
+        return CommandResponse::err(e);
+    }
+    docker_state.set_timeout(secs);
+    CommandResponse::ok_empty()
+}
+
+/// The connection path in use: "default", a socket path, "podman:<socket>" or a
+/// `scheme://` endpoint. Empty while no daemon has been reached.
+#[tauri::command]
+fn get_connection_path(docker_state: State<'_, DockerState>) -> CommandResponse<String> {
+    CommandResponse::ok(docker_state.get_path())
+}
+
+/// Remove the persisted connection so the next launch uses the default chain
+fn clear_saved_connection(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    if let Some(file) = connection_file(app_handle) {
+        if let Err(e) = std::fs::remove_file(&file) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return Err(format!("Failed to clear saved connection: {}", e));
+            }
         }
     }
+    Ok(())
+}
+
+/// Forget the persisted connection and reconnect through the default fallback chain
+#[tauri::command]
+async fn reset_connection(
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    if let Err(e) = clear_saved_connection(&app_handle) {
+        return Ok(CommandResponse::err(e));
+    }
 
-    let res = handle.await;
+    // An explicit reset should always try again
+    docker_state.reset_reconnect_cooldown();
+    match docker_state.connect_with_retry().await {
+        Ok(_) => {
+            let path = docker_state.get_path();
+            let _ = app_handle.emit("docker-connected", path.clone());
+            Ok(CommandResponse::ok(path))
+        }
+        Err(e) => Ok(CommandResponse::connection_err(e)),
+    }
+}
 
-    {
-        let mut lock = state.inner().0.lock().unwrap();
-        lock.remove(&session_id);
+/// Leave the current connection, e.g. a remote context, and return to the local
+/// daemon. Log, exec, attach, event and pull streams opened on the old client are
+/// aborted and the saved connection is forgotten. Emits `docker-disconnected`, then
+/// `docker-connected` if a local daemon answers. Returns the local path, or an empty
+/// string when none is running.
+#[tauri::command]
+async fn disconnect_docker(
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+    limits_cache: State<'_, StatsLimitsCache>,
+) -> Result<CommandResponse<String>, String> {
+    if let Err(e) = clear_saved_connection(&app_handle) {
+        return Ok(CommandResponse::err(e));
     }
+    abort_streaming_tasks(&app_handle);
+    let old_path = docker_state.get_path();
+    docker_state.disconnect();
+    docker_state.reset_reconnect_cooldown();
+    // Host totals and limits belong to the old daemon
+    limits_cache.clear();
+    log::info!("Disconnected from Docker at {}", old_path);
+    let _ = app_handle.emit("docker-disconnected", old_path);
 
-    match res {
-        Ok(_) => Ok(CommandResponse::ok_empty()),
-        Err(_) => Ok(CommandResponse::err("Pull cancelled or failed")),
+    match connect_docker().await {
+        Ok((docker, path)) => {
+            docker_state.set_client(docker, path.clone());
+            let _ = app_handle.emit("docker-connected", path.clone());
+            Ok(CommandResponse::ok(path))
+        }
+        // No local daemon yet; the connection monitor reports it once one starts
+        Err(_) => Ok(CommandResponse::ok(String::new())),
     }
 }
 
+/// Reconnect now, e.g. after switching from Colima to Docker Desktop outside the app.
+/// Unlike `reset_connection` the saved connection is kept. Returns the new path.
 #[tauri::command]
-fn stop_pull(session_id: String, state: State<'_, PullState>) -> CommandResponse<()> {
-    let mut lock = state.inner().0.lock().unwrap();
-    if let Some(handle) = lock.remove(&session_id) {
-        handle.abort();
+async fn reconnect_docker(
+    app_handle: tauri::AppHandle,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<String>, String> {
+    // Tries the saved connection first; a manual reconnect skips the cooldown too
+    match docker_state.reconnect_saved().await {
+        Ok(_) => {
+            let path = docker_state.get_path();
+            log::info!("Reconnected to Docker at {}", path);
+            let _ = app_handle.emit("docker-connected", path.clone());
+            Ok(CommandResponse::ok(path))
+        }
+        Err(e) => Ok(CommandResponse::connection_err(e)),
     }
-    CommandResponse::ok_empty()
 }
 
 #[tauri::command]
@@ -759,36 +6978,189 @@ async fn check_colima_installed() -> CommandResponse<bool> {
     CommandResponse::ok(installed)
 }
 
+/// State and allocated CPU, memory and disk of a Colima VM, so the UI can suggest
+/// reconfiguring it. Only available on macOS.
+#[tauri::command]
+async fn get_colima_status(
+    profile: Option<String>,
+) -> Result<CommandResponse<docker_lifecycle::ColimaStatus>, String> {
+    if !cfg!(target_os = "macos") {
+        return Ok(CommandResponse::err_coded(
+            "NOT_SUPPORTED",
+            "Colima is only supported on macOS",
+        ));
+    }
+    let profile = match colima_profile(profile) {
+        Ok(p) => p,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+    if !docker_lifecycle::check_colima_installed().await {
+        return Ok(CommandResponse::err_coded(
+            "NOT_INSTALLED",
+            "Colima is not installed. Install it with: brew install colima docker",
+        ));
+    }
+    match docker_lifecycle::get_colima_status(&profile).await {
+        Ok(status) => Ok(CommandResponse::ok(status)),
+        Err(e) => Ok(CommandResponse::err(e)),
+    }
+}
+
+/// Validate an optional Colima profile from the frontend, defaulting to "default"
+fn colima_profile(profile: Option<String>) -> Result<String, String> {
+    let profile = profile.unwrap_or_else(|| docker_lifecycle::DEFAULT_COLIMA_PROFILE.to_string());
+    docker_lifecycle::validate_colima_profile(&profile)?;
+    Ok(profile)
+}
+
 #[tauri::command]
-async fn check_docker_running() -> Result<CommandResponse<bool>, String> {
-    let running = docker_lifecycle::check_docker_running().await;
+async fn check_docker_running(
+    profile: Option<String>,
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<bool>, String> {
+    let profile = match colima_profile(profile) {
+        Ok(p) => p,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+    let running = docker_lifecycle::check_docker_running(&profile).await;
+    if running {
+        docker_state.reset_reconnect_cooldown();
+    }
     Ok(CommandResponse::ok(running))
 }
 
 #[tauri::command]
-async fn get_docker_status() -> Result<CommandResponse<docker_lifecycle::DockerStatus>, String> {
-    let status = docker_lifecycle::get_docker_status().await;
+async fn get_docker_status(
+    profile: Option<String>,
+) -> Result<CommandResponse<docker_lifecycle::DockerStatus>, String> {
+    let profile = match colima_profile(profile) {
+        Ok(p) => p,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+    let status = docker_lifecycle::get_docker_status(&profile).await;
     Ok(CommandResponse::ok(status))
 }
 
 #[tauri::command]
-async fn start_docker() -> Result<CommandResponse<()>, String> {
-    match docker_lifecycle::start_docker_runtime().await {
+async fn start_docker(
+    cpu: Option<u32>,
+    memory_gb: Option<u32>,
+    disk_gb: Option<u32>,
+    profile: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<CommandResponse<()>, String> {
+    let profile = match colima_profile(profile) {
+        Ok(p) => p,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+    // Only Colima uses the resources, so they are validated on macOS alone
+    let resources = if cfg!(target_os = "macos") {
+        match docker_lifecycle::VmResources::with_overrides(
+            cpu,
+            memory_gb,
+            disk_gb,
+            docker_lifecycle::host_cpu_count(),
+            docker_lifecycle::host_memory_gb(),
+        ) {
+            Ok(r) => r,
+            Err(e) => return Ok(CommandResponse::err(e)),
+        }
+    } else {
+        docker_lifecycle::VmResources::default()
+    };
+
+    // Stream Colima's output so the UI can show what a long first start is doing
+    let on_progress: docker_lifecycle::ProgressFn = Arc::new(move |line: String| {
+        let _ = app_handle.emit("colima-progress", line);
+    });
+
+    match docker_lifecycle::start_docker_runtime(resources, &profile, on_progress).await {
         Ok(_) => Ok(CommandResponse::ok_empty()),
         Err(e) => Ok(CommandResponse::err(e)),
     }
 }
 
 #[tauri::command]
-async fn wait_for_docker(timeout_secs: u64) -> Result<CommandResponse<()>, String> {
-    match docker_lifecycle::wait_for_docker_ready(timeout_secs).await {
+async fn wait_for_docker(
+    timeout_secs: u64,
+    profile: Option<String>,
+) -> Result<CommandResponse<()>, String> {
+    let profile = match colima_profile(profile) {
+        Ok(p) => p,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+    match docker_lifecycle::wait_for_docker_ready(timeout_secs, &profile).await {
         Ok(_) => Ok(CommandResponse::ok_empty()),
         Err(e) => Ok(CommandResponse::err(e)),
     }
 }
 
+/// Whether the Docker socket can be used, and if not, why and what to do about it
+#[derive(Serialize, Debug, PartialEq)]
+struct SocketAccess {
+    /// "ok", "permission_denied", "not_running" or "error"
+    status: &'static str,
+    /// Connection path in use when `status` is "ok"
+    path: Option<String>,
+    error: Option<String>,
+    hint: Option<String>,
+}
+
+/// Explain a failed connection: a socket the user may not open (on Linux usually
+/// not being in the `docker` group) needs a different fix than a daemon that is down
+fn socket_access_from_error(e: &bollard::errors::Error) -> SocketAccess {
+    use std::io::ErrorKind;
+
+    let kind = io_error_kind(e);
+    let (status, hint) = if kind == Some(ErrorKind::PermissionDenied) {
+        (
+            "permission_denied",
+            "Your user can't access the Docker socket. Add it to the docker group with `sudo usermod -aG docker $USER`, then log out and back in.",
+        )
+    } else if matches!(
+        kind,
+        Some(ErrorKind::NotFound | ErrorKind::ConnectionRefused)
+    ) || matches!(e, bollard::errors::Error::SocketNotFoundError(_))
+    {
+        (
+            "not_running",
+            "The Docker daemon isn't running. Start Docker and try again.",
+        )
+    } else {
+        ("error", "Docker could not be reached.")
+    };
+    SocketAccess {
+        status,
+        path: None,
+        error: Some(e.to_string()),
+        hint: Some(hint.to_string()),
+    }
+}
+
+/// Try to connect to the daemon and report whether the socket is usable, telling
+/// "permission denied" apart from "daemon not running" for the onboarding screen
+#[tauri::command]
+async fn check_socket_permissions() -> Result<CommandResponse<SocketAccess>, String> {
+    match connect_docker().await {
+        Ok((_, path)) => Ok(CommandResponse::ok(SocketAccess {
+            status: "ok",
+            path: Some(path),
+            error: None,
+            hint: None,
+        })),
+        Err(e) => Ok(CommandResponse::ok(socket_access_from_error(&e))),
+    }
+}
+
+/// End a running `wait_for_docker` early; it then returns an error
+#[tauri::command]
+fn cancel_wait_for_docker() -> CommandResponse<()> {
+    docker_lifecycle::cancel_wait_for_docker();
+    CommandResponse::ok_empty()
+}
+
 #[tauri::command]
-fn get_install_instructions() -> CommandResponse<String> {
+fn get_install_instructions() -> CommandResponse<docker_lifecycle::InstallInstructions> {
     let instructions = docker_lifecycle::get_install_instructions();
     CommandResponse::ok(instructions)
 }
@@ -799,13 +7171,255 @@ fn did_we_start_docker() -> CommandResponse<bool> {
     CommandResponse::ok(we_started)
 }
 
+/// Seconds a container gets to stop on quit before the daemon kills it
+const QUIT_STOP_TIMEOUT_SECS: i32 = 10;
+/// Extra time allowed for the stop requests themselves before killing directly
+const QUIT_STOP_MARGIN: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Set once `prepare_quit` work is done so the follow-up close isn't intercepted again
+static QUIT_PREPARED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+#[derive(Serialize, Clone)]
+struct QuitProgress {
+    stopped: usize,
+    total: usize,
+    container: String,
+}
+
+/// Stop all running containers, emitting `quit-progress` after each one. Containers that
+/// are still running once the grace period is over are killed so quit can't hang.
+async fn stop_all_containers(app_handle: &tauri::AppHandle, docker: &Docker) -> usize {
+    let options = Some(ListContainersOptions {
+        filters: Some(HashMap::from([(
+            "status".to_string(),
+            vec!["running".to_string()],
+        )])),
+        ..Default::default()
+    });
+    let ids: Vec<String> = match docker.list_containers(options).await {
+        Ok(containers) => containers.into_iter().filter_map(|c| c.id).collect(),
+        Err(e) => {
+            log::warn!("Failed to list containers to stop on quit: {}", e);
+            return 0;
+        }
+    };
+    let total = ids.len();
+
+    let mut pending: std::collections::HashSet<String> = ids.iter().cloned().collect();
+    let mut futures = FuturesUnordered::new();
+    for id in ids {
+        let docker = docker.clone();
+        futures.push(async move {
+            let options = Some(StopContainerOptions {
+                t: Some(QUIT_STOP_TIMEOUT_SECS),
+                ..Default::default()
+            });
+            let result = docker.stop_container(&id, options).await;
+            (id, result)
+        });
+    }
+
+    let deadline = std::time::Duration::from_secs(QUIT_STOP_TIMEOUT_SECS as u64) + QUIT_STOP_MARGIN;
+    let _ = tokio::time::timeout(deadline, async {
+        while let Some((id, result)) = futures.next().await {
+            if let Err(e) = result {
+                log::warn!("Failed to stop container {} on quit: {}", id, e);
+            }
+            pending.remove(&id);
+            let _ = app_handle.emit(
+                "quit-progress",
+                QuitProgress {
+                    stopped: total - pending.len(),
+                    total,
+                    container: id,
+                },
+            );
+        }
+    })
+    .await;
+    drop(futures);
+
+    for id in pending {
+        log::warn!("Container {} did not stop in time, killing it", id);
+        let _ = docker
+            .kill_container(&id, None::<bollard::query_parameters::KillContainerOptions>)
+            .await;
+    }
+    total
+}
+
+/// Get the saved preferences
+#[tauri::command]
+fn get_preferences(state: State<'_, PreferencesState>) -> CommandResponse<Preferences> {
+    CommandResponse::ok(state.0.lock().unwrap().clone())
+}
+
+/// Choose whether quitting stops all running containers first
+#[tauri::command]
+fn set_stop_all_containers_on_quit(
+    enabled: bool,
+    app_handle: tauri::AppHandle,
+    state: State<'_, PreferencesState>,
+) -> CommandResponse<()> {
+    let mut preferences = state.0.lock().unwrap();
+    preferences.stop_all_containers_on_quit = enabled;
+    match save_preferences(&app_handle, &preferences) {
+        Ok(()) => CommandResponse::ok_empty(),
+        Err(e) => CommandResponse::err(e),
+    }
+}
+
+/// Set the registry prefix Docker Hub pulls go through, e.g. a corporate pull-through
+/// cache at "mirror.corp". None or an empty string pulls from Docker Hub directly again.
+#[tauri::command]
+fn set_registry_mirror(
+    mirror: Option<String>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, PreferencesState>,
+) -> CommandResponse<()> {
+    let mirror = mirror
+        .map(|m| m.trim().trim_end_matches('/').to_string())
+        .filter(|m| !m.is_empty());
+    if let Some(ref m) = mirror {
+        if let Err(e) = validate_registry_mirror(m) {
+            return CommandResponse::err(e);
+        }
+    }
+    let mut preferences = state.0.lock().unwrap();
+    preferences.registry_mirror = mirror;
+    match save_preferences(&app_handle, &preferences) {
+        Ok(()) => CommandResponse::ok_empty(),
+        Err(e) => CommandResponse::err(e),
+    }
+}
+
+/// Registry mirrors configured on the daemon (`registry-mirrors` in daemon.json).
+/// These apply to every pull and can only be changed in the daemon's own config;
+/// see `set_registry_mirror` for the app-side prefix.
+#[tauri::command]
+async fn get_registry_mirrors(
+    docker_state: State<'_, DockerState>,
+) -> Result<CommandResponse<Vec<String>>, String> {
+    let docker = match docker_state.client().await {
+        Ok(d) => d,
+        Err(e) => return Ok(CommandResponse::connection_err(e)),
+    };
+    match docker.info().await {
+        Ok(info) => Ok(CommandResponse::ok(
+            info.registry_config
+                .and_then(|c| c.mirrors)
+                .unwrap_or_default(),
+        )),
+        Err(e) => Ok(CommandResponse::docker_err(e)),
+    }
+}
+
+/// Stop all running containers if the `stop_all_containers_on_quit` preference is on.
+/// Returns how many containers were stopped. Also run from the close handler before
+/// the runtime is halted; once it has run, the close handler doesn't stop them again.
+#[tauri::command]
+async fn prepare_quit(app_handle: tauri::AppHandle) -> Result<CommandResponse<usize>, String> {
+    if QUIT_PREPARED.load(std::sync::atomic::Ordering::SeqCst) {
+        return Ok(CommandResponse::ok(0));
+    }
+    let enabled = app_handle
+        .state::<PreferencesState>()
+        .0
+        .lock()
+        .unwrap()
+        .stop_all_containers_on_quit;
+    if !enabled {
+        return Ok(CommandResponse::ok(0));
+    }
+    let stopped = match app_handle.state::<DockerState>().cached_client() {
+        Some(docker) => stop_all_containers(&app_handle, &docker).await,
+        None => 0,
+    };
+    QUIT_PREPARED.store(true, std::sync::atomic::Ordering::SeqCst);
+    Ok(CommandResponse::ok(stopped))
+}
+
+/// Whether quitting should stop all containers first: the preference is on and
+/// `prepare_quit` hasn't already done it
+fn should_stop_containers_on_quit(app_handle: &tauri::AppHandle) -> bool {
+    !QUIT_PREPARED.load(std::sync::atomic::Ordering::SeqCst)
+        && app_handle
+            .try_state::<PreferencesState>()
+            .is_some_and(|p| p.0.lock().unwrap().stop_all_containers_on_quit)
+}
+
+/// Shutdown shared by closing the window and the Quit menu item: stop all containers
+/// when asked to, then the runtime if Opentainer started it
+async fn run_quit_sequence(app_handle: &tauri::AppHandle, stop_containers: bool) {
+    if stop_containers {
+        log::info!("Stopping all containers before quitting...");
+        let _ = prepare_quit(app_handle.clone()).await;
+    }
+    if docker_lifecycle::did_we_start_docker() {
+        log::info!("Opentainer started Colima, stopping it on quit...");
+        let _ = docker_lifecycle::stop_docker_runtime().await;
+        log::info!("Colima stopped.");
+    }
+}
+
+/// Abort every log, exec and pull task plus the connection monitor, so nothing
+/// emits into the webview or hits the socket while the app is shutting down.
+fn abort_background_tasks(app_handle: &tauri::AppHandle) {
+    abort_streaming_tasks(app_handle);
+    if let Some(monitor) = app_handle.try_state::<ConnectionMonitor>() {
+        if let Some(handle) = monitor.0.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Abort every stream tied to the current Docker client: logs, execs, attaches,
+/// event subscriptions and pulls
+fn abort_streaming_tasks(app_handle: &tauri::AppHandle) {
+    if let Some(logs) = app_handle.try_state::<LogState>() {
+        for (_, session) in logs.0.lock().unwrap().drain() {
+            session.handle.abort();
+        }
+    }
+    if let Some(multi_logs) = app_handle.try_state::<MultiLogState>() {
+        for (_, handles) in multi_logs.0.lock().unwrap().drain() {
+            for handle in handles {
+                handle.abort();
+            }
+        }
+    }
+    if let Some(execs) = app_handle.try_state::<ExecState>() {
+        for (_, session) in execs.0.lock().unwrap().drain() {
+            session.handle.abort();
+        }
+    }
+    if let Some(events) = app_handle.try_state::<EventsState>() {
+        for (_, handle) in events.0.lock().unwrap().drain() {
+            handle.abort();
+        }
+    }
+    if let Some(attaches) = app_handle.try_state::<AttachState>() {
+        for (_, session) in attaches.0.lock().unwrap().drain() {
+            session.handle.abort();
+        }
+    }
+    if let Some(pulls) = app_handle.try_state::<PullState>() {
+        for (_, session) in pulls.0.lock().unwrap().drain() {
+            session.abort.abort();
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .manage(DockerState::new())
         .manage(LogState(Mutex::new(HashMap::new())))
+        .manage(MultiLogState(Mutex::new(HashMap::new())))
         .manage(ExecState(Mutex::new(HashMap::new())))
+        .manage(AttachState(Mutex::new(HashMap::new())))
+        .manage(EventsState(Mutex::new(HashMap::new())))
         .manage(PullState(Mutex::new(HashMap::new())))
+        .manage(StatsLimitsCache::new())
         .plugin(tauri_plugin_opener::init())
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -816,6 +7430,14 @@ pub fn run() {
                 )?;
             }
 
+            // Connect once the config dir is resolvable so a saved connection can be preferred
+            let saved_path = load_saved_connection(app.handle());
+            app.manage(DockerState::new(saved_path));
+            app.manage(PreferencesState(Mutex::new(load_preferences(app.handle()))));
+
+            let monitor = tauri::async_runtime::spawn(monitor_connection(app.handle().clone()));
+            app.manage(ConnectionMonitor(Mutex::new(Some(monitor))));
+
             // Build a custom macOS app menu so that Cmd+Q closes windows
             // instead of calling NSApplication terminate (which skips ExitRequested)
             #[cfg(target_os = "macos")]
@@ -851,18 +7473,21 @@ pub fn run() {
                     if event.id().as_ref() == "custom-quit" {
                         log::info!("Custom Quit menu item triggered (Cmd+Q)");
 
-                        if docker_lifecycle::did_we_start_docker() {
+                        let stop_containers = should_stop_containers_on_quit(&handle);
+                        if stop_containers || docker_lifecycle::did_we_start_docker() {
                             // Emit stopping event so frontend shows the stopping UI
-                            let _ = handle.emit("docker-stopping", ());
+                            if docker_lifecycle::did_we_start_docker() {
+                                let _ = handle.emit("docker-stopping", ());
+                            }
 
                             let h = handle.clone();
                             tauri::async_runtime::spawn(async move {
-                                let _ = docker_lifecycle::stop_docker_runtime().await;
-                                log::info!("Colima stopped (via Cmd+Q). Exiting app.");
+                                run_quit_sequence(&h, stop_containers).await;
+                                log::info!("Quit sequence done (via Cmd+Q). Exiting app.");
                                 h.exit(0);
                             });
                         } else {
-                            // We didn't start Docker, just exit immediately
+                            // Nothing to stop, just exit immediately
                             handle.exit(0);
                         }
                     }
@@ -874,49 +7499,121 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             get_app_version,
             list_containers,
+            watch_containers,
+            stop_watch_containers,
             container_action,
+            bulk_container_action,
+            remove_container_force,
+            recreate_container,
+            create_container,
+            restart_with_env,
+            get_container_ports,
+            get_container_urls,
+            get_container_mounts,
+            get_container_networks,
+            export_container,
+            export_logs,
+            export_container_inspect,
+            read_container_file,
+            write_container_file,
+            get_container_size,
+            list_compose_projects,
+            compose_up,
+            compose_down,
+            prune_project,
+            wait_container,
+            get_container_health,
+            get_exit_info,
+            get_container_restart_info,
+            get_run_command,
+            get_container_command,
+            set_restart_policy,
+            get_container_env,
             get_batch_stats,
+            get_container_stats,
             list_images,
             list_volumes,
+            list_dangling_volumes,
             list_networks,
+            create_network,
+            get_volume_users,
+            get_network_users,
+            get_resource_summary,
             start_logs,
             stop_logs,
+            start_multi_logs,
+            stop_multi_logs,
+            set_log_timestamps,
+            get_container_logs_stats,
             remove_image,
+            prune_images,
+            tag_image,
+            get_image_layers,
+            get_image_digests,
+            resolve_image_digest,
             remove_volume,
+            create_volume,
             start_exec,
+            start_exec_command,
             exec_input,
             exec_resize,
+            exec_signal,
             stop_exec,
+            restart_exec,
+            get_exec_status,
+            attach_container,
+            attach_input,
+            attach_resize,
+            stop_attach,
+            run_exec,
+            detect_container_shell,
             pull_image,
             stop_pull,
+            build_image,
+            list_docker_contexts,
+            use_docker_context,
+            reset_connection,
+            reconnect_docker,
+            disconnect_docker,
+            get_connection_path,
+            set_request_timeout,
             // Docker lifecycle commands
             check_colima_installed,
+            get_colima_status,
             check_docker_running,
             get_docker_status,
             start_docker,
             wait_for_docker,
+            cancel_wait_for_docker,
+            check_socket_permissions,
             get_install_instructions,
-            did_we_start_docker
+            did_we_start_docker,
+            get_preferences,
+            set_stop_all_containers_on_quit,
+            set_registry_mirror,
+            get_registry_mirrors,
+            prepare_quit
         ])
         .on_window_event(|window, event| {
             // Handle window close request (red X button OR custom Cmd+Q) - stop Docker if we started it
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                if docker_lifecycle::did_we_start_docker() {
+                let stop_containers = should_stop_containers_on_quit(window.app_handle());
+                if stop_containers || docker_lifecycle::did_we_start_docker() {
                     // Prevent window from closing immediately
                     api.prevent_close();
 
-                    // Emit event to frontend to show stopping UI
-                    let _ = window.emit("docker-stopping", ());
-
-                    log::info!("Opentainer started Colima, stopping it on close...");
+                    // Only the runtime stop gets the stopping UI; stopping containers
+                    // reports its own `quit-progress`
+                    if docker_lifecycle::did_we_start_docker() {
+                        let _ = window.emit("docker-stopping", ());
+                    }
 
-                    // Spawn async task to stop Docker then close
+                    // Spawn async task to stop containers and Docker then close
                     let win = window.clone();
                     tauri::async_runtime::spawn(async move {
-                        let _ = docker_lifecycle::stop_docker_runtime().await;
-                        log::info!("Colima stopped. Closing window now.");
-                        // Now actually close the window (WE_STARTED_DOCKER is now false,
-                        // so the next CloseRequested won't prevent close again)
+                        run_quit_sequence(win.app_handle(), stop_containers).await;
+                        // Now actually close the window (WE_STARTED_DOCKER and QUIT_PREPARED
+                        // mean the next CloseRequested won't prevent close again)
                         let _ = win.close();
                     });
                 }
@@ -924,11 +7621,13 @@ pub fn run() {
         })
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|_app_handle, event| {
+        .run(|app_handle, event| {
             // Log when app actually exits
             if let tauri::RunEvent::Exit = event {
                 log::info!("Opentainer RunEvent::Exit fired");
 
+                abort_background_tasks(app_handle);
+
                 // Safety fallback: stop Docker if it's still marked as running
                 // (should be a no-op since CloseRequested already stopped it)
                 if docker_lifecycle::did_we_start_docker() {
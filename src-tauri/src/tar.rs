@@ -0,0 +1,323 @@
+//! Minimal ustar archive support
+//!
+//! The Docker API moves files as tar archives: build contexts and uploads are sent as
+//! one, and `download_from_container` returns one. This covers what those need without
+//! pulling in a full tar implementation.
+
+/// Largest file size a ustar header can describe (11 octal digits)
+const TAR_MAX_SIZE: u64 = 0o77777777777;
+
+/// Minimal ustar writer
+#[derive(Default)]
+pub struct TarBuilder {
+    buf: Vec<u8>,
+}
+
+impl TarBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn append_file(
+        &mut self,
+        path: &str,
+        data: &[u8],
+        mode: u32,
+        mtime: u64,
+    ) -> Result<(), String> {
+        self.append_owned_file(path, data, mode, 0, 0, mtime)
+    }
+
+    /// `append_file` with an explicit owner, e.g. to keep that of a file being replaced
+    pub fn append_owned_file(
+        &mut self,
+        path: &str,
+        data: &[u8],
+        mode: u32,
+        uid: u64,
+        gid: u64,
+        mtime: u64,
+    ) -> Result<(), String> {
+        let header = tar_header(path, data.len() as u64, mode, (uid, gid), mtime, b'0', "")?;
+        self.buf.extend_from_slice(&header);
+        self.buf.extend_from_slice(data);
+        self.pad();
+        Ok(())
+    }
+
+    pub fn append_dir(&mut self, path: &str, mode: u32, mtime: u64) -> Result<(), String> {
+        let path = format!("{}/", path.trim_end_matches('/'));
+        let header = tar_header(&path, 0, mode, (0, 0), mtime, b'5', "")?;
+        self.buf.extend_from_slice(&header);
+        Ok(())
+    }
+
+    pub fn append_symlink(&mut self, path: &str, target: &str, mtime: u64) -> Result<(), String> {
+        let header = tar_header(path, 0, 0o777, (0, 0), mtime, b'2', target)?;
+        self.buf.extend_from_slice(&header);
+        Ok(())
+    }
+
+    /// Write the end-of-archive marker and return the archive bytes
+    pub fn finish(mut self) -> Vec<u8> {
+        self.buf.extend_from_slice(&[0u8; 1024]);
+        self.buf
+    }
+
+    fn pad(&mut self) {
+        let rem = self.buf.len() % 512;
+        if rem != 0 {
+            self.buf.resize(self.buf.len() + 512 - rem, 0);
+        }
+    }
+}
+
+/// Write `value` as zero-padded octal filling `field` minus its trailing NUL
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    let s = format!("{:0width$o}", value, width = digits);
+    field[..digits].copy_from_slice(&s.as_bytes()[s.len() - digits..]);
+    field[digits] = 0;
+}
+
+/// Split a path into ustar `prefix` and `name` fields (155 and 100 bytes)
+fn split_tar_path(path: &str) -> Result<(&str, &str), String> {
+    if path.len() <= 100 {
+        return Ok(("", path));
+    }
+    // The split has to fall on a '/', which is dropped from both halves
+    path.char_indices()
+        .filter(|&(i, c)| c == '/' && i <= 155 && path.len() - i - 1 <= 100 && i + 1 < path.len())
+        .map(|(i, _)| (&path[..i], &path[i + 1..]))
+        .next()
+        .ok_or_else(|| format!("Path too long for archive: {}", path))
+}
+
+fn tar_header(
+    path: &str,
+    size: u64,
+    mode: u32,
+    (uid, gid): (u64, u64),
+    mtime: u64,
+    typeflag: u8,
+    linkname: &str,
+) -> Result<[u8; 512], String> {
+    if size > TAR_MAX_SIZE {
+        return Err(format!("File too large for archive: {}", path));
+    }
+    if linkname.len() > 100 {
+        return Err(format!("Link target too long for archive: {}", linkname));
+    }
+    let (prefix, name) = split_tar_path(path)?;
+
+    let mut header = [0u8; 512];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    write_octal(&mut header[100..108], u64::from(mode & 0o7777));
+    write_octal(&mut header[108..116], uid);
+    write_octal(&mut header[116..124], gid);
+    write_octal(&mut header[124..136], size);
+    write_octal(&mut header[136..148], mtime.min(TAR_MAX_SIZE));
+    header[156] = typeflag;
+    header[157..157 + linkname.len()].copy_from_slice(linkname.as_bytes());
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    // The checksum is computed with its own field filled with spaces
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+    write_octal(&mut header[148..155], u64::from(checksum));
+    header[155] = b' ';
+    Ok(header)
+}
+
+/// What a tar entry holds
+#[derive(Debug, PartialEq)]
+pub enum TarEntryKind {
+    File,
+    Directory,
+    Symlink,
+    Other,
+}
+
+/// Header of an entry in an archive being read
+#[derive(Debug, PartialEq)]
+pub struct TarEntry {
+    pub kind: TarEntryKind,
+    pub size: u64,
+    /// Permission bits
+    pub mode: u32,
+    pub uid: u64,
+    pub gid: u64,
+    /// Offset of the entry's data in the archive
+    pub data_offset: usize,
+}
+
+/// Size of an entry's data rounded up to whole 512-byte blocks
+fn padded(size: u64) -> u64 {
+    size.div_ceil(512) * 512
+}
+
+/// Parse an octal header field; fields are NUL or space terminated
+fn read_octal(field: &[u8]) -> Option<u64> {
+    let digits: Vec<u8> = field
+        .iter()
+        .copied()
+        .skip_while(|&b| b == b' ')
+        .take_while(|&b| b != 0 && b != b' ')
+        .collect();
+    if digits.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(std::str::from_utf8(&digits).ok()?, 8).ok()
+}
+
+/// Find the first real entry of an archive, skipping pax and GNU long-name headers.
+/// Returns Ok(None) while `archive` doesn't yet hold the whole header, so it can be
+/// called again as more of a streamed archive arrives.
+pub fn first_entry(archive: &[u8]) -> Result<Option<TarEntry>, String> {
+    let mut offset = 0usize;
+    loop {
+        let Some(header) = archive.get(offset..offset + 512) else {
+            return Ok(None);
+        };
+        if header.iter().all(|&b| b == 0) {
+            return Err("Archive is empty".to_string());
+        }
+        let stored = read_octal(&header[148..156]).ok_or("Invalid archive header")?;
+        let checksum: u64 = header
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| {
+                if (148..156).contains(&i) {
+                    32
+                } else {
+                    u64::from(b)
+                }
+            })
+            .sum();
+        if stored != checksum {
+            return Err("Invalid archive header checksum".to_string());
+        }
+        // Sizes over 8 GiB use a binary encoding, far beyond anything read whole
+        let size = if header[124] & 0x80 != 0 {
+            u64::MAX
+        } else {
+            read_octal(&header[124..136]).ok_or("Invalid archive entry size")?
+        };
+
+        let kind = match header[156] {
+            b'0' | 0 | b'7' => TarEntryKind::File,
+            b'5' => TarEntryKind::Directory,
+            b'2' => TarEntryKind::Symlink,
+            // Extended headers describing the next entry
+            b'x' | b'g' | b'L' | b'K' => {
+                offset = usize::try_from(padded(size))
+                    .ok()
+                    .and_then(|len| (offset + 512).checked_add(len))
+                    .ok_or("Invalid archive entry size")?;
+                continue;
+            }
+            _ => TarEntryKind::Other,
+        };
+        return Ok(Some(TarEntry {
+            kind,
+            size,
+            mode: read_octal(&header[100..108]).map_or(0, |m| (m & 0o7777) as u32),
+            uid: read_octal(&header[108..116]).unwrap_or(0),
+            gid: read_octal(&header[116..124]).unwrap_or(0),
+            data_offset: offset + 512,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tar_header_has_valid_checksum_and_fields() {
+        let mut tar = TarBuilder::new();
+        tar.append_file("etc/app.conf", b"hello", 0o644, 0).unwrap();
+        let archive = tar.finish();
+        // header + one padded data block + end marker
+        assert_eq!(archive.len(), 512 + 512 + 1024);
+
+        let header = &archive[..512];
+        assert_eq!(&header[..12], b"etc/app.conf");
+        assert_eq!(&header[100..107], b"0000644");
+        assert_eq!(&header[124..135], b"00000000005");
+        assert_eq!(&header[257..262], b"ustar");
+
+        let stored = std::str::from_utf8(&header[148..154]).unwrap();
+        let mut copy = header.to_vec();
+        copy[148..156].fill(b' ');
+        let sum: u32 = copy.iter().map(|&b| u32::from(b)).sum();
+        assert_eq!(u32::from_str_radix(stored, 8).unwrap(), sum);
+        assert_eq!(&archive[512..517], b"hello");
+    }
+
+    #[test]
+    fn tar_splits_long_paths_into_prefix() {
+        let long = format!("{}/{}", "d".repeat(120), "f".repeat(50));
+        let (prefix, name) = split_tar_path(&long).unwrap();
+        assert_eq!(prefix.len(), 120);
+        assert_eq!(name.len(), 50);
+        assert!(split_tar_path(&"x".repeat(300)).is_err());
+    }
+
+    #[test]
+    fn first_entry_reads_back_a_written_file() {
+        let mut tar = TarBuilder::new();
+        tar.append_file("app.conf", b"port=80\n", 0o644, 0).unwrap();
+        let archive = tar.finish();
+
+        let entry = first_entry(&archive).unwrap().unwrap();
+        assert_eq!(entry.kind, TarEntryKind::File);
+        assert_eq!(entry.size, 8);
+        assert_eq!((entry.mode, entry.uid, entry.gid), (0o644, 0, 0));
+        assert_eq!(
+            &archive[entry.data_offset..entry.data_offset + 8],
+            b"port=80\n"
+        );
+    }
+
+    #[test]
+    fn first_entry_reads_back_mode_and_owner() {
+        let mut tar = TarBuilder::new();
+        tar.append_owned_file("run.sh", b"#!/bin/sh\n", 0o750, 101, 1000, 0)
+            .unwrap();
+        let entry = first_entry(&tar.finish()).unwrap().unwrap();
+        assert_eq!((entry.mode, entry.uid, entry.gid), (0o750, 101, 1000));
+    }
+
+    #[test]
+    fn first_entry_waits_for_a_whole_header() {
+        let mut tar = TarBuilder::new();
+        tar.append_dir("etc", 0o755, 0).unwrap();
+        let archive = tar.finish();
+        assert_eq!(first_entry(&archive[..100]).unwrap(), None);
+        assert_eq!(
+            first_entry(&archive).unwrap().unwrap().kind,
+            TarEntryKind::Directory
+        );
+    }
+
+    #[test]
+    fn first_entry_skips_pax_headers_and_rejects_corruption() {
+        let mut archive = tar_header("PaxHeaders/app.conf", 20, 0o644, (0, 0), 0, b'x', "")
+            .unwrap()
+            .to_vec();
+        archive.extend_from_slice(&[b'a'; 512]);
+        let mut tar = TarBuilder::new();
+        tar.append_symlink("app.conf", "/etc/app.conf", 0).unwrap();
+        archive.extend(tar.finish());
+        let entry = first_entry(&archive).unwrap().unwrap();
+        assert_eq!(entry.kind, TarEntryKind::Symlink);
+        assert_eq!(entry.data_offset, 1536);
+
+        archive[0] ^= 1;
+        assert!(first_entry(&archive).is_err());
+        assert!(first_entry(&[0u8; 1024]).is_err());
+    }
+}
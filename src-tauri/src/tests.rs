@@ -66,6 +66,1288 @@ fn validate_docker_id_rejects_pipe() {
     assert!(validate_docker_id("nginx | cat /etc/passwd").is_err());
 }
 
+// ── validate_exec_user / validate_exec_working_dir ─────────────────
+
+#[test]
+fn validate_exec_user_accepts_names_and_ids() {
+    assert!(validate_exec_user("root").is_ok());
+    assert!(validate_exec_user("0").is_ok());
+    assert!(validate_exec_user("1000:1000").is_ok());
+    assert!(validate_exec_user("www-data:www-data").is_ok());
+}
+
+#[test]
+fn validate_exec_user_rejects_malformed() {
+    assert!(validate_exec_user("").is_err());
+    assert!(validate_exec_user("1000:").is_err());
+    assert!(validate_exec_user("a:b:c").is_err());
+    assert!(validate_exec_user("-u").is_err());
+    assert!(validate_exec_user("root; id").is_err());
+}
+
+#[test]
+fn validate_exec_working_dir_requires_absolute_path() {
+    assert!(validate_exec_working_dir("/app").is_ok());
+    assert!(validate_exec_working_dir("/srv/my app").is_ok());
+    assert!(validate_exec_working_dir("app").is_err());
+    assert!(validate_exec_working_dir("").is_err());
+    assert!(validate_exec_working_dir("/app\n/etc").is_err());
+}
+
+#[test]
+fn validate_docker_id_rejects_leading_dash() {
+    assert!(validate_docker_id("-rf").is_err());
+    assert!(validate_docker_id("--privileged").is_err());
+}
+
+#[test]
+fn validate_docker_id_rejects_double_dot() {
+    assert!(validate_docker_id("..").is_err());
+    assert!(validate_docker_id("../etc/passwd").is_err());
+    assert!(validate_docker_id("image..name").is_err());
+}
+
+#[test]
+fn validate_docker_id_rejects_double_slash() {
+    assert!(validate_docker_id("ghcr.io//image").is_err());
+    assert!(validate_docker_id("//").is_err());
+}
+
+#[test]
+fn validate_docker_id_rejects_trailing_colon() {
+    assert!(validate_docker_id("nginx:").is_err());
+}
+
+#[test]
+fn validate_docker_id_still_accepts_valid_refs() {
+    for id in [
+        "abc123def456",
+        "nginx",
+        "nginx:latest",
+        "nginx:1.25.3-alpine",
+        "library/nginx",
+        "ghcr.io/user/image:v1.2.3",
+        "localhost:5000/team/app:dev",
+        "nginx@sha256:abc123",
+        "ghcr.io/user/image:v1@sha256:abc123",
+        "my_volume-name.v2",
+        "container.1",
+    ] {
+        assert!(validate_docker_id(id).is_ok(), "{id} should be valid");
+    }
+}
+
+// ── run_command ───────────────────────────────────────────────────
+
+#[test]
+fn run_command_reconstructs_docker_run_flags() {
+    let mut info = inspected_container();
+    let host_config = info.host_config.as_mut().unwrap();
+    host_config.port_bindings = Some(HashMap::from([
+        (
+            "80/tcp".to_string(),
+            Some(vec![
+                bollard::models::PortBinding {
+                    host_ip: Some("0.0.0.0".to_string()),
+                    host_port: Some("8080".to_string()),
+                },
+                bollard::models::PortBinding {
+                    host_ip: Some("::".to_string()),
+                    host_port: Some("8080".to_string()),
+                },
+            ]),
+        ),
+        (
+            "53/udp".to_string(),
+            Some(vec![bollard::models::PortBinding {
+                host_ip: Some("127.0.0.1".to_string()),
+                host_port: Some("5353".to_string()),
+            }]),
+        ),
+    ]));
+    let image = ImageConfig {
+        env: Some(vec![
+            "PATH=/usr/local/bin:/usr/bin".to_string(),
+            "NGINX_VERSION=1.25".to_string(),
+        ]),
+        cmd: Some(vec!["nginx".to_string()]),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        run_command(&info, Some(&image)),
+        "docker run -d --name web --restart always --network appnet \
+         -p 127.0.0.1:5353:53/udp -p 8080:80 -v /srv/site:/usr/share/nginx/html:ro \
+         -e APP_MODE=prod nginx:1.25 nginx -g"
+    );
+}
+
+#[test]
+fn run_command_quotes_and_skips_image_defaults() {
+    let info = ContainerInspectResponse {
+        config: Some(bollard::models::ContainerConfig {
+            image: Some("alpine".to_string()),
+            env: Some(vec!["GREETING=hello world".to_string()]),
+            cmd: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                "echo it's up".to_string(),
+            ]),
+            ..Default::default()
+        }),
+        host_config: Some(bollard::models::HostConfig {
+            restart_policy: Some(RestartPolicy {
+                name: Some(RestartPolicyNameEnum::ON_FAILURE),
+                maximum_retry_count: Some(3),
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(
+        run_command(&info, None),
+        "docker run -d --restart on-failure:3 -e 'GREETING=hello world' alpine sh -c 'echo it'\\''s up'"
+    );
+
+    let image = ImageConfig {
+        cmd: info.config.as_ref().unwrap().cmd.clone(),
+        ..Default::default()
+    };
+    assert!(run_command(&info, Some(&image)).ends_with(" alpine"));
+}
+
+#[test]
+fn run_command_emits_changed_entrypoint() {
+    let image = ImageConfig {
+        entrypoint: Some(vec!["/docker-entrypoint.sh".to_string()]),
+        cmd: Some(vec!["nginx".to_string(), "-g".to_string()]),
+        ..Default::default()
+    };
+    let container = |entrypoint: Option<Vec<&str>>| ContainerInspectResponse {
+        config: Some(bollard::models::ContainerConfig {
+            image: Some("nginx".to_string()),
+            entrypoint: entrypoint.map(|e| e.into_iter().map(String::from).collect()),
+            cmd: image.cmd.clone(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    // Inherited from the image: nothing to add
+    let info = container(Some(vec!["/docker-entrypoint.sh"]));
+    assert_eq!(run_command(&info, Some(&image)), "docker run -d nginx");
+
+    // A multi-word entrypoint keeps its tail ahead of the command, which
+    // `--entrypoint` would otherwise drop
+    let info = container(Some(vec!["sh", "-c", "exec \"$@\"", "--"]));
+    assert_eq!(
+        run_command(&info, Some(&image)),
+        "docker run -d --entrypoint sh nginx -c 'exec \"$@\"' -- nginx -g"
+    );
+
+    // Cleared entrypoint
+    let info = container(None);
+    assert_eq!(
+        run_command(&info, Some(&image)),
+        "docker run -d --entrypoint '' nginx nginx -g"
+    );
+}
+
+// ── container_command ─────────────────────────────────────────────
+
+#[test]
+fn container_command_separates_entrypoint_and_cmd() {
+    let info = ContainerInspectResponse {
+        config: Some(bollard::models::ContainerConfig {
+            entrypoint: Some(vec!["/docker-entrypoint.sh".to_string()]),
+            cmd: Some(vec![
+                "nginx".to_string(),
+                "-g".to_string(),
+                "daemon off;".to_string(),
+            ]),
+            working_dir: Some("/srv".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let command = container_command(&info);
+    assert_eq!(command.entrypoint, vec!["/docker-entrypoint.sh"]);
+    assert_eq!(command.cmd, vec!["nginx", "-g", "daemon off;"]);
+    assert_eq!(command.working_dir, "/srv");
+}
+
+#[test]
+fn container_command_defaults_missing_fields_to_empty() {
+    let info = ContainerInspectResponse {
+        config: Some(bollard::models::ContainerConfig {
+            cmd: Some(vec!["sh".to_string()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(
+        container_command(&info),
+        ContainerCommand {
+            entrypoint: vec![],
+            cmd: vec!["sh".to_string()],
+            working_dir: String::new(),
+        }
+    );
+}
+
+// ── matching_repo_digest ──────────────────────────────────────────
+
+#[test]
+fn matching_repo_digest_normalizes_docker_hub_names() {
+    let digests = vec![
+        "ghcr.io/acme/nginx@sha256:1111".to_string(),
+        "nginx@sha256:2222".to_string(),
+    ];
+    assert_eq!(
+        matching_repo_digest("nginx:latest", &digests),
+        Some("nginx@sha256:2222".to_string())
+    );
+    assert_eq!(
+        matching_repo_digest("docker.io/library/nginx", &digests),
+        Some("nginx@sha256:2222".to_string())
+    );
+    assert_eq!(
+        matching_repo_digest("ghcr.io/acme/nginx:1.27", &digests),
+        Some("ghcr.io/acme/nginx@sha256:1111".to_string())
+    );
+}
+
+#[test]
+fn matching_repo_digest_ignores_other_repositories() {
+    let digests = vec!["acme/nginx@sha256:1111".to_string()];
+    assert_eq!(matching_repo_digest("nginx:latest", &digests), None);
+    assert_eq!(matching_repo_digest("nginx:latest", &[]), None);
+}
+
+// ── parse_image_ref / validate_image_ref ──────────────────────────
+
+#[test]
+fn parse_image_ref_plain_name() {
+    let r = parse_image_ref("ubuntu").unwrap();
+    assert_eq!(r.registry, None);
+    assert_eq!(r.repository, "ubuntu");
+    assert_eq!(r.tag, None);
+    assert_eq!(r.digest, None);
+}
+
+#[test]
+fn parse_image_ref_with_tag() {
+    let r = parse_image_ref("ubuntu:22.04").unwrap();
+    assert_eq!(r.repository, "ubuntu");
+    assert_eq!(r.tag.as_deref(), Some("22.04"));
+}
+
+#[test]
+fn parse_image_ref_registry_with_port() {
+    let r = parse_image_ref("registry:5000/foo/bar:tag").unwrap();
+    assert_eq!(r.registry.as_deref(), Some("registry:5000"));
+    assert_eq!(r.repository, "foo/bar");
+    assert_eq!(r.tag.as_deref(), Some("tag"));
+}
+
+#[test]
+fn parse_image_ref_with_digest() {
+    let r = parse_image_ref("ghcr.io/user/image:v1@sha256:abc123").unwrap();
+    assert_eq!(r.registry.as_deref(), Some("ghcr.io"));
+    assert_eq!(r.repository, "user/image");
+    assert_eq!(r.tag.as_deref(), Some("v1"));
+    assert_eq!(r.digest.as_deref(), Some("sha256:abc123"));
+}
+
+#[test]
+fn parse_image_ref_namespace_is_not_registry() {
+    let r = parse_image_ref("library/nginx").unwrap();
+    assert_eq!(r.registry, None);
+    assert_eq!(r.repository, "library/nginx");
+}
+
+#[test]
+fn validate_image_ref_accepts_image_ids() {
+    assert!(validate_image_ref("abc123def456").is_ok());
+    assert!(validate_image_ref(&format!("sha256:{}", "a".repeat(64))).is_ok());
+}
+
+#[test]
+fn validate_image_ref_rejects_malformed() {
+    assert!(validate_image_ref("").is_err());
+    assert!(validate_image_ref("foo::bar").is_err());
+    assert!(validate_image_ref("foo:bad tag").is_err());
+    assert!(validate_image_ref("foo:a:b").is_err());
+    assert!(validate_image_ref("Ubuntu").is_err());
+    assert!(validate_image_ref("foo:").is_err());
+    assert!(validate_image_ref("foo@sha256:").is_err());
+    assert!(validate_image_ref("foo@sha256:xyz").is_err());
+    assert!(validate_image_ref("registry:port/foo").is_err());
+    assert!(validate_image_ref("nginx; rm -rf /").is_err());
+}
+
+// ── DockerState::connect_single_flight ────────────────────────────
+
+#[tokio::test]
+async fn concurrent_reconnects_share_one_attempt() {
+    let state = DockerState::with_state(None, String::new());
+    let calls = std::sync::atomic::AtomicUsize::new(0);
+    let connect = || async {
+        calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        // Clients connect lazily, so nothing needs to listen here
+        let docker =
+            Docker::connect_with_http("http://127.0.0.1:2375", 5, bollard::API_DEFAULT_VERSION)?;
+        Ok((docker, "tcp://127.0.0.1:2375".to_string()))
+    };
+
+    let results =
+        futures_util::future::join_all((0..5).map(|_| state.connect_single_flight(connect))).await;
+    assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(state.get_path(), "tcp://127.0.0.1:2375");
+}
+
+#[tokio::test]
+async fn concurrent_reconnects_share_one_failure() {
+    let state = DockerState::with_state(None, String::new());
+    let calls = std::sync::atomic::AtomicUsize::new(0);
+    let connect = || async {
+        calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        Err(bollard::errors::Error::RequestTimeoutError)
+    };
+
+    let results =
+        futures_util::future::join_all((0..5).map(|_| state.connect_single_flight(connect))).await;
+    assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert!(results.iter().all(Result::is_err));
+}
+
+// ── validate_log_tail ─────────────────────────────────────────────
+
+#[test]
+fn validate_log_tail_accepts_counts_and_all() {
+    assert!(validate_log_tail("0").is_ok());
+    assert!(validate_log_tail("100").is_ok());
+    assert!(validate_log_tail("all").is_ok());
+}
+
+#[test]
+fn validate_log_tail_rejects_other_values() {
+    assert!(validate_log_tail("").is_err());
+    assert!(validate_log_tail("-1").is_err());
+    assert!(validate_log_tail("ten").is_err());
+    assert!(validate_log_tail("1234567890").is_err());
+}
+
+// ── strip_ansi ────────────────────────────────────────────────────
+
+#[test]
+fn strip_ansi_removes_sgr_color_codes() {
+    let colored = "\x1b[32mINFO\x1b[0m server started on \x1b[1;34m:8080\x1b[0m";
+    assert_eq!(strip_ansi(colored), "INFO server started on :8080");
+}
+
+#[test]
+fn strip_ansi_removes_cursor_and_erase_sequences() {
+    assert_eq!(strip_ansi("\x1b[2K\x1b[1Gprogress 50%"), "progress 50%");
+    assert_eq!(strip_ansi("a\x1b[?25lb\x1b[?25hc"), "abc");
+}
+
+#[test]
+fn strip_ansi_removes_osc_sequences() {
+    assert_eq!(strip_ansi("\x1b]0;title\x07done"), "done");
+    assert_eq!(
+        strip_ansi("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\"),
+        "link"
+    );
+}
+
+#[test]
+fn strip_ansi_removes_nf_escapes_with_their_final_byte() {
+    assert_eq!(strip_ansi("\x1b(B\x1b[mok"), "ok");
+    assert_eq!(strip_ansi("a\x1b)0b"), "ab");
+}
+
+#[test]
+fn strip_ansi_leaves_plain_text_alone() {
+    let plain = "2024-01-01T00:00:00Z GET /health 200 [ok] ünïcode";
+    assert_eq!(strip_ansi(plain), plain);
+}
+
+// ── LogLineAssembler ──────────────────────────────────────────────
+
+/// Split a multiplexed log body (8-byte header: stream type, 3 padding bytes,
+/// big-endian payload length) into frames, as the daemon sends them.
+fn multiplexed_frames(mut buf: &[u8]) -> Vec<LogOutput> {
+    let mut frames = Vec::new();
+    while buf.len() >= 8 {
+        let len = u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]) as usize;
+        let message = buf[8..8 + len].to_vec().into();
+        frames.push(match buf[0] {
+            1 => LogOutput::StdOut { message },
+            2 => LogOutput::StdErr { message },
+            _ => LogOutput::StdIn { message },
+        });
+        buf = &buf[8 + len..];
+    }
+    frames
+}
+
+fn frame(stream: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = vec![stream, 0, 0, 0];
+    out.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    out.extend_from_slice(payload);
+    out
+}
+
+#[test]
+fn log_assembler_splits_multiplexed_stdout_and_stderr() {
+    let mut body = frame(1, b"hello ");
+    body.extend(frame(2, b"warning: disk\n"));
+    body.extend(frame(1, b"world\nnext"));
+    body.extend(frame(1, b" line\n"));
+
+    let mut assembler = LogLineAssembler::new(false, false);
+    let lines: Vec<_> = multiplexed_frames(&body)
+        .into_iter()
+        .flat_map(|f| assembler.push(f))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            (LogStream::Stderr, "warning: disk\n".to_string()),
+            (LogStream::Stdout, "hello world\n".to_string()),
+            (LogStream::Stdout, "next line\n".to_string()),
+        ]
+    );
+    assert!(assembler.finish().is_empty());
+}
+
+#[test]
+fn log_assembler_joins_utf8_split_across_frames() {
+    let text = "größe\n".as_bytes();
+    let mut body = frame(1, &text[..3]);
+    body.extend(frame(1, &text[3..]));
+
+    let mut assembler = LogLineAssembler::new(false, false);
+    let lines: Vec<_> = multiplexed_frames(&body)
+        .into_iter()
+        .flat_map(|f| assembler.push(f))
+        .collect();
+    assert_eq!(lines, vec![(LogStream::Stdout, "größe\n".to_string())]);
+}
+
+#[test]
+fn log_assembler_treats_tty_output_as_one_stream() {
+    let mut assembler = LogLineAssembler::new(true, false);
+    let mut lines = assembler.push(LogOutput::Console {
+        message: b"prompt> ".to_vec().into(),
+    });
+    lines.extend(assembler.push(LogOutput::StdErr {
+        message: b"ls\npartial".to_vec().into(),
+    }));
+    assert_eq!(
+        lines,
+        vec![(LogStream::Console, "prompt> ls\n".to_string())]
+    );
+    assert_eq!(
+        assembler.finish(),
+        vec![(LogStream::Console, "partial".to_string())]
+    );
+}
+
+#[test]
+fn log_assembler_drops_timestamps_of_continuation_frames() {
+    let mut body = frame(1, b"2024-05-01T10:00:00.000000001Z first half, ");
+    body.extend(frame(1, b"2024-05-01T10:00:00.000000002Z second half\n"));
+    body.extend(frame(1, b"2024-05-01T10:00:01.000000000Z next\n"));
+
+    let mut assembler = LogLineAssembler::new(false, true);
+    let lines: Vec<_> = multiplexed_frames(&body)
+        .into_iter()
+        .flat_map(|f| assembler.push(f))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            (
+                LogStream::Stdout,
+                "2024-05-01T10:00:00.000000001Z first half, second half\n".to_string()
+            ),
+            (
+                LogStream::Stdout,
+                "2024-05-01T10:00:01.000000000Z next\n".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn log_assembler_caps_a_line_without_newline() {
+    let mut assembler = LogLineAssembler::new(false, false);
+    let chunk = vec![b'x'; LOG_MAX_LINE_BYTES / 2 + 1];
+    assert!(assembler
+        .push(LogOutput::StdOut {
+            message: chunk.clone().into()
+        })
+        .is_empty());
+    let lines = assembler.push(LogOutput::StdOut {
+        message: chunk.into(),
+    });
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].1.len(), LOG_MAX_LINE_BYTES + 2);
+    assert!(assembler.finish().is_empty());
+}
+
+// ── LogVolume ─────────────────────────────────────────────────────
+
+#[test]
+fn log_volume_counts_lines_bytes_and_stderr() {
+    let mut volume = LogVolume::default();
+    volume.add(&LogOutput::StdOut {
+        message: b"GET / 200\n".to_vec().into(),
+    });
+    volume.add(&LogOutput::StdErr {
+        message: b"warn: slow\nerror: boom\n".to_vec().into(),
+    });
+    volume.add(&LogOutput::Console {
+        message: b"no newline".to_vec().into(),
+    });
+    volume.add(&LogOutput::StdOut {
+        message: Vec::new().into(),
+    });
+    assert_eq!(
+        volume,
+        LogVolume {
+            total_lines: 4,
+            stderr_lines: 2,
+            bytes: 10 + 23 + 10,
+        }
+    );
+}
+
+// ── validate_shell_path ───────────────────────────────────────────
+
+#[test]
+fn validate_shell_path_accepts_absolute_paths() {
+    assert!(validate_shell_path("/bin/ash").is_ok());
+    assert!(validate_shell_path("/usr/local/bin/zsh").is_ok());
+}
+
+#[test]
+fn validate_shell_path_rejects_relative_and_metacharacters() {
+    assert!(validate_shell_path("bash").is_err());
+    assert!(validate_shell_path("/bin/sh -c id").is_err());
+    assert!(validate_shell_path("/bin/sh;id").is_err());
+    assert!(validate_shell_path("/tmp/../bin/sh").is_err());
+}
+
+// ── validate_env_entry ────────────────────────────────────────────
+
+#[test]
+fn validate_env_entry_accepts_key_value() {
+    assert!(validate_env_entry("TERM=xterm-256color").is_ok());
+    assert!(validate_env_entry("_DEBUG=1").is_ok());
+    assert!(validate_env_entry("EMPTY=").is_ok());
+    assert!(validate_env_entry("OPTS=--a=b --c").is_ok());
+}
+
+#[test]
+fn validate_env_entry_rejects_bad_keys() {
+    assert!(validate_env_entry("NOVALUE").is_err());
+    assert!(validate_env_entry("=value").is_err());
+    assert!(validate_env_entry("1ABC=x").is_err());
+    assert!(validate_env_entry("MY-VAR=x").is_err());
+    assert!(validate_env_entry("A B=x").is_err());
+}
+
+// ── validate_context_name ─────────────────────────────────────────
+
+#[test]
+fn validate_context_name_accepts_typical_names() {
+    assert!(validate_context_name("default").is_ok());
+    assert!(validate_context_name("desktop-linux").is_ok());
+    assert!(validate_context_name("remote_server.v2").is_ok());
+}
+
+#[test]
+fn validate_context_name_rejects_empty() {
+    let err = validate_context_name("").unwrap_err();
+    assert!(err.contains("empty"));
+}
+
+#[test]
+fn validate_context_name_rejects_leading_symbol() {
+    assert!(validate_context_name("-rm").is_err());
+    assert!(validate_context_name(".hidden").is_err());
+}
+
+#[test]
+fn validate_context_name_rejects_shell_metacharacters() {
+    assert!(validate_context_name("prod; rm -rf /").is_err());
+    assert!(validate_context_name("a/b").is_err());
+}
+
+// ── parse_docker_contexts ─────────────────────────────────────────
+
+#[test]
+fn parse_docker_contexts_reads_json_lines() {
+    let out = r#"{"Current":true,"Description":"Current DOCKER_HOST based configuration","DockerEndpoint":"unix:///var/run/docker.sock","Error":"","Name":"default"}
+{"Current":false,"Description":"","DockerEndpoint":"tcp://10.0.0.5:2375","Error":"","Name":"remote"}
+"#;
+    let contexts = parse_docker_contexts(out);
+    assert_eq!(contexts.len(), 2);
+    assert_eq!(contexts[0].name, "default");
+    assert!(contexts[0].current);
+    assert_eq!(contexts[1].docker_endpoint, "tcp://10.0.0.5:2375");
+    assert!(!contexts[1].current);
+}
+
+#[test]
+fn parse_docker_contexts_skips_garbage_lines() {
+    let out = "not json\n{\"Name\":\"colima\"}\n\n";
+    let contexts = parse_docker_contexts(out);
+    assert_eq!(contexts.len(), 1);
+    assert_eq!(contexts[0].name, "colima");
+    assert_eq!(contexts[0].docker_endpoint, "");
+}
+
+// ── docker_cli_host ───────────────────────────────────────────────
+
+#[test]
+fn docker_cli_host_maps_paths() {
+    assert_eq!(docker_cli_host("default"), None);
+    assert_eq!(docker_cli_host(""), None);
+    assert_eq!(
+        docker_cli_host("/Users/me/.colima/default/docker.sock"),
+        Some("unix:///Users/me/.colima/default/docker.sock".to_string())
+    );
+    assert_eq!(
+        docker_cli_host("podman:/run/user/1000/podman/podman.sock"),
+        Some("unix:///run/user/1000/podman/podman.sock".to_string())
+    );
+    assert_eq!(
+        docker_cli_host("tcp://10.0.0.5:2375"),
+        Some("tcp://10.0.0.5:2375".to_string())
+    );
+}
+
+// ── format_bytes ──────────────────────────────────────────────────
+
+#[test]
+fn format_bytes_below_one_kib_is_whole_bytes() {
+    assert_eq!(format_bytes(0), "0 B");
+    assert_eq!(format_bytes(1), "1 B");
+    assert_eq!(format_bytes(1023), "1023 B");
+}
+
+#[test]
+fn format_bytes_uses_binary_units() {
+    assert_eq!(format_bytes(1024), "1.0 KiB");
+    assert_eq!(format_bytes(1536), "1.5 KiB");
+    assert_eq!(format_bytes(1048576), "1.0 MiB");
+    assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GiB");
+}
+
+#[test]
+fn format_bytes_rounds_to_one_decimal() {
+    // 1000 (decimal kB) is still below a binary KiB
+    assert_eq!(format_bytes(1000), "1000 B");
+    // 1_000_000 bytes = 976.56 KiB
+    assert_eq!(format_bytes(1_000_000), "976.6 KiB");
+    // Just below 1 MiB rounds up into the next unit instead of "1024.0 KiB"
+    assert_eq!(format_bytes(1048575), "1.0 MiB");
+}
+
+#[test]
+fn format_bytes_keeps_sign() {
+    assert_eq!(format_bytes(-1536), "-1.5 KiB");
+}
+
+// ── image_layers ──────────────────────────────────────────────────
+
+fn history_entry(created_by: &str, size: i64) -> HistoryResponseItem {
+    HistoryResponseItem {
+        created_by: created_by.to_string(),
+        size,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn image_layers_skips_metadata_steps_and_sorts_largest_first() {
+    let layers = vec!["sha256:base".to_string(), "sha256:app".to_string()];
+    // image_history is newest first
+    let history = vec![
+        history_entry("CMD [\"node\"]", 0),
+        history_entry("COPY . /app", 2048),
+        history_entry("ENV NODE_ENV=production", 0),
+        history_entry("ADD rootfs.tar /", 4096),
+    ];
+    let result = image_layers(&layers, &history);
+    assert_eq!(result.len(), 2);
+    assert_eq!(result[0].layer_id, "sha256:base");
+    assert_eq!(result[0].created_by, "ADD rootfs.tar /");
+    assert_eq!(result[0].size_human, "4.0 KiB");
+    assert_eq!(result[1].layer_id, "sha256:app");
+    assert_eq!(result[1].size, 2048);
+}
+
+#[test]
+fn image_layers_keeps_zero_byte_layers_paired() {
+    let layers = vec![
+        "sha256:base".to_string(),
+        "sha256:workdir".to_string(),
+        "sha256:cleanup".to_string(),
+        "sha256:app".to_string(),
+    ];
+    let history = vec![
+        history_entry("CMD [\"./app\"]", 0),
+        history_entry("COPY app /srv/app # buildkit", 512),
+        history_entry("RUN /bin/sh -c rm -f /tmp/cache # buildkit", 0),
+        history_entry("/bin/sh -c #(nop)  ENV APP_ENV=prod", 0),
+        history_entry("/bin/sh -c #(nop) WORKDIR /srv", 0),
+        history_entry("/bin/sh -c #(nop) ADD file:abc in / ", 4096),
+    ];
+    let result = image_layers(&layers, &history);
+    let paired: Vec<_> = result
+        .iter()
+        .map(|l| (l.layer_id.as_str(), l.created_by.as_str()))
+        .collect();
+    assert!(paired.contains(&("sha256:app", "COPY app /srv/app # buildkit")));
+    assert!(paired.contains(&(
+        "sha256:cleanup",
+        "RUN /bin/sh -c rm -f /tmp/cache # buildkit"
+    )));
+    assert!(paired.contains(&("sha256:workdir", "/bin/sh -c #(nop) WORKDIR /srv")));
+    assert!(paired.contains(&("sha256:base", "/bin/sh -c #(nop) ADD file:abc in / ")));
+}
+
+#[test]
+fn image_layers_handles_history_shorter_than_layers() {
+    let layers = vec![
+        "sha256:one".to_string(),
+        "sha256:two".to_string(),
+        "sha256:squashed".to_string(),
+    ];
+    let history = vec![history_entry("squashed", 1024)];
+    let result = image_layers(&layers, &history);
+    assert_eq!(result.len(), 3);
+    assert_eq!(result[0].layer_id, "sha256:squashed");
+    assert_eq!(result[0].created_by, "squashed");
+    assert!(result[1..]
+        .iter()
+        .all(|l| l.size == 0 && l.created_by.is_empty()));
+}
+
+// ── cpu_percent / mem_percent ─────────────────────────────────────
+
+fn cpu_stats(
+    total_usage: u64,
+    system_usage: u64,
+    online_cpus: u32,
+) -> bollard::models::ContainerCpuStats {
+    bollard::models::ContainerCpuStats {
+        cpu_usage: Some(bollard::models::ContainerCpuUsage {
+            total_usage: Some(total_usage),
+            ..Default::default()
+        }),
+        system_cpu_usage: Some(system_usage),
+        online_cpus: Some(online_cpus),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn cpu_percent_uses_deltas_and_online_cpus() {
+    let stats = ContainerStatsResponse {
+        precpu_stats: Some(cpu_stats(1_000_000, 10_000_000, 4)),
+        cpu_stats: Some(cpu_stats(1_500_000, 12_000_000, 4)),
+        ..Default::default()
+    };
+    // (500_000 / 2_000_000) * 4 cpus * 100 = 100%
+    assert!((cpu_percent(&stats) - 100.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn cpu_percent_is_zero_for_first_sample() {
+    let stats = ContainerStatsResponse {
+        precpu_stats: Some(bollard::models::ContainerCpuStats::default()),
+        cpu_stats: Some(cpu_stats(1_500_000, 12_000_000, 4)),
+        ..Default::default()
+    };
+    assert_eq!(cpu_percent(&stats), 0.0);
+    assert_eq!(cpu_percent(&ContainerStatsResponse::default()), 0.0);
+}
+
+#[test]
+fn mem_percent_excludes_inactive_file_cache() {
+    let stats = ContainerStatsResponse {
+        memory_stats: Some(bollard::models::ContainerMemoryStats {
+            usage: Some(300),
+            limit: Some(1000),
+            stats: Some(HashMap::from([("inactive_file".to_string(), 100)])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert!((mem_percent(&stats) - 20.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn mem_percent_is_zero_without_limit() {
+    let stats = ContainerStatsResponse {
+        memory_stats: Some(bollard::models::ContainerMemoryStats {
+            usage: Some(300),
+            limit: Some(0),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(mem_percent(&stats), 0.0);
+}
+
+// ── container_limits ──────────────────────────────────────────────
+
+const HOST_MEMORY: i64 = 16 * 1024 * 1024 * 1024;
+
+#[test]
+fn container_limits_falls_back_to_host_when_unlimited() {
+    let host_config = bollard::models::HostConfig {
+        memory: Some(0),
+        nano_cpus: Some(0),
+        ..Default::default()
+    };
+    let limits = container_limits(Some(&host_config), HOST_MEMORY, 8.0);
+    assert_eq!(limits.memory_bytes, HOST_MEMORY);
+    assert!(!limits.memory_limited);
+    assert_eq!(limits.cpus, 8.0);
+    assert!(!limits.cpu_limited);
+
+    assert_eq!(container_limits(None, HOST_MEMORY, 8.0), limits);
+}
+
+#[test]
+fn container_limits_reads_memory_and_nano_cpus() {
+    let host_config = bollard::models::HostConfig {
+        memory: Some(512 * 1024 * 1024),
+        nano_cpus: Some(1_500_000_000),
+        ..Default::default()
+    };
+    let limits = container_limits(Some(&host_config), HOST_MEMORY, 8.0);
+    assert_eq!(limits.memory_bytes, 512 * 1024 * 1024);
+    assert!(limits.memory_limited);
+    assert_eq!(limits.cpus, 1.5);
+    assert!(limits.cpu_limited);
+}
+
+#[test]
+fn container_limits_reads_cpu_quota_and_period() {
+    let host_config = bollard::models::HostConfig {
+        cpu_quota: Some(50_000),
+        cpu_period: Some(100_000),
+        ..Default::default()
+    };
+    let limits = container_limits(Some(&host_config), HOST_MEMORY, 8.0);
+    assert_eq!(limits.cpus, 0.5);
+    assert!(limits.cpu_limited);
+    assert!(!limits.memory_limited);
+}
+
+// ── StatsLimitsCache::host_totals ─────────────────────────────────
+
+#[tokio::test]
+async fn host_totals_does_not_cache_a_failed_lookup() {
+    let cache = StatsLimitsCache::new();
+    // Nothing listens on port 1, so `info` fails straight away
+    let docker =
+        Docker::connect_with_http("http://127.0.0.1:1", 1, bollard::API_DEFAULT_VERSION).unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+    assert_eq!(cache.host_totals(&docker, timeout).await, (0, 0.0));
+    assert!(cache.host.lock().unwrap().is_none());
+}
+
+// ── container_filters ─────────────────────────────────────────────
+
+#[test]
+fn container_filters_empty_when_unset() {
+    assert!(container_filters(None, None, None).unwrap().is_empty());
+    assert!(
+        container_filters(Some(vec![]), Some(String::new()), Some(vec![]))
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn container_filters_builds_status_and_name() {
+    let filters = container_filters(
+        Some(vec!["running".to_string(), "paused".to_string()]),
+        Some("web".to_string()),
+        None,
+    )
+    .unwrap();
+    assert_eq!(filters["status"], vec!["running", "paused"]);
+    assert_eq!(filters["name"], vec!["web"]);
+}
+
+#[test]
+fn container_filters_rejects_unknown_status() {
+    let err = container_filters(Some(vec!["sleeping".to_string()]), None, None).unwrap_err();
+    assert!(err.contains("sleeping"));
+}
+
+#[test]
+fn container_filters_rejects_bad_name() {
+    assert!(container_filters(None, Some("web; rm -rf /".to_string()), None).is_err());
+}
+
+#[test]
+fn container_filters_builds_label_map() {
+    let filters = container_filters(
+        None,
+        None,
+        Some(vec![
+            "com.docker.compose.project=shop".to_string(),
+            "io.example.tier".to_string(),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(filters.len(), 1);
+    assert_eq!(
+        filters["label"],
+        vec!["com.docker.compose.project=shop", "io.example.tier"]
+    );
+}
+
+// ── recreate_plan ─────────────────────────────────────────────────
+
+const RECREATE_ID: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+fn inspected_container() -> ContainerInspectResponse {
+    ContainerInspectResponse {
+        id: Some(RECREATE_ID.to_string()),
+        name: Some("/web".to_string()),
+        config: Some(bollard::models::ContainerConfig {
+            hostname: Some("0123456789ab".to_string()),
+            image: Some("nginx:1.25".to_string()),
+            env: Some(vec![
+                "PATH=/usr/local/bin:/usr/bin".to_string(),
+                "NGINX_VERSION=1.25".to_string(),
+                "APP_MODE=prod".to_string(),
+            ]),
+            cmd: Some(vec!["nginx".to_string(), "-g".to_string()]),
+            ..Default::default()
+        }),
+        host_config: Some(bollard::models::HostConfig {
+            binds: Some(vec!["/srv/site:/usr/share/nginx/html:ro".to_string()]),
+            network_mode: Some("appnet".to_string()),
+            restart_policy: Some(RestartPolicy {
+                name: Some(RestartPolicyNameEnum::ALWAYS),
+                maximum_retry_count: None,
+            }),
+            ..Default::default()
+        }),
+        mounts: Some(vec![
+            bollard::models::MountPoint {
+                typ: Some(MountPointTypeEnum::BIND),
+                source: Some("/srv/site".to_string()),
+                destination: Some("/usr/share/nginx/html".to_string()),
+                ..Default::default()
+            },
+            bollard::models::MountPoint {
+                typ: Some(MountPointTypeEnum::VOLUME),
+                name: Some("3f1c0anon".to_string()),
+                destination: Some("/var/cache/nginx".to_string()),
+                rw: Some(true),
+                ..Default::default()
+            },
+        ]),
+        network_settings: Some(bollard::models::NetworkSettings {
+            networks: Some(HashMap::from([
+                (
+                    "appnet".to_string(),
+                    EndpointSettings {
+                        aliases: Some(vec!["web".to_string(), "0123456789ab".to_string()]),
+                        ip_address: Some("172.20.0.5".to_string()),
+                        ..Default::default()
+                    },
+                ),
+                ("monitoring".to_string(), EndpointSettings::default()),
+            ])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn recreate_plan_keeps_config_volumes_and_networks() {
+    let plan = recreate_plan(&inspected_container(), None, None);
+    assert_eq!(plan.name.as_deref(), Some("web"));
+
+    let body = plan.body;
+    assert_eq!(body.image.as_deref(), Some("nginx:1.25"));
+    assert_eq!(body.hostname, None);
+    assert_eq!(body.env.as_ref().map(Vec::len), Some(3));
+
+    let host_config = body.host_config.unwrap();
+    assert_eq!(
+        host_config.binds,
+        Some(vec![
+            "/srv/site:/usr/share/nginx/html:ro".to_string(),
+            "3f1c0anon:/var/cache/nginx".to_string(),
+        ])
+    );
+    assert_eq!(
+        host_config.restart_policy.and_then(|r| r.name),
+        Some(RestartPolicyNameEnum::ALWAYS)
+    );
+
+    let endpoints = body.networking_config.unwrap().endpoints_config.unwrap();
+    let primary = &endpoints["appnet"];
+    assert_eq!(primary.aliases, Some(vec!["web".to_string()]));
+    assert_eq!(primary.ip_address, None);
+    assert_eq!(
+        plan.extra_networks
+            .iter()
+            .map(|(n, _)| n.as_str())
+            .collect::<Vec<_>>(),
+        vec!["monitoring"]
+    );
+}
+
+#[test]
+fn recreate_plan_drops_old_image_defaults_when_swapping_image() {
+    let old_image = ImageConfig {
+        env: Some(vec![
+            "PATH=/usr/local/bin:/usr/bin".to_string(),
+            "NGINX_VERSION=1.25".to_string(),
+        ]),
+        cmd: Some(vec!["nginx".to_string(), "-g".to_string()]),
+        ..Default::default()
+    };
+    let plan = recreate_plan(&inspected_container(), Some(&old_image), Some("nginx:1.27"));
+    assert_eq!(plan.body.image.as_deref(), Some("nginx:1.27"));
+    assert_eq!(plan.body.env, Some(vec!["APP_MODE=prod".to_string()]));
+    assert_eq!(plan.body.cmd, None);
+}
+
+#[test]
+fn recreate_plan_skips_networks_for_host_mode() {
+    let mut info = inspected_container();
+    info.host_config.as_mut().unwrap().network_mode = Some("host".to_string());
+    let plan = recreate_plan(&info, None, None);
+    assert!(plan.body.networking_config.is_none());
+    assert!(plan.extra_networks.is_empty());
+}
+
+// ── validate_list_limit ───────────────────────────────────────────
+
+#[test]
+fn validate_list_limit_accepts_positive() {
+    assert_eq!(validate_list_limit(1), Ok(1));
+    assert_eq!(validate_list_limit(50), Ok(50));
+}
+
+#[test]
+fn validate_list_limit_rejects_zero_negative_and_overflow() {
+    assert!(validate_list_limit(0).is_err());
+    assert!(validate_list_limit(-5).is_err());
+    assert!(validate_list_limit(i32::MAX as isize + 1).is_err());
+}
+
+// ── merge_env ─────────────────────────────────────────────────────
+
+#[test]
+fn merge_env_replaces_existing_keys_in_place() {
+    let existing = vec![
+        "PATH=/usr/bin".to_string(),
+        "LOG_LEVEL=info".to_string(),
+        "EMPTY=".to_string(),
+    ];
+    let updates = HashMap::from([("LOG_LEVEL".to_string(), "debug".to_string())]);
+    assert_eq!(
+        merge_env(existing, &updates),
+        vec!["PATH=/usr/bin", "LOG_LEVEL=debug", "EMPTY="]
+    );
+}
+
+#[test]
+fn merge_env_appends_new_keys_sorted() {
+    let existing = vec!["PATH=/usr/bin".to_string()];
+    let updates = HashMap::from([
+        ("ZETA".to_string(), "1".to_string()),
+        ("ALPHA".to_string(), "a=b".to_string()),
+    ]);
+    assert_eq!(
+        merge_env(existing, &updates),
+        vec!["PATH=/usr/bin", "ALPHA=a=b", "ZETA=1"]
+    );
+}
+
+// ── validate_label_filter ─────────────────────────────────────────
+
+#[test]
+fn validate_label_filter_accepts_key_and_key_value() {
+    assert!(validate_label_filter("com.docker.compose.project").is_ok());
+    assert!(validate_label_filter("com.docker.compose.project=my app").is_ok());
+    assert!(validate_label_filter("tier=").is_ok());
+}
+
+#[test]
+fn validate_label_filter_rejects_bad_keys() {
+    assert!(validate_label_filter("").is_err());
+    assert!(validate_label_filter("=value").is_err());
+    assert!(validate_label_filter("bad key=x").is_err());
+    assert!(validate_label_filter("key=line\nbreak").is_err());
+}
+
+// ── normalize_ports ───────────────────────────────────────────────
+
+fn binding(ip: &str, port: &str) -> bollard::models::PortBinding {
+    bollard::models::PortBinding {
+        host_ip: Some(ip.to_string()),
+        host_port: Some(port.to_string()),
+    }
+}
+
+#[test]
+fn normalize_ports_collapses_ipv4_and_ipv6_bindings() {
+    let mut ports = HashMap::new();
+    ports.insert(
+        "80/tcp".to_string(),
+        Some(vec![binding("::", "8080"), binding("0.0.0.0", "8080")]),
+    );
+    assert_eq!(
+        normalize_ports(&ports),
+        vec![PortMapping {
+            private_port: 80,
+            public_port: 8080,
+            protocol: "tcp".to_string(),
+            ip: "0.0.0.0".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn normalize_ports_skips_unpublished_ports() {
+    let mut ports = HashMap::new();
+    ports.insert("6379/tcp".to_string(), None);
+    ports.insert("53/udp".to_string(), Some(vec![]));
+    assert!(normalize_ports(&ports).is_empty());
+}
+
+#[test]
+fn normalize_ports_keeps_protocols_apart_and_sorted() {
+    let mut ports = HashMap::new();
+    ports.insert("53/udp".to_string(), Some(vec![binding("0.0.0.0", "5353")]));
+    ports.insert("53/tcp".to_string(), Some(vec![binding("0.0.0.0", "5353")]));
+    ports.insert(
+        "22/tcp".to_string(),
+        Some(vec![binding("127.0.0.1", "2222")]),
+    );
+    let mapped: Vec<(u16, String)> = normalize_ports(&ports)
+        .into_iter()
+        .map(|m| (m.private_port, m.protocol))
+        .collect();
+    assert_eq!(
+        mapped,
+        vec![
+            (22, "tcp".to_string()),
+            (53, "tcp".to_string()),
+            (53, "udp".to_string())
+        ]
+    );
+}
+
+// ── parse_container_action ────────────────────────────────────────
+
+#[test]
+fn parse_container_action_accepts_only_known_actions() {
+    assert_eq!(parse_container_action("start"), Ok(ContainerAction::Start));
+    assert_eq!(
+        parse_container_action("remove"),
+        Ok(ContainerAction::Remove)
+    );
+    assert!(parse_container_action("kill").is_err());
+    assert!(parse_container_action("Stop").is_err());
+}
+
+// ── parse_restart_policy ──────────────────────────────────────────
+
+#[test]
+fn parse_restart_policy_accepts_known_policies() {
+    assert_eq!(parse_restart_policy("no"), Ok(RestartPolicyNameEnum::NO));
+    assert_eq!(
+        parse_restart_policy("always"),
+        Ok(RestartPolicyNameEnum::ALWAYS)
+    );
+    assert_eq!(
+        parse_restart_policy("unless-stopped"),
+        Ok(RestartPolicyNameEnum::UNLESS_STOPPED)
+    );
+    assert_eq!(
+        parse_restart_policy("on-failure"),
+        Ok(RestartPolicyNameEnum::ON_FAILURE)
+    );
+}
+
+#[test]
+fn parse_restart_policy_rejects_unknown_or_empty() {
+    assert!(parse_restart_policy("").is_err());
+    assert!(parse_restart_policy("sometimes").is_err());
+    assert!(parse_restart_policy("Always").is_err());
+}
+
+// ── redact_env_entry ──────────────────────────────────────────────
+
+#[test]
+fn redact_env_entry_masks_secret_looking_keys() {
+    assert_eq!(
+        redact_env_entry("DB_PASSWORD=hunter2"),
+        "DB_PASSWORD=********"
+    );
+    assert_eq!(
+        redact_env_entry("github_token=abc"),
+        "github_token=********"
+    );
+    assert_eq!(redact_env_entry("API_KEY=xyz"), "API_KEY=********");
+    assert_eq!(redact_env_entry("CLIENT_SECRET="), "CLIENT_SECRET=********");
+}
+
+#[test]
+fn redact_env_entry_leaves_other_entries_alone() {
+    assert_eq!(redact_env_entry("PATH=/usr/bin:/bin"), "PATH=/usr/bin:/bin");
+    assert_eq!(
+        redact_env_entry("NODE_ENV=production"),
+        "NODE_ENV=production"
+    );
+    assert_eq!(redact_env_entry("NO_VALUE"), "NO_VALUE");
+}
+
+#[test]
+fn redact_env_entry_only_checks_the_key() {
+    assert_eq!(redact_env_entry("MODE=token-auth"), "MODE=token-auth");
+}
+
+// ── validate_stop_timeout ─────────────────────────────────────────
+
+#[test]
+fn validate_stop_timeout_accepts_zero_and_positive() {
+    assert!(validate_stop_timeout(0).is_ok());
+    assert!(validate_stop_timeout(10).is_ok());
+    assert!(validate_stop_timeout(600).is_ok());
+}
+
+#[test]
+fn validate_stop_timeout_rejects_negative_and_overflow() {
+    assert!(validate_stop_timeout(-1).is_err());
+    assert!(validate_stop_timeout(i32::MAX as i64 + 1).is_err());
+}
+
 // ── CommandResponse helpers ───────────────────────────────────────
 
 #[test]
@@ -91,3 +1373,971 @@ fn command_response_err_sets_fields() {
     assert!(resp.data.is_none());
     assert_eq!(resp.error, Some("something broke".to_string()));
 }
+
+#[test]
+fn command_response_err_coded_sets_code() {
+    let resp: CommandResponse<()> = CommandResponse::err_coded("CONFLICT", "in use");
+    assert!(!resp.success);
+    assert_eq!(resp.error, Some("in use".to_string()));
+    assert_eq!(resp.error_code, Some("CONFLICT".to_string()));
+}
+
+#[test]
+fn command_response_docker_err_categorises_status_codes() {
+    let server_error = |status_code| bollard::errors::Error::DockerResponseServerError {
+        status_code,
+        message: "boom".to_string(),
+    };
+    let code = |e| CommandResponse::<()>::docker_err(e).error_code.unwrap();
+    assert_eq!(code(server_error(404)), "NOT_FOUND");
+    assert_eq!(code(server_error(409)), "CONFLICT");
+    assert_eq!(code(server_error(403)), "PERMISSION_DENIED");
+    assert_eq!(code(server_error(500)), "DAEMON_ERROR");
+    assert_eq!(code(server_error(418)), "UNKNOWN");
+}
+
+#[test]
+fn command_response_docker_err_categorises_connection_errors() {
+    let io = |kind| bollard::errors::Error::IOError {
+        err: std::io::Error::from(kind),
+    };
+    let code = |e| CommandResponse::<()>::docker_err(e).error_code.unwrap();
+    assert_eq!(
+        code(io(std::io::ErrorKind::ConnectionRefused)),
+        "CONNECTION"
+    );
+    assert_eq!(
+        code(io(std::io::ErrorKind::PermissionDenied)),
+        "PERMISSION_DENIED"
+    );
+    assert_eq!(
+        code(bollard::errors::Error::SocketNotFoundError(
+            "/var/run/docker.sock".to_string()
+        )),
+        "CONNECTION"
+    );
+    assert_eq!(code(bollard::errors::Error::RequestTimeoutError), "TIMEOUT");
+}
+
+#[test]
+fn command_response_skips_missing_error_code_when_serialized() {
+    let json = serde_json::to_value(CommandResponse::ok(1)).unwrap();
+    assert!(json.get("error_code").is_none());
+}
+
+// ── group_compose_projects ────────────────────────────────────────
+
+fn summary(id: &str, name: &str, labels: &[(&str, &str)]) -> bollard::models::ContainerSummary {
+    bollard::models::ContainerSummary {
+        id: Some(id.to_string()),
+        names: Some(vec![format!("/{}", name)]),
+        labels: Some(
+            labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        ),
+        state: Some(bollard::models::ContainerSummaryStateEnum::RUNNING),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn group_compose_projects_groups_by_project_label() {
+    let projects = group_compose_projects(vec![
+        summary(
+            "2",
+            "web-db-1",
+            &[
+                (COMPOSE_PROJECT_LABEL, "web"),
+                (COMPOSE_SERVICE_LABEL, "db"),
+            ],
+        ),
+        summary(
+            "1",
+            "web-app-1",
+            &[
+                (COMPOSE_PROJECT_LABEL, "web"),
+                (COMPOSE_SERVICE_LABEL, "app"),
+            ],
+        ),
+        summary(
+            "3",
+            "api-app-1",
+            &[
+                (COMPOSE_PROJECT_LABEL, "api"),
+                (COMPOSE_SERVICE_LABEL, "app"),
+            ],
+        ),
+    ]);
+    assert_eq!(projects.len(), 2);
+    assert_eq!(projects[0].project, "api");
+    assert_eq!(projects[1].project, "web");
+    let names: Vec<&str> = projects[1]
+        .services
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["app", "db"]);
+    assert_eq!(projects[1].services[0].id, "1");
+    assert_eq!(projects[1].services[0].state, "running");
+}
+
+#[test]
+fn group_compose_projects_puts_unlabelled_containers_in_standalone_last() {
+    let projects = group_compose_projects(vec![
+        summary("1", "scratch", &[]),
+        summary(
+            "2",
+            "zeta-app-1",
+            &[
+                (COMPOSE_PROJECT_LABEL, "zeta"),
+                (COMPOSE_SERVICE_LABEL, "app"),
+            ],
+        ),
+    ]);
+    assert_eq!(projects.len(), 2);
+    assert_eq!(projects[0].project, "zeta");
+    assert_eq!(projects[1].project, STANDALONE_PROJECT);
+    assert_eq!(projects[1].services[0].name, "scratch");
+}
+
+#[test]
+fn group_compose_projects_handles_empty_list() {
+    assert!(group_compose_projects(vec![]).is_empty());
+}
+
+// ── validate_compose_project ──────────────────────────────────────
+
+#[test]
+fn validate_compose_project_accepts_typical_names() {
+    assert!(validate_compose_project("myapp").is_ok());
+    assert!(validate_compose_project("my-app_2").is_ok());
+    assert!(validate_compose_project("1app").is_ok());
+}
+
+#[test]
+fn validate_compose_project_rejects_bad_names() {
+    assert!(validate_compose_project("").is_err());
+    assert!(validate_compose_project("MyApp").is_err());
+    assert!(validate_compose_project("-app").is_err());
+    assert!(validate_compose_project("app;rm").is_err());
+    assert!(validate_compose_project("my app").is_err());
+}
+
+// ── container_health ──────────────────────────────────────────────
+
+#[test]
+fn container_health_reports_none_without_healthcheck() {
+    let state = bollard::models::ContainerState::default();
+    let health = container_health(Some(&state));
+    assert_eq!(health.status, "none");
+    assert_eq!(health.failing_streak, 0);
+    assert_eq!(health.last_output, None);
+    assert_eq!(container_health(None).status, "none");
+}
+
+#[test]
+fn container_health_reads_status_and_last_probe() {
+    let state = bollard::models::ContainerState {
+        health: Some(bollard::models::Health {
+            status: Some(bollard::models::HealthStatusEnum::UNHEALTHY),
+            failing_streak: Some(3),
+            log: Some(vec![
+                bollard::models::HealthcheckResult {
+                    exit_code: Some(0),
+                    output: Some("ok".to_string()),
+                    ..Default::default()
+                },
+                bollard::models::HealthcheckResult {
+                    exit_code: Some(1),
+                    output: Some("connection refused".to_string()),
+                    ..Default::default()
+                },
+            ]),
+        }),
+        ..Default::default()
+    };
+    let health = container_health(Some(&state));
+    assert_eq!(health.status, "unhealthy");
+    assert_eq!(health.failing_streak, 3);
+    assert_eq!(health.last_output.as_deref(), Some("connection refused"));
+    assert_eq!(health.last_exit_code, Some(1));
+}
+
+// ── exit_info ─────────────────────────────────────────────────────
+
+#[test]
+fn exit_info_reads_exit_code_and_oom_flag() {
+    let state = bollard::models::ContainerState {
+        exit_code: Some(137),
+        oom_killed: Some(true),
+        error: Some(String::new()),
+        finished_at: Some("2024-05-01T10:00:00Z".to_string()),
+        ..Default::default()
+    };
+    let info = exit_info("abc".to_string(), Some(&state));
+    assert!(info.success);
+    assert_eq!(info.exit_code, Some(137));
+    assert!(info.oom_killed);
+    assert_eq!(info.error, None);
+    assert_eq!(info.finished_at.as_deref(), Some("2024-05-01T10:00:00Z"));
+}
+
+#[test]
+fn exit_info_drops_zero_finished_at() {
+    let state = bollard::models::ContainerState {
+        finished_at: Some(ZERO_TIMESTAMP.to_string()),
+        error: Some("OCI runtime create failed".to_string()),
+        ..Default::default()
+    };
+    let info = exit_info("abc".to_string(), Some(&state));
+    assert_eq!(info.finished_at, None);
+    assert!(!info.oom_killed);
+    assert_eq!(info.error.as_deref(), Some("OCI runtime create failed"));
+}
+
+// ── volume_users / network_users ──────────────────────────────────
+
+fn container_using(
+    id: &str,
+    volume: &str,
+    network: (&str, &str),
+) -> bollard::models::ContainerSummary {
+    bollard::models::ContainerSummary {
+        id: Some(id.to_string()),
+        names: Some(vec![format!("/{}-name", id)]),
+        mounts: Some(vec![bollard::models::MountPoint {
+            name: Some(volume.to_string()),
+            ..Default::default()
+        }]),
+        network_settings: Some(bollard::models::ContainerSummaryNetworkSettings {
+            networks: Some(HashMap::from([(
+                network.0.to_string(),
+                bollard::models::EndpointSettings {
+                    network_id: Some(network.1.to_string()),
+                    ..Default::default()
+                },
+            )])),
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn volume_users_matches_mount_name() {
+    let containers = vec![
+        container_using("a", "data", ("bridge", "111")),
+        container_using("b", "cache", ("bridge", "111")),
+    ];
+    let users = volume_users(&containers, "data");
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].id, "a");
+    assert_eq!(users[0].name, "a-name");
+    assert!(volume_users(&containers, "missing").is_empty());
+}
+
+#[test]
+fn network_users_matches_name_or_id_prefix() {
+    let containers = vec![
+        container_using("a", "data", ("web", "abcdef123")),
+        container_using("b", "data", ("bridge", "999999")),
+    ];
+    assert_eq!(network_users(&containers, "web")[0].id, "a");
+    assert_eq!(network_users(&containers, "abcdef")[0].id, "a");
+    assert_eq!(network_users(&containers, "999999")[0].id, "b");
+    assert!(network_users(&containers, "other").is_empty());
+}
+
+// ── disk_usage_total ──────────────────────────────────────────────
+
+#[test]
+fn disk_usage_total_sums_reported_categories() {
+    let df = bollard::models::SystemDataUsageResponse {
+        images_disk_usage: Some(bollard::models::ImagesDiskUsage {
+            total_size: Some(1000),
+            ..Default::default()
+        }),
+        volumes_disk_usage: Some(bollard::models::VolumesDiskUsage {
+            total_size: Some(500),
+            ..Default::default()
+        }),
+        build_cache_disk_usage: Some(bollard::models::BuildCacheDiskUsage::default()),
+        ..Default::default()
+    };
+    assert_eq!(disk_usage_total(&df), Some(1500));
+}
+
+#[test]
+fn disk_usage_total_is_none_without_totals() {
+    let df = bollard::models::SystemDataUsageResponse::default();
+    assert_eq!(disk_usage_total(&df), None);
+}
+
+#[test]
+fn images_disk_usage_prefers_df_and_falls_back_to_summed_sizes() {
+    let images = [
+        bollard::models::ImageSummary {
+            size: 300,
+            ..Default::default()
+        },
+        bollard::models::ImageSummary {
+            size: 200,
+            ..Default::default()
+        },
+    ];
+    let df = bollard::models::SystemDataUsageResponse {
+        images_disk_usage: Some(bollard::models::ImagesDiskUsage {
+            total_size: Some(400),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(images_disk_usage(Some(&df), Some(&images)), Some(400));
+
+    let empty_df = bollard::models::SystemDataUsageResponse::default();
+    assert_eq!(images_disk_usage(Some(&empty_df), Some(&images)), Some(500));
+    assert_eq!(images_disk_usage(None, None), None);
+}
+
+// ── split_log_timestamp / format_log_line ─────────────────────────
+
+#[test]
+fn split_log_timestamp_separates_daemon_prefix() {
+    let (ts, rest) = split_log_timestamp("2024-05-01T10:00:00.123456789Z hello world");
+    assert_eq!(ts, Some("2024-05-01T10:00:00.123456789Z"));
+    assert_eq!(rest, "hello world");
+}
+
+#[test]
+fn split_log_timestamp_leaves_plain_lines_alone() {
+    assert_eq!(split_log_timestamp("hello world"), (None, "hello world"));
+    assert_eq!(split_log_timestamp("no-spaces"), (None, "no-spaces"));
+    assert_eq!(
+        split_log_timestamp("2024-05-01 hello"),
+        (None, "2024-05-01 hello")
+    );
+}
+
+#[test]
+fn format_log_line_toggles_timestamp_and_strips_ansi() {
+    let line = "2024-05-01T10:00:00Z \x1b[31merror\x1b[0m";
+    assert_eq!(format_log_line(line, true, false), line);
+    assert_eq!(format_log_line(line, false, false), "\x1b[31merror\x1b[0m");
+    assert_eq!(format_log_line(line, false, true), "error");
+}
+
+// ── split_log_details ─────────────────────────────────────────────
+
+#[test]
+fn split_log_details_extracts_attributes() {
+    let (attributes, line) = split_log_details(
+        "2024-05-01T10:00:00Z com.example.team=core,env=prod%2Fus+east hello world",
+    );
+    assert_eq!(line, "2024-05-01T10:00:00Z hello world");
+    assert_eq!(attributes.len(), 2);
+    assert_eq!(attributes["com.example.team"], "core");
+    assert_eq!(attributes["env"], "prod/us east");
+}
+
+#[test]
+fn split_log_details_handles_lines_without_attributes() {
+    let (attributes, line) = split_log_details("2024-05-01T10:00:00Z  hello world");
+    assert!(attributes.is_empty());
+    assert_eq!(line, "2024-05-01T10:00:00Z hello world");
+
+    let (attributes, line) = split_log_details(" no timestamp");
+    assert!(attributes.is_empty());
+    assert_eq!(line, "no timestamp");
+}
+
+// ── exec_control_sequence ─────────────────────────────────────────
+
+#[test]
+fn exec_control_sequence_maps_known_signals() {
+    assert_eq!(exec_control_sequence("SIGINT"), Ok("\x03"));
+    assert_eq!(exec_control_sequence("EOF"), Ok("\x04"));
+    assert_eq!(exec_control_sequence("SIGTSTP"), Ok("\x1a"));
+    assert_eq!(exec_control_sequence("SIGQUIT"), Ok("\x1c"));
+    assert!(exec_control_sequence("SIGKILL").is_err());
+    assert!(exec_control_sequence("sigint").is_err());
+}
+
+// ── base64_encode / utf8_complete_len ─────────────────────────────
+
+#[test]
+fn base64_encode_pads_partial_groups() {
+    assert_eq!(base64_encode(b""), "");
+    assert_eq!(base64_encode(b"f"), "Zg==");
+    assert_eq!(base64_encode(b"fo"), "Zm8=");
+    assert_eq!(base64_encode(b"foo"), "Zm9v");
+    assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    assert_eq!(base64_encode(&[0xff, 0xfe, 0x00]), "//4A");
+}
+
+#[test]
+fn utf8_complete_len_holds_back_split_characters() {
+    assert_eq!(utf8_complete_len(b"plain"), 5);
+    // "é" is C3 A9, "€" is E2 82 AC, "😀" is F0 9F 98 80
+    assert_eq!(utf8_complete_len(b"caf\xc3"), 3);
+    assert_eq!(utf8_complete_len(b"caf\xc3\xa9"), 5);
+    assert_eq!(utf8_complete_len(b"\xe2\x82"), 0);
+    assert_eq!(utf8_complete_len(b"a\xf0\x9f\x98"), 1);
+    assert_eq!(utf8_complete_len(b"a\xf0\x9f\x98\x80"), 5);
+}
+
+#[test]
+fn utf8_complete_len_passes_invalid_bytes_through() {
+    // Latin-1 "é" and stray continuation bytes can't be completed, so nothing waits
+    assert_eq!(utf8_complete_len(b"caf\xe9!"), 5);
+    assert_eq!(utf8_complete_len(b"\x80\x80\x80\x80"), 4);
+    assert_eq!(utf8_complete_len(b"\xff"), 1);
+}
+
+// ── normalize_mounts ──────────────────────────────────────────────
+
+#[test]
+fn normalize_mounts_maps_bind_and_volume_mounts() {
+    let mounts = vec![
+        bollard::models::MountPoint {
+            typ: Some(MountPointTypeEnum::BIND),
+            source: Some("/Users/me/project".to_string()),
+            destination: Some("/app".to_string()),
+            mode: Some("ro".to_string()),
+            rw: Some(false),
+            ..Default::default()
+        },
+        bollard::models::MountPoint {
+            typ: Some(MountPointTypeEnum::VOLUME),
+            name: Some("pgdata".to_string()),
+            source: Some("/var/lib/docker/volumes/pgdata/_data".to_string()),
+            destination: Some("/var/lib/postgresql/data".to_string()),
+            mode: Some("z".to_string()),
+            rw: Some(true),
+            ..Default::default()
+        },
+    ];
+    let normalized = normalize_mounts(&mounts);
+    assert_eq!(normalized.len(), 2);
+    assert_eq!(normalized[0].kind, "bind");
+    assert_eq!(normalized[0].name, None);
+    assert!(!normalized[0].rw);
+    assert_eq!(normalized[1].kind, "volume");
+    assert_eq!(normalized[1].name.as_deref(), Some("pgdata"));
+    assert_eq!(normalized[1].destination, "/var/lib/postgresql/data");
+}
+
+#[test]
+fn normalize_mounts_handles_no_mounts() {
+    assert!(normalize_mounts(&[]).is_empty());
+}
+
+// ── validate_platform ─────────────────────────────────────────────
+
+#[test]
+fn validate_platform_accepts_os_arch_and_variant() {
+    assert!(validate_platform("linux/amd64").is_ok());
+    assert!(validate_platform("linux/arm64/v8").is_ok());
+    assert!(validate_platform("windows/amd64").is_ok());
+}
+
+#[test]
+fn validate_platform_rejects_malformed_values() {
+    assert!(validate_platform("").is_err());
+    assert!(validate_platform("amd64").is_err());
+    assert!(validate_platform("linux/").is_err());
+    assert!(validate_platform("linux/arm64/v8/extra").is_err());
+    assert!(validate_platform("Linux/AMD64").is_err());
+    assert!(validate_platform("linux/amd64;rm").is_err());
+}
+
+// ── normalize_networks ────────────────────────────────────────────
+
+#[test]
+fn normalize_networks_flattens_endpoints_sorted_by_name() {
+    let networks = HashMap::from([
+        (
+            "web".to_string(),
+            bollard::models::EndpointSettings {
+                network_id: Some("n2".to_string()),
+                ip_address: Some("172.20.0.3".to_string()),
+                gateway: Some("172.20.0.1".to_string()),
+                mac_address: Some("02:42:ac:14:00:03".to_string()),
+                aliases: Some(vec!["api".to_string()]),
+                ..Default::default()
+            },
+        ),
+        (
+            "bridge".to_string(),
+            bollard::models::EndpointSettings {
+                network_id: Some("n1".to_string()),
+                ip_address: Some("172.17.0.2".to_string()),
+                ..Default::default()
+            },
+        ),
+    ]);
+    let normalized = normalize_networks(&networks);
+    assert_eq!(normalized.len(), 2);
+    assert_eq!(normalized[0].network_name, "bridge");
+    assert_eq!(normalized[0].gateway, "");
+    assert!(normalized[0].aliases.is_empty());
+    assert_eq!(normalized[1].network_name, "web");
+    assert_eq!(normalized[1].ip_address, "172.20.0.3");
+    assert_eq!(normalized[1].mac_address, "02:42:ac:14:00:03");
+    assert_eq!(normalized[1].aliases, vec!["api".to_string()]);
+}
+
+// ── validate_export_path ──────────────────────────────────────────
+
+#[test]
+fn validate_export_path_accepts_file_in_existing_dir() {
+    let dest = std::env::temp_dir().join("opentainer-export-test.tar");
+    assert!(validate_export_path(dest.to_str().unwrap()).is_ok());
+}
+
+#[test]
+fn validate_export_path_rejects_relative_missing_dir_and_directory() {
+    assert!(validate_export_path("export.tar").is_err());
+    assert!(validate_export_path("/definitely/not/a/real/dir/export.tar").is_err());
+    assert!(validate_export_path(std::env::temp_dir().to_str().unwrap()).is_err());
+}
+
+// ── ExportFile ────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn export_file_writes_log_lines_as_received() {
+    let dest = std::env::temp_dir().join("opentainer-export-logs-test.log");
+    let file = ExportFile::create(dest.clone()).unwrap();
+    let frames = [
+        LogOutput::StdOut {
+            message: "2024-01-01T00:00:00Z starting\n".into(),
+        },
+        LogOutput::StdErr {
+            message: "2024-01-01T00:00:01Z warning: low disk\n".into(),
+        },
+        LogOutput::StdOut {
+            message: "partial ".into(),
+        },
+        LogOutput::StdOut {
+            message: "line\n".into(),
+        },
+    ];
+    for frame in frames {
+        assert!(file.write(frame.into_bytes()).await);
+    }
+    file.finish().await.unwrap();
+
+    let written = std::fs::read_to_string(&dest).unwrap();
+    let _ = std::fs::remove_file(&dest);
+    assert_eq!(
+        written,
+        "2024-01-01T00:00:00Z starting\n2024-01-01T00:00:01Z warning: low disk\npartial line\n"
+    );
+}
+
+#[tokio::test]
+async fn export_file_abort_removes_partial_file() {
+    let dest = std::env::temp_dir().join("opentainer-export-abort-test.tar");
+    let file = ExportFile::create(dest.clone()).unwrap();
+    assert!(file.write(vec![0u8; 1024]).await);
+    file.abort().await;
+    assert!(!dest.exists());
+}
+
+// ── split_container_path ──────────────────────────────────────────
+
+#[test]
+fn split_container_path_separates_dir_and_name() {
+    assert_eq!(split_container_path("/file"), Some(("/", "file")));
+    assert_eq!(split_container_path("/a/b"), Some(("/a", "b")));
+    assert_eq!(
+        split_container_path("/etc/nginx/nginx.conf"),
+        Some(("/etc/nginx", "nginx.conf"))
+    );
+}
+
+#[test]
+fn split_container_path_rejects_directories() {
+    assert_eq!(split_container_path("/a/"), None);
+    assert_eq!(split_container_path("/"), None);
+    assert_eq!(split_container_path("file"), None);
+}
+
+// ── validate_request_timeout ──────────────────────────────────────
+
+#[test]
+fn validate_request_timeout_accepts_range_bounds() {
+    assert!(validate_request_timeout(5).is_ok());
+    assert!(validate_request_timeout(DEFAULT_REQUEST_TIMEOUT_SECS).is_ok());
+    assert!(validate_request_timeout(3600).is_ok());
+}
+
+#[test]
+fn validate_request_timeout_rejects_out_of_range() {
+    assert!(validate_request_timeout(0).is_err());
+    assert!(validate_request_timeout(4).is_err());
+    assert!(validate_request_timeout(3601).is_err());
+}
+
+// ── container_change ──────────────────────────────────────────────
+
+fn container_event(action: &str) -> bollard::models::EventMessage {
+    bollard::models::EventMessage {
+        typ: Some(bollard::models::EventMessageTypeEnum::CONTAINER),
+        action: Some(action.to_string()),
+        actor: Some(bollard::models::EventActor {
+            id: Some("abc123".to_string()),
+            attributes: Some(HashMap::from([("name".to_string(), "web".to_string())])),
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn container_change_maps_lifecycle_actions_to_states() {
+    let change = container_change(&container_event("start")).unwrap();
+    assert_eq!(change.id, "abc123");
+    assert_eq!(change.name.as_deref(), Some("web"));
+    assert_eq!(change.state, "running");
+    assert_eq!(
+        container_change(&container_event("die")).unwrap().state,
+        "exited"
+    );
+    assert_eq!(
+        container_change(&container_event("stop")).unwrap().state,
+        "exited"
+    );
+    assert_eq!(
+        container_change(&container_event("destroy")).unwrap().state,
+        "removed"
+    );
+}
+
+#[test]
+fn container_change_ignores_other_events() {
+    assert!(container_change(&container_event("exec_start: sh")).is_none());
+    let mut image_event = container_event("start");
+    image_event.typ = Some(bollard::models::EventMessageTypeEnum::IMAGE);
+    assert!(container_change(&image_event).is_none());
+}
+
+// ── image_prune_filters ───────────────────────────────────────────
+
+#[test]
+fn image_prune_filters_defaults_to_no_filters() {
+    assert!(image_prune_filters(None, false).unwrap().is_empty());
+    assert!(image_prune_filters(Some(String::new()), false)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn image_prune_filters_adds_until_and_all() {
+    let filters = image_prune_filters(Some("24h".to_string()), true).unwrap();
+    assert_eq!(filters.get("until"), Some(&vec!["24h".to_string()]));
+    assert_eq!(filters.get("dangling"), Some(&vec!["false".to_string()]));
+}
+
+#[test]
+fn image_prune_filters_rejects_bad_until() {
+    assert!(image_prune_filters(Some("yesterday".to_string()), false).is_err());
+    assert!(image_prune_filters(Some("24x".to_string()), false).is_err());
+    assert!(image_prune_filters(Some("h".to_string()), false).is_err());
+}
+
+#[test]
+fn validate_prune_until_accepts_durations_and_timestamps() {
+    for until in [
+        "24h",
+        "1h30m",
+        "90s",
+        "1.5h",
+        "500ms",
+        "1714557600",
+        "2024-05-01",
+        "2024-05-01T10:00:00Z",
+    ] {
+        assert!(validate_prune_until(until).is_ok(), "{}", until);
+    }
+}
+
+// ── container_urls / url_host_for_path ────────────────────────────
+
+fn port(private_port: u16, public_port: u16, protocol: &str) -> PortMapping {
+    PortMapping {
+        private_port,
+        public_port,
+        protocol: protocol.to_string(),
+        ip: "0.0.0.0".to_string(),
+    }
+}
+
+#[test]
+fn container_urls_lists_tcp_ports_with_common_http_first() {
+    let ports = vec![
+        port(5432, 5432, "tcp"),
+        port(53, 5353, "udp"),
+        port(443, 8443, "tcp"),
+        port(8080, 18080, "tcp"),
+    ];
+    let urls = container_urls(&ports, "localhost");
+    let list: Vec<&str> = urls.iter().map(|u| u.url.as_str()).collect();
+    assert_eq!(
+        list,
+        vec![
+            "https://localhost:8443",
+            "http://localhost:18080",
+            "http://localhost:5432"
+        ]
+    );
+}
+
+#[test]
+fn container_urls_handles_no_published_ports() {
+    assert!(container_urls(&[], "localhost").is_empty());
+}
+
+#[test]
+fn url_host_for_path_uses_remote_host() {
+    assert_eq!(url_host_for_path("default"), "localhost");
+    assert_eq!(
+        url_host_for_path("/Users/me/.colima/default/docker.sock"),
+        "localhost"
+    );
+    assert_eq!(
+        url_host_for_path("podman:/run/podman/podman.sock"),
+        "localhost"
+    );
+    assert_eq!(
+        url_host_for_path("unix:///var/run/docker.sock"),
+        "localhost"
+    );
+    assert_eq!(url_host_for_path("tcp://192.168.1.10:2376"), "192.168.1.10");
+    assert_eq!(url_host_for_path("ssh://user@build-box"), "build-box");
+    assert_eq!(url_host_for_path("tcp://[fd00::1]:2375"), "[fd00::1]");
+}
+
+// ── validate_dockerfile_path ──────────────────────────────────────
+
+#[test]
+fn validate_dockerfile_path_stays_inside_context() {
+    assert!(validate_dockerfile_path("Dockerfile").is_ok());
+    assert!(validate_dockerfile_path("./docker/Dockerfile.dev").is_ok());
+    assert!(validate_dockerfile_path("").is_err());
+    assert!(validate_dockerfile_path("../Dockerfile").is_err());
+    assert!(validate_dockerfile_path("/etc/Dockerfile").is_err());
+}
+
+// ── validate_labels ───────────────────────────────────────────────
+
+#[test]
+fn validate_labels_accepts_reverse_dns_keys() {
+    let labels = HashMap::from([
+        ("com.example.role".to_string(), "db".to_string()),
+        ("tier".to_string(), String::new()),
+        ("app-2.version".to_string(), "1.0".to_string()),
+    ]);
+    assert!(validate_labels(&labels).is_ok());
+}
+
+#[test]
+fn validate_labels_rejects_bad_and_reserved_keys() {
+    for key in [
+        "",
+        "Com.Example",
+        "a..b",
+        ".a",
+        "a-",
+        "a_b",
+        "io.opentainer.created",
+    ] {
+        let labels = HashMap::from([(key.to_string(), "x".to_string())]);
+        assert!(validate_labels(&labels).is_err(), "{key}");
+    }
+    let labels = HashMap::from([("role".to_string(), "a\nb".to_string())]);
+    assert!(validate_labels(&labels).is_err());
+}
+
+#[test]
+fn resource_labels_adds_the_app_label() {
+    let labels = resource_labels(None).unwrap();
+    assert_eq!(
+        labels.get(OPENTAINER_CREATED_LABEL).map(String::as_str),
+        Some("true")
+    );
+}
+
+// ── is_stale_connection_error ─────────────────────────────────────
+
+#[test]
+fn is_stale_connection_error_detects_dropped_sockets_only() {
+    let io = |kind| bollard::errors::Error::IOError {
+        err: std::io::Error::from(kind),
+    };
+    assert!(is_stale_connection_error(&io(
+        std::io::ErrorKind::BrokenPipe
+    )));
+    assert!(is_stale_connection_error(&io(
+        std::io::ErrorKind::ConnectionReset
+    )));
+    assert!(is_stale_connection_error(&io(
+        std::io::ErrorKind::UnexpectedEof
+    )));
+
+    // A daemon that is down or refusing requests won't be fixed by reconnecting
+    assert!(!is_stale_connection_error(&io(
+        std::io::ErrorKind::ConnectionRefused
+    )));
+    assert!(!is_stale_connection_error(
+        &bollard::errors::Error::SocketNotFoundError("/var/run/docker.sock".to_string())
+    ));
+    assert!(!is_stale_connection_error(
+        &bollard::errors::Error::DockerResponseServerError {
+            status_code: 500,
+            message: "boom".to_string(),
+        }
+    ));
+}
+
+// ── registry mirror ───────────────────────────────────────────────
+
+#[test]
+fn validate_registry_mirror_requires_a_registry_host() {
+    assert!(validate_registry_mirror("mirror.corp").is_ok());
+    assert!(validate_registry_mirror("mirror.corp:5000/dockerhub").is_ok());
+    assert!(validate_registry_mirror("localhost:5000").is_ok());
+    assert!(validate_registry_mirror("mirror").is_err());
+    assert!(validate_registry_mirror("https://mirror.corp").is_err());
+    assert!(validate_registry_mirror("mirror.corp/").is_err());
+    assert!(validate_registry_mirror("mirror.corp/hub:latest").is_err());
+}
+
+#[test]
+fn mirrored_image_ref_rewrites_docker_hub_refs_only() {
+    assert_eq!(
+        mirrored_image_ref("nginx:1.27", "mirror.corp").as_deref(),
+        Some("mirror.corp/library/nginx:1.27")
+    );
+    assert_eq!(
+        mirrored_image_ref("grafana/grafana", "mirror.corp:5000/hub").as_deref(),
+        Some("mirror.corp:5000/hub/grafana/grafana")
+    );
+    assert_eq!(
+        mirrored_image_ref("docker.io/library/redis", "mirror.corp").as_deref(),
+        Some("mirror.corp/library/redis")
+    );
+    assert_eq!(mirrored_image_ref("ghcr.io/org/app:1", "mirror.corp"), None);
+}
+
+// ── parse_build_step ──────────────────────────────────────────────
+
+#[test]
+fn parse_build_step_reads_classic_builder_output() {
+    let output = "Step 1/3 : FROM alpine:3.20\n ---> 324bc02ae123\nStep 2/3 : RUN apk add --no-cache curl\n ---> Running in 5d1c\nStep 3/3 : CMD [\"curl\"]\nSuccessfully built 9a0b\n";
+    let steps: Vec<BuildProgress> = output.lines().filter_map(parse_build_step).collect();
+    assert_eq!(steps.len(), 3);
+    assert_eq!(
+        steps[1],
+        BuildProgress {
+            current_step: 2,
+            total_steps: 3,
+            message: "RUN apk add --no-cache curl".to_string(),
+        }
+    );
+    assert_eq!(steps[2].message, "CMD [\"curl\"]");
+}
+
+#[test]
+fn parse_build_step_ignores_other_lines() {
+    assert_eq!(parse_build_step(" ---> Using cache"), None);
+    assert_eq!(parse_build_step("Step x/3 : RUN true"), None);
+    assert_eq!(parse_build_step("#5 [2/3] RUN apk add curl"), None);
+}
+
+// ── restart_info ──────────────────────────────────────────────────
+
+#[test]
+fn restart_info_reads_count_and_times() {
+    let info = ContainerInspectResponse {
+        restart_count: Some(14),
+        state: Some(bollard::models::ContainerState {
+            status: Some(bollard::models::ContainerStateStatusEnum::RESTARTING),
+            started_at: Some("2024-05-01T10:00:00Z".to_string()),
+            finished_at: Some("2024-05-01T10:00:03Z".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let restart = restart_info(&info);
+    assert_eq!(restart.restart_count, 14);
+    assert_eq!(restart.started_at.as_deref(), Some("2024-05-01T10:00:00Z"));
+    assert_eq!(restart.finished_at.as_deref(), Some("2024-05-01T10:00:03Z"));
+    assert_eq!(restart.status.as_deref(), Some("restarting"));
+}
+
+#[test]
+fn restart_info_handles_never_started_container() {
+    let info = ContainerInspectResponse {
+        state: Some(bollard::models::ContainerState {
+            status: Some(bollard::models::ContainerStateStatusEnum::CREATED),
+            started_at: Some(ZERO_TIMESTAMP.to_string()),
+            finished_at: Some(ZERO_TIMESTAMP.to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let restart = restart_info(&info);
+    assert_eq!(restart.restart_count, 0);
+    assert_eq!(restart.started_at, None);
+    assert_eq!(restart.finished_at, None);
+}
+
+// ── first_shell_path ──────────────────────────────────────────────
+
+#[test]
+fn first_shell_path_takes_first_absolute_path() {
+    assert_eq!(
+        first_shell_path("/usr/bin/bash\n").as_deref(),
+        Some("/usr/bin/bash")
+    );
+    assert_eq!(
+        first_shell_path("sh\n/bin/ash\n").as_deref(),
+        Some("/bin/ash")
+    );
+    assert_eq!(first_shell_path(""), None);
+    assert_eq!(first_shell_path("/bin/sh; rm -rf /\n"), None);
+}
+
+// ── validate_container_path ───────────────────────────────────────
+
+#[test]
+fn validate_container_path_requires_absolute_paths_without_traversal() {
+    assert!(validate_container_path("/etc/nginx/nginx.conf").is_ok());
+    assert!(validate_container_path("/app/..config").is_ok());
+    assert!(validate_container_path("etc/hosts").is_err());
+    assert!(validate_container_path("/app/../etc/shadow").is_err());
+    assert!(validate_container_path("/app/a\0b").is_err());
+}
+
+// ── socket_access_from_error ──────────────────────────────────────
+
+#[test]
+fn socket_access_tells_permission_denied_from_daemon_down() {
+    let io = |kind| bollard::errors::Error::IOError {
+        err: std::io::Error::from(kind),
+    };
+    let denied = socket_access_from_error(&io(std::io::ErrorKind::PermissionDenied));
+    assert_eq!(denied.status, "permission_denied");
+    assert!(denied.hint.unwrap().contains("usermod -aG docker"));
+
+    let down = socket_access_from_error(&io(std::io::ErrorKind::ConnectionRefused));
+    assert_eq!(down.status, "not_running");
+    let missing = socket_access_from_error(&bollard::errors::Error::SocketNotFoundError(
+        "/var/run/docker.sock".to_string(),
+    ));
+    assert_eq!(missing.status, "not_running");
+
+    let other = socket_access_from_error(&bollard::errors::Error::RequestTimeoutError);
+    assert_eq!(other.status, "error");
+    assert!(other.error.is_some());
+}
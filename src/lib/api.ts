@@ -5,6 +5,7 @@ import { openUrl } from "@tauri-apps/plugin-opener";
 // Define the interface for the API
 export interface DockerStatusResponse {
     running: boolean;
+    provider: "none" | "orbstack" | "colima" | "podman" | "docker-desktop" | "docker-engine";
     colima_installed: boolean;
     we_started: boolean;
     error?: string;
@@ -13,10 +14,11 @@ export interface DockerStatusResponse {
 export interface AppApi {
     listContainers: () => Promise<{ success: boolean; data?: any[]; error?: string }>;
     containerAction: (id: string, action: string) => Promise<{ success: boolean; error?: string }>;
-    startLogs: (id: string, onData: (data: string) => void, options?: { timestamps?: boolean }) => () => void;
+    startLogs: (id: string, onData: (data: string) => void, options?: { timestamps?: boolean; tail?: string }) => () => void;
     startExec: (sessionId: string, cols: number, rows: number, onData: (data: string) => void, containerId: string) => {
         write: (data: string) => void;
         resize: (w: number, h: number) => void;
+        restart: () => void;
         dispose: () => void;
     };
     listImages: () => Promise<{ success: boolean; data?: any[]; error?: string }>;
@@ -28,7 +30,7 @@ export interface AppApi {
         onError: (err: string) => void
     ) => () => void;
     listVolumes: () => Promise<{ success: boolean; data?: any[]; error?: string }>;
-    removeVolume: (name: string) => Promise<{ success: boolean; error?: string }>;
+    removeVolume: (name: string, force?: boolean) => Promise<{ success: boolean; error?: string }>;
     listNetworks: () => Promise<{ success: boolean; data?: any[]; error?: string }>;
     getBatchStats: (ids: string[]) => Promise<{ success: boolean; data?: { id: string; success: boolean; data?: any; error?: string }[]; error?: string }>;
     openExternal: (url: string) => Promise<{ success: boolean; error?: string }>;
@@ -39,7 +41,9 @@ export interface AppApi {
     getDockerStatus: () => Promise<{ success: boolean; data?: DockerStatusResponse; error?: string }>;
     startDocker: () => Promise<{ success: boolean; error?: string }>;
     waitForDocker: (timeoutSecs: number) => Promise<{ success: boolean; error?: string }>;
-    getInstallInstructions: () => Promise<{ success: boolean; data?: string; error?: string }>;
+    cancelWaitForDocker: () => Promise<{ success: boolean; error?: string }>;
+    getInstallInstructions: () => Promise<{ success: boolean; data?: { distro?: string; instructions: string }; error?: string }>;
+    reconnectDocker: () => Promise<{ success: boolean; data?: string; error?: string }>;
 }
 
 export const api: AppApi = {
@@ -66,7 +70,7 @@ export const api: AppApi = {
     listImages: async () => invoke("list_images"),
     listVolumes: async () => invoke("list_volumes"),
     listNetworks: async () => invoke("list_networks"),
-    startLogs: (id: string, onData: (data: string) => void, options?: { timestamps?: boolean }) => {
+    startLogs: (id: string, onData: (data: string) => void, options?: { timestamps?: boolean; tail?: string }) => {
         const sessionId = Math.random().toString(36).substring(7);
         const eventName = `logs-${sessionId}`;
 
@@ -123,6 +127,11 @@ export const api: AppApi = {
                     invoke("exec_resize", { sessionId, cols, rows });
                 }
             },
+            restart: () => {
+                if (active) {
+                    invoke("restart_exec", { sessionId });
+                }
+            },
             dispose: () => {
                 active = false;
                 if (unlisten) unlisten();
@@ -178,12 +187,14 @@ export const api: AppApi = {
             invoke("stop_pull", { sessionId });
         };
     },
-    removeVolume: async (name: string) => invoke("remove_volume", { name }),
+    removeVolume: async (name: string, force?: boolean) => invoke("remove_volume", { name, force }),
     // Docker lifecycle methods
     checkColimaInstalled: async () => invoke("check_colima_installed"),
     checkDockerRunning: async () => invoke("check_docker_running"),
     getDockerStatus: async () => invoke("get_docker_status"),
     startDocker: async () => invoke("start_docker"),
     waitForDocker: async (timeoutSecs: number) => invoke("wait_for_docker", { timeoutSecs }),
+    cancelWaitForDocker: async () => invoke("cancel_wait_for_docker"),
     getInstallInstructions: async () => invoke("get_install_instructions"),
+    reconnectDocker: async () => invoke("reconnect_docker"),
 };
@@ -2,6 +2,7 @@ export interface ApiResult<T> {
     success: boolean;
     data?: T;
     error?: string;
+    error_code?: string;
 }
 
 export interface Container {
@@ -54,6 +55,7 @@ export interface Volume {
         Size: number;
         RefCount: number;
     };
+    size_human?: string;
 }
 
 export interface Network {